@@ -0,0 +1,60 @@
+-- Initial schema for MariaDB/MySQL.
+-- Tables are only created when missing, so databases set up by hand are adopted as-is.
+
+CREATE TABLE IF NOT EXISTS Project (
+    ProjectID INT NOT NULL AUTO_INCREMENT,
+    Title VARCHAR(255) NOT NULL,
+    Description TEXT NOT NULL,
+    PRIMARY KEY (ProjectID)
+);
+
+CREATE TABLE IF NOT EXISTS Sprint (
+    SprintID INT NOT NULL AUTO_INCREMENT,
+    Title VARCHAR(255) NOT NULL,
+    startDate DATE NOT NULL,
+    endDate DATE NOT NULL,
+    PRIMARY KEY (SprintID)
+);
+
+CREATE TABLE IF NOT EXISTS Task (
+    TaskID INT NOT NULL AUTO_INCREMENT,
+    Title VARCHAR(255) NOT NULL,
+    Status VARCHAR(32) NOT NULL,
+    Description TEXT NOT NULL,
+    commitedHours INT NOT NULL DEFAULT 0,
+    estimatedHours INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (TaskID)
+);
+
+CREATE TABLE IF NOT EXISTS Member (
+    MemberID INT NOT NULL AUTO_INCREMENT,
+    firstName VARCHAR(255) NOT NULL,
+    lastName VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    phone VARCHAR(32) NOT NULL,
+    PRIMARY KEY (MemberID)
+);
+
+CREATE TABLE IF NOT EXISTS ProjectSprint (
+    ProjectID INT NOT NULL,
+    SprintID INT NOT NULL,
+    PRIMARY KEY (ProjectID, SprintID),
+    FOREIGN KEY (ProjectID) REFERENCES Project (ProjectID),
+    FOREIGN KEY (SprintID) REFERENCES Sprint (SprintID)
+);
+
+CREATE TABLE IF NOT EXISTS PartOf (
+    TaskID INT NOT NULL,
+    SprintID INT NOT NULL,
+    PRIMARY KEY (TaskID, SprintID),
+    FOREIGN KEY (TaskID) REFERENCES Task (TaskID),
+    FOREIGN KEY (SprintID) REFERENCES Sprint (SprintID)
+);
+
+CREATE TABLE IF NOT EXISTS ContributesTo (
+    MemberID INT NOT NULL,
+    ProjectID INT NOT NULL,
+    PRIMARY KEY (MemberID, ProjectID),
+    FOREIGN KEY (MemberID) REFERENCES Member (MemberID),
+    FOREIGN KEY (ProjectID) REFERENCES Project (ProjectID)
+);
@@ -0,0 +1,9 @@
+-- Tasks can be assigned to any number of members of their project.
+
+CREATE TABLE AssignedTo (
+    MemberID INT NOT NULL,
+    TaskID INT NOT NULL,
+    PRIMARY KEY (MemberID, TaskID),
+    FOREIGN KEY (MemberID) REFERENCES Member (MemberID),
+    FOREIGN KEY (TaskID) REFERENCES Task (TaskID)
+);
@@ -0,0 +1,13 @@
+-- Hours worked on a task, logged by members. Task.commitedHours is kept as the sum of a task's entries.
+
+CREATE TABLE WorkLog (
+    EntryID INT NOT NULL AUTO_INCREMENT,
+    TaskID INT NOT NULL,
+    MemberID INT NOT NULL,
+    workDate DATE NOT NULL,
+    hours INT NOT NULL,
+    note TEXT NOT NULL,
+    PRIMARY KEY (EntryID),
+    FOREIGN KEY (TaskID) REFERENCES Task (TaskID),
+    FOREIGN KEY (MemberID) REFERENCES Member (MemberID)
+);
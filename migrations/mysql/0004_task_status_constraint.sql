@@ -0,0 +1,9 @@
+-- Task status is one of a fixed set of values. Statuses typed in by hand are normalised first,
+-- anything unrecognisable falls back to NotStarted.
+
+UPDATE Task SET Status = 'NotStarted' WHERE LOWER(REPLACE(REPLACE(Status, ' ', ''), '_', '')) = 'notstarted';
+UPDATE Task SET Status = 'InProgress' WHERE LOWER(REPLACE(REPLACE(Status, ' ', ''), '_', '')) = 'inprogress';
+UPDATE Task SET Status = 'Completed' WHERE LOWER(REPLACE(REPLACE(Status, ' ', ''), '_', '')) = 'completed';
+UPDATE Task SET Status = 'NotStarted' WHERE Status NOT IN ('NotStarted', 'InProgress', 'Completed');
+
+ALTER TABLE Task ADD CONSTRAINT TaskStatusCheck CHECK (Status IN ('NotStarted', 'InProgress', 'Completed'));
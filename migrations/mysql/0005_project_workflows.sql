@@ -0,0 +1,32 @@
+-- Every project defines its own ordered task statuses and the transitions allowed between them.
+-- Existing projects get the statuses TATs used so far, with every transition allowed.
+-- Which statuses are valid now depends on the project, so the fixed status constraint goes away.
+-- The workflow tables are utf8mb4 so statuses can use any emoji.
+
+ALTER TABLE Task DROP CONSTRAINT TaskStatusCheck;
+
+CREATE TABLE ProjectStatus (
+    ProjectID INT NOT NULL,
+    Name VARCHAR(32) NOT NULL,
+    Emoji VARCHAR(16) NOT NULL,
+    Position INT NOT NULL,
+    PRIMARY KEY (ProjectID, Name),
+    FOREIGN KEY (ProjectID) REFERENCES Project (ProjectID)
+) DEFAULT CHARSET = utf8mb4;
+
+CREATE TABLE StatusTransition (
+    ProjectID INT NOT NULL,
+    FromStatus VARCHAR(32) NOT NULL,
+    ToStatus VARCHAR(32) NOT NULL,
+    PRIMARY KEY (ProjectID, FromStatus, ToStatus),
+    FOREIGN KEY (ProjectID, FromStatus) REFERENCES ProjectStatus (ProjectID, Name),
+    FOREIGN KEY (ProjectID, ToStatus) REFERENCES ProjectStatus (ProjectID, Name)
+) DEFAULT CHARSET = utf8mb4;
+
+INSERT INTO ProjectStatus (ProjectID, Name, Emoji, Position) SELECT ProjectID, 'NotStarted', '⏳', 0 FROM Project;
+INSERT INTO ProjectStatus (ProjectID, Name, Emoji, Position) SELECT ProjectID, 'InProgress', '🚧', 1 FROM Project;
+INSERT INTO ProjectStatus (ProjectID, Name, Emoji, Position) SELECT ProjectID, 'Completed', '✅', 2 FROM Project;
+
+INSERT INTO StatusTransition (ProjectID, FromStatus, ToStatus)
+SELECT f.ProjectID, f.Name, t.Name FROM ProjectStatus f
+INNER JOIN ProjectStatus t ON f.ProjectID = t.ProjectID AND f.Name <> t.Name;
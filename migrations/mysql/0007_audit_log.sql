@@ -0,0 +1,16 @@
+-- Field-level history of every change made through TATs. Entries outlive the records they describe,
+-- so neither EntityID nor MemberID reference their tables.
+
+CREATE TABLE AuditLog (
+    EntryID INT NOT NULL AUTO_INCREMENT,
+    Entity VARCHAR(16) NOT NULL,
+    EntityID INT NOT NULL,
+    Action VARCHAR(16) NOT NULL,
+    Field VARCHAR(32) NULL,
+    OldValue TEXT NULL,
+    NewValue TEXT NULL,
+    changedAt DATETIME NOT NULL,
+    MemberID INT NULL,
+    PRIMARY KEY (EntryID),
+    INDEX AuditLogEntity (Entity, EntityID)
+) DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,10 @@
+-- A task can block other tasks of its project, which cannot be worked on until it is done.
+-- Links may span sprints, but never form a cycle.
+
+CREATE TABLE TaskDependency (
+    BlockerID INT NOT NULL,
+    BlockedID INT NOT NULL,
+    PRIMARY KEY (BlockerID, BlockedID),
+    FOREIGN KEY (BlockerID) REFERENCES Task (TaskID),
+    FOREIGN KEY (BlockedID) REFERENCES Task (TaskID)
+);
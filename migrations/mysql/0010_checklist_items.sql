@@ -0,0 +1,13 @@
+-- Tasks can hold a checklist of smaller steps, each of which is either done or not.
+-- Items go to the trash and carry a Version like the records above them.
+
+CREATE TABLE ChecklistItem (
+    ItemID INT NOT NULL AUTO_INCREMENT,
+    TaskID INT NOT NULL,
+    Title VARCHAR(255) NOT NULL,
+    Done BOOLEAN NOT NULL DEFAULT FALSE,
+    DeletedAt DATETIME NULL,
+    Version INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (ItemID),
+    FOREIGN KEY (TaskID) REFERENCES Task (TaskID)
+);
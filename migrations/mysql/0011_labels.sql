@@ -0,0 +1,20 @@
+-- Projects define their own labels, such as bug or tech-debt, each shown in a color.
+-- Tasks can carry any number of the labels of their project.
+
+CREATE TABLE Label (
+    LabelID INT NOT NULL AUTO_INCREMENT,
+    ProjectID INT NOT NULL,
+    Name VARCHAR(32) NOT NULL,
+    Color VARCHAR(32) NOT NULL,
+    PRIMARY KEY (LabelID),
+    UNIQUE (ProjectID, Name),
+    FOREIGN KEY (ProjectID) REFERENCES Project (ProjectID)
+);
+
+CREATE TABLE TaskLabel (
+    TaskID INT NOT NULL,
+    LabelID INT NOT NULL,
+    PRIMARY KEY (TaskID, LabelID),
+    FOREIGN KEY (TaskID) REFERENCES Task (TaskID),
+    FOREIGN KEY (LabelID) REFERENCES Label (LabelID)
+);
@@ -0,0 +1,13 @@
+-- Members discuss tasks in comments. editedAt stays NULL until the author changes the body.
+
+CREATE TABLE TaskComment (
+    CommentID INT NOT NULL AUTO_INCREMENT,
+    TaskID INT NOT NULL,
+    MemberID INT NOT NULL,
+    Body TEXT NOT NULL,
+    postedAt DATETIME NOT NULL,
+    editedAt DATETIME NULL,
+    PRIMARY KEY (CommentID),
+    FOREIGN KEY (TaskID) REFERENCES Task (TaskID),
+    FOREIGN KEY (MemberID) REFERENCES Member (MemberID)
+) DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,36 @@
+-- A task's status has to be one of the statuses its project defines. The project of a task is only known
+-- once the task is linked to a sprint, so the status is checked when it changes and when the task is
+-- linked to a sprint or moved to another one. Tasks already in an undefined status are left to 'tats doctor'.
+
+CREATE TRIGGER TaskStatusDefinedCheck BEFORE UPDATE ON Task
+FOR EACH ROW
+IF NEW.Status <> OLD.Status AND NOT EXISTS (
+    SELECT 1 FROM PartOf
+    INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+    INNER JOIN ProjectStatus ON ProjectSprint.ProjectID = ProjectStatus.ProjectID
+    WHERE PartOf.TaskID = NEW.TaskID AND ProjectStatus.Name = NEW.Status
+) THEN
+    SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = 'task status is not defined by its project';
+END IF;
+
+CREATE TRIGGER PartOfStatusInsertCheck BEFORE INSERT ON PartOf
+FOR EACH ROW
+IF NOT EXISTS (
+    SELECT 1 FROM Task
+    INNER JOIN ProjectSprint ON ProjectSprint.SprintID = NEW.SprintID
+    INNER JOIN ProjectStatus ON ProjectSprint.ProjectID = ProjectStatus.ProjectID
+    WHERE Task.TaskID = NEW.TaskID AND ProjectStatus.Name = Task.Status
+) THEN
+    SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = 'task status is not defined by its project';
+END IF;
+
+CREATE TRIGGER PartOfStatusUpdateCheck BEFORE UPDATE ON PartOf
+FOR EACH ROW
+IF NOT EXISTS (
+    SELECT 1 FROM Task
+    INNER JOIN ProjectSprint ON ProjectSprint.SprintID = NEW.SprintID
+    INNER JOIN ProjectStatus ON ProjectSprint.ProjectID = ProjectStatus.ProjectID
+    WHERE Task.TaskID = NEW.TaskID AND ProjectStatus.Name = Task.Status
+) THEN
+    SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = 'task status is not defined by its project';
+END IF;
@@ -0,0 +1,50 @@
+-- Initial schema for PostgreSQL. Mirrors the tables of the MariaDB database.
+-- Names are quoted so they keep their case, the queries of TATs have their mixed case names quoted the same way.
+
+CREATE TABLE IF NOT EXISTS "Project" (
+    "ProjectID" SERIAL PRIMARY KEY,
+    "Title" VARCHAR(255) NOT NULL,
+    "Description" TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS "Sprint" (
+    "SprintID" SERIAL PRIMARY KEY,
+    "Title" VARCHAR(255) NOT NULL,
+    "startDate" DATE NOT NULL,
+    "endDate" DATE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS "Task" (
+    "TaskID" SERIAL PRIMARY KEY,
+    "Title" VARCHAR(255) NOT NULL,
+    "Status" VARCHAR(32) NOT NULL,
+    "Description" TEXT NOT NULL,
+    "commitedHours" INT NOT NULL DEFAULT 0,
+    "estimatedHours" INT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS "Member" (
+    "MemberID" SERIAL PRIMARY KEY,
+    "firstName" VARCHAR(255) NOT NULL,
+    "lastName" VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    phone VARCHAR(32) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS "ProjectSprint" (
+    "ProjectID" INT NOT NULL REFERENCES "Project" ("ProjectID"),
+    "SprintID" INT NOT NULL REFERENCES "Sprint" ("SprintID"),
+    PRIMARY KEY ("ProjectID", "SprintID")
+);
+
+CREATE TABLE IF NOT EXISTS "PartOf" (
+    "TaskID" INT NOT NULL REFERENCES "Task" ("TaskID"),
+    "SprintID" INT NOT NULL REFERENCES "Sprint" ("SprintID"),
+    PRIMARY KEY ("TaskID", "SprintID")
+);
+
+CREATE TABLE IF NOT EXISTS "ContributesTo" (
+    "MemberID" INT NOT NULL REFERENCES "Member" ("MemberID"),
+    "ProjectID" INT NOT NULL REFERENCES "Project" ("ProjectID"),
+    PRIMARY KEY ("MemberID", "ProjectID")
+);
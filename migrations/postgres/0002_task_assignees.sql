@@ -0,0 +1,7 @@
+-- Tasks can be assigned to any number of members of their project.
+
+CREATE TABLE "AssignedTo" (
+    "MemberID" INT NOT NULL REFERENCES "Member" ("MemberID"),
+    "TaskID" INT NOT NULL REFERENCES "Task" ("TaskID"),
+    PRIMARY KEY ("MemberID", "TaskID")
+);
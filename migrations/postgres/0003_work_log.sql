@@ -0,0 +1,10 @@
+-- Hours worked on a task, logged by members. Task.commitedHours is kept as the sum of a task's entries.
+
+CREATE TABLE "WorkLog" (
+    "EntryID" SERIAL PRIMARY KEY,
+    "TaskID" INT NOT NULL REFERENCES "Task" ("TaskID"),
+    "MemberID" INT NOT NULL REFERENCES "Member" ("MemberID"),
+    "workDate" DATE NOT NULL,
+    hours INT NOT NULL,
+    note TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Deleting a project, sprint, task or member moves it to the trash: the row is kept, stamped with DeletedAt,
+-- and hidden until it is restored or purged for good.
+
+ALTER TABLE "Project" ADD COLUMN "DeletedAt" TIMESTAMP NULL;
+ALTER TABLE "Sprint" ADD COLUMN "DeletedAt" TIMESTAMP NULL;
+ALTER TABLE "Task" ADD COLUMN "DeletedAt" TIMESTAMP NULL;
+ALTER TABLE "Member" ADD COLUMN "DeletedAt" TIMESTAMP NULL;
@@ -0,0 +1,7 @@
+-- Every update of a project, sprint, task or member bumps its Version. Updates only go through when the
+-- record is still at the version the editor started from, so concurrent editors cannot overwrite each other unseen.
+
+ALTER TABLE "Project" ADD COLUMN "Version" INT NOT NULL DEFAULT 0;
+ALTER TABLE "Sprint" ADD COLUMN "Version" INT NOT NULL DEFAULT 0;
+ALTER TABLE "Task" ADD COLUMN "Version" INT NOT NULL DEFAULT 0;
+ALTER TABLE "Member" ADD COLUMN "Version" INT NOT NULL DEFAULT 0;
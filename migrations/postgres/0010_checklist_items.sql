@@ -0,0 +1,11 @@
+-- Tasks can hold a checklist of smaller steps, each of which is either done or not.
+-- Items go to the trash and carry a Version like the records above them.
+
+CREATE TABLE "ChecklistItem" (
+    "ItemID" SERIAL PRIMARY KEY,
+    "TaskID" INT NOT NULL REFERENCES "Task" ("TaskID"),
+    "Title" VARCHAR(255) NOT NULL,
+    "Done" BOOLEAN NOT NULL DEFAULT FALSE,
+    "DeletedAt" TIMESTAMP NULL,
+    "Version" INT NOT NULL DEFAULT 0
+);
@@ -0,0 +1,16 @@
+-- Projects define their own labels, such as bug or tech-debt, each shown in a color.
+-- Tasks can carry any number of the labels of their project.
+
+CREATE TABLE "Label" (
+    "LabelID" SERIAL PRIMARY KEY,
+    "ProjectID" INT NOT NULL REFERENCES "Project" ("ProjectID"),
+    "Name" VARCHAR(32) NOT NULL,
+    "Color" VARCHAR(32) NOT NULL,
+    UNIQUE ("ProjectID", "Name")
+);
+
+CREATE TABLE "TaskLabel" (
+    "TaskID" INT NOT NULL REFERENCES "Task" ("TaskID"),
+    "LabelID" INT NOT NULL REFERENCES "Label" ("LabelID"),
+    PRIMARY KEY ("TaskID", "LabelID")
+);
@@ -0,0 +1,3 @@
+-- Tasks have a priority from 0 (P0, the most urgent) to 3 (P3). Existing and new tasks start at P2.
+
+ALTER TABLE "Task" ADD COLUMN "Priority" INT NOT NULL DEFAULT 2 CHECK ("Priority" BETWEEN 0 AND 3);
@@ -0,0 +1,10 @@
+-- Members discuss tasks in comments. editedAt stays NULL until the author changes the body.
+
+CREATE TABLE "TaskComment" (
+    "CommentID" SERIAL PRIMARY KEY,
+    "TaskID" INT NOT NULL REFERENCES "Task" ("TaskID"),
+    "MemberID" INT NOT NULL REFERENCES "Member" ("MemberID"),
+    "Body" TEXT NOT NULL,
+    "postedAt" TIMESTAMP NOT NULL,
+    "editedAt" TIMESTAMP NULL
+);
@@ -0,0 +1,3 @@
+-- Tasks can be due on a day within their sprint. Tasks without a due date keep it NULL.
+
+ALTER TABLE "Task" ADD COLUMN "dueDate" DATE NULL;
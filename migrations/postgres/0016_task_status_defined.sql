@@ -0,0 +1,25 @@
+-- A task's status has to be one of the statuses its project defines. The project of a task is only known
+-- once the task is linked to a sprint, so the status is checked when it changes and when the task is
+-- linked to a sprint or moved to another one. Tasks already in an undefined status are left to 'tats doctor'.
+
+CREATE FUNCTION "CheckTaskStatusDefined"() RETURNS TRIGGER AS $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM "Task"
+        INNER JOIN "PartOf" ON "Task"."TaskID" = "PartOf"."TaskID"
+        INNER JOIN "ProjectSprint" ON "PartOf"."SprintID" = "ProjectSprint"."SprintID"
+        INNER JOIN "ProjectStatus" ON "ProjectSprint"."ProjectID" = "ProjectStatus"."ProjectID"
+        WHERE "Task"."TaskID" = NEW."TaskID" AND "ProjectStatus"."Name" = "Task"."Status"
+    ) THEN
+        RAISE EXCEPTION 'task status is not defined by its project';
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER "TaskStatusDefinedCheck" AFTER UPDATE OF "Status" ON "Task"
+FOR EACH ROW WHEN (NEW."Status" IS DISTINCT FROM OLD."Status")
+EXECUTE FUNCTION "CheckTaskStatusDefined"();
+
+CREATE TRIGGER "PartOfStatusCheck" AFTER INSERT OR UPDATE OF "SprintID" ON "PartOf"
+FOR EACH ROW EXECUTE FUNCTION "CheckTaskStatusDefined"();
@@ -0,0 +1,49 @@
+-- Initial schema for the embedded SQLite store. Mirrors the tables of the MariaDB database.
+
+CREATE TABLE IF NOT EXISTS Project (
+    ProjectID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Title TEXT NOT NULL,
+    Description TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS Sprint (
+    SprintID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Title TEXT NOT NULL,
+    startDate DATE NOT NULL,
+    endDate DATE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS Task (
+    TaskID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Title TEXT NOT NULL,
+    Status TEXT NOT NULL,
+    Description TEXT NOT NULL,
+    commitedHours INTEGER NOT NULL DEFAULT 0,
+    estimatedHours INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS Member (
+    MemberID INTEGER PRIMARY KEY AUTOINCREMENT,
+    firstName TEXT NOT NULL,
+    lastName TEXT NOT NULL,
+    email TEXT NOT NULL,
+    phone TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ProjectSprint (
+    ProjectID INTEGER NOT NULL REFERENCES Project (ProjectID),
+    SprintID INTEGER NOT NULL REFERENCES Sprint (SprintID),
+    PRIMARY KEY (ProjectID, SprintID)
+);
+
+CREATE TABLE IF NOT EXISTS PartOf (
+    TaskID INTEGER NOT NULL REFERENCES Task (TaskID),
+    SprintID INTEGER NOT NULL REFERENCES Sprint (SprintID),
+    PRIMARY KEY (TaskID, SprintID)
+);
+
+CREATE TABLE IF NOT EXISTS ContributesTo (
+    MemberID INTEGER NOT NULL REFERENCES Member (MemberID),
+    ProjectID INTEGER NOT NULL REFERENCES Project (ProjectID),
+    PRIMARY KEY (MemberID, ProjectID)
+);
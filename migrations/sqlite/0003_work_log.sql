@@ -0,0 +1,10 @@
+-- Hours worked on a task, logged by members. Task.commitedHours is kept as the sum of a task's entries.
+
+CREATE TABLE WorkLog (
+    EntryID INTEGER PRIMARY KEY AUTOINCREMENT,
+    TaskID INTEGER NOT NULL REFERENCES Task (TaskID),
+    MemberID INTEGER NOT NULL REFERENCES Member (MemberID),
+    workDate DATE NOT NULL,
+    hours INTEGER NOT NULL,
+    note TEXT NOT NULL
+);
@@ -0,0 +1,20 @@
+-- Task status is one of a fixed set of values. Statuses typed in by hand are normalised first,
+-- anything unrecognisable falls back to NotStarted.
+-- SQLite cannot add a CHECK constraint to an existing table, so the constraint is enforced by triggers.
+
+UPDATE Task SET Status = 'NotStarted' WHERE LOWER(REPLACE(REPLACE(Status, ' ', ''), '_', '')) = 'notstarted';
+UPDATE Task SET Status = 'InProgress' WHERE LOWER(REPLACE(REPLACE(Status, ' ', ''), '_', '')) = 'inprogress';
+UPDATE Task SET Status = 'Completed' WHERE LOWER(REPLACE(REPLACE(Status, ' ', ''), '_', '')) = 'completed';
+UPDATE Task SET Status = 'NotStarted' WHERE Status NOT IN ('NotStarted', 'InProgress', 'Completed');
+
+CREATE TRIGGER TaskStatusInsertCheck BEFORE INSERT ON Task
+WHEN NEW.Status NOT IN ('NotStarted', 'InProgress', 'Completed')
+BEGIN
+    SELECT RAISE(ABORT, 'invalid task status');
+END;
+
+CREATE TRIGGER TaskStatusUpdateCheck BEFORE UPDATE OF Status ON Task
+WHEN NEW.Status NOT IN ('NotStarted', 'InProgress', 'Completed')
+BEGIN
+    SELECT RAISE(ABORT, 'invalid task status');
+END;
@@ -0,0 +1,16 @@
+-- Field-level history of every change made through TATs. Entries outlive the records they describe,
+-- so neither EntityID nor MemberID reference their tables.
+
+CREATE TABLE AuditLog (
+    EntryID INTEGER PRIMARY KEY AUTOINCREMENT,
+    Entity TEXT NOT NULL,
+    EntityID INTEGER NOT NULL,
+    Action TEXT NOT NULL,
+    Field TEXT NULL,
+    OldValue TEXT NULL,
+    NewValue TEXT NULL,
+    changedAt DATETIME NOT NULL,
+    MemberID INTEGER NULL
+);
+
+CREATE INDEX AuditLogEntity ON AuditLog (Entity, EntityID);
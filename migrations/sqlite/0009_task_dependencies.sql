@@ -0,0 +1,8 @@
+-- A task can block other tasks of its project, which cannot be worked on until it is done.
+-- Links may span sprints, but never form a cycle.
+
+CREATE TABLE TaskDependency (
+    BlockerID INTEGER NOT NULL REFERENCES Task (TaskID),
+    BlockedID INTEGER NOT NULL REFERENCES Task (TaskID),
+    PRIMARY KEY (BlockerID, BlockedID)
+);
@@ -0,0 +1,11 @@
+-- Tasks can hold a checklist of smaller steps, each of which is either done or not.
+-- Items go to the trash and carry a Version like the records above them.
+
+CREATE TABLE ChecklistItem (
+    ItemID INTEGER PRIMARY KEY AUTOINCREMENT,
+    TaskID INTEGER NOT NULL REFERENCES Task (TaskID),
+    Title TEXT NOT NULL,
+    Done BOOLEAN NOT NULL DEFAULT 0,
+    DeletedAt DATETIME NULL,
+    Version INT NOT NULL DEFAULT 0
+);
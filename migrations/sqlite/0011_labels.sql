@@ -0,0 +1,16 @@
+-- Projects define their own labels, such as bug or tech-debt, each shown in a color.
+-- Tasks can carry any number of the labels of their project.
+
+CREATE TABLE Label (
+    LabelID INTEGER PRIMARY KEY AUTOINCREMENT,
+    ProjectID INTEGER NOT NULL REFERENCES Project (ProjectID),
+    Name TEXT NOT NULL,
+    Color TEXT NOT NULL,
+    UNIQUE (ProjectID, Name)
+);
+
+CREATE TABLE TaskLabel (
+    TaskID INTEGER NOT NULL REFERENCES Task (TaskID),
+    LabelID INTEGER NOT NULL REFERENCES Label (LabelID),
+    PRIMARY KEY (TaskID, LabelID)
+);
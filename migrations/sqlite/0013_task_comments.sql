@@ -0,0 +1,10 @@
+-- Members discuss tasks in comments. editedAt stays NULL until the author changes the body.
+
+CREATE TABLE TaskComment (
+    CommentID INTEGER PRIMARY KEY AUTOINCREMENT,
+    TaskID INTEGER NOT NULL REFERENCES Task (TaskID),
+    MemberID INTEGER NOT NULL REFERENCES Member (MemberID),
+    Body TEXT NOT NULL,
+    postedAt DATETIME NOT NULL,
+    editedAt DATETIME NULL
+);
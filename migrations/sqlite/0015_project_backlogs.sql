@@ -0,0 +1,13 @@
+-- Every project has a backlog holding the tasks not scheduled into a sprint yet. The backlog is a sprint
+-- marked with the project it belongs to, so its tasks work like any other. Its dates are never shown.
+-- Existing projects get an empty backlog.
+
+ALTER TABLE Sprint ADD COLUMN BacklogOf INTEGER NULL REFERENCES Project (ProjectID);
+
+CREATE UNIQUE INDEX SprintBacklogOf ON Sprint (BacklogOf);
+
+INSERT INTO Sprint (Title, startDate, endDate, BacklogOf)
+SELECT 'Backlog', CURRENT_DATE, CURRENT_DATE, ProjectID FROM Project;
+
+INSERT INTO ProjectSprint (ProjectID, SprintID)
+SELECT BacklogOf, SprintID FROM Sprint WHERE BacklogOf IS NOT NULL;
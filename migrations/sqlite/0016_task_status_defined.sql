@@ -0,0 +1,36 @@
+-- A task's status has to be one of the statuses its project defines. The project of a task is only known
+-- once the task is linked to a sprint, so the status is checked when it changes and when the task is
+-- linked to a sprint or moved to another one. Tasks already in an undefined status are left to 'tats doctor'.
+
+CREATE TRIGGER TaskStatusDefinedCheck BEFORE UPDATE OF Status ON Task
+WHEN NEW.Status IS NOT OLD.Status AND NOT EXISTS (
+    SELECT 1 FROM PartOf
+    INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+    INNER JOIN ProjectStatus ON ProjectSprint.ProjectID = ProjectStatus.ProjectID
+    WHERE PartOf.TaskID = NEW.TaskID AND ProjectStatus.Name = NEW.Status
+)
+BEGIN
+    SELECT RAISE(ABORT, 'task status is not defined by its project');
+END;
+
+CREATE TRIGGER PartOfStatusInsertCheck BEFORE INSERT ON PartOf
+WHEN NOT EXISTS (
+    SELECT 1 FROM Task
+    INNER JOIN ProjectSprint ON ProjectSprint.SprintID = NEW.SprintID
+    INNER JOIN ProjectStatus ON ProjectSprint.ProjectID = ProjectStatus.ProjectID
+    WHERE Task.TaskID = NEW.TaskID AND ProjectStatus.Name = Task.Status
+)
+BEGIN
+    SELECT RAISE(ABORT, 'task status is not defined by its project');
+END;
+
+CREATE TRIGGER PartOfStatusUpdateCheck BEFORE UPDATE OF SprintID ON PartOf
+WHEN NOT EXISTS (
+    SELECT 1 FROM Task
+    INNER JOIN ProjectSprint ON ProjectSprint.SprintID = NEW.SprintID
+    INNER JOIN ProjectStatus ON ProjectSprint.ProjectID = ProjectStatus.ProjectID
+    WHERE Task.TaskID = NEW.TaskID AND ProjectStatus.Name = Task.Status
+)
+BEGIN
+    SELECT RAISE(ABORT, 'task status is not defined by its project');
+END;
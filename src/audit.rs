@@ -0,0 +1,195 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use chrono::NaiveDate;
+
+use crate::models::{Label, RecordKind, Workflow};
+
+/// The member changes are recorded against; 0 when nobody was picked.
+static ACTING_MEMBER: AtomicI32 = AtomicI32::new(0);
+
+/// Sets the member every following change is recorded against, or nobody.
+pub fn set_acting_member(member_id: Option<i32>) {
+    ACTING_MEMBER.store(member_id.unwrap_or(0), Ordering::Relaxed);
+}
+
+pub fn acting_member() -> Option<i32> {
+    match ACTING_MEMBER.load(Ordering::Relaxed) {
+        0 => None,
+        member_id => Some(member_id),
+    }
+}
+
+/// What happened to a record. Stored as its name in the `Action` column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    Trash,
+    Restore,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::Trash => "trash",
+            Action::Restore => "restore",
+        }
+    }
+}
+
+/// The audited fields of a record, by column name, rendered as text.
+pub type Fields = Vec<(&'static str, String)>;
+
+/// A change waiting to be written to the audit log.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub kind: RecordKind,
+    pub id: i32,
+    pub action: Action,
+    pub field: Option<&'static str>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl Change {
+    /// A change to a record as a whole.
+    pub fn record(kind: RecordKind, id: i32, action: Action) -> Change {
+        Change {
+            kind,
+            id,
+            action,
+            field: None,
+            old_value: None,
+            new_value: None,
+        }
+    }
+
+    /// One change per field of a newly created record.
+    pub fn created(kind: RecordKind, id: i32, after: Fields) -> Vec<Change> {
+        after
+            .into_iter()
+            .map(|(field, value)| Change {
+                field: Some(field),
+                new_value: Some(value),
+                ..Change::record(kind, id, Action::Create)
+            })
+            .collect()
+    }
+
+    /// One change per field of a deleted record.
+    pub fn deleted(kind: RecordKind, id: i32, before: Fields) -> Vec<Change> {
+        before
+            .into_iter()
+            .map(|(field, value)| Change {
+                field: Some(field),
+                old_value: Some(value),
+                ..Change::record(kind, id, Action::Delete)
+            })
+            .collect()
+    }
+
+    /// One change per field whose value differs between `before` and `after`, which list the same fields.
+    pub fn updated(kind: RecordKind, id: i32, before: Fields, after: Fields) -> Vec<Change> {
+        before
+            .into_iter()
+            .zip(after)
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| Change {
+                field: Some(field),
+                old_value: Some(old),
+                new_value: Some(new),
+                ..Change::record(kind, id, Action::Update)
+            })
+            .collect()
+    }
+}
+
+/// Renders a set of record IDs, such as the members of a project, as one field value.
+pub fn id_list(ids: &[i32]) -> String {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    let ids: Vec<String> = ids.iter().map(|id| format!("#{}", id)).collect();
+    ids.join(", ")
+}
+
+pub fn project_fields(title: &str, description: &str) -> Fields {
+    vec![("Title", title.into()), ("Description", description.into())]
+}
+
+pub fn sprint_fields(title: &str, start_date: NaiveDate, end_date: NaiveDate) -> Fields {
+    vec![
+        ("Title", title.into()),
+        ("startDate", start_date.to_string()),
+        ("endDate", end_date.to_string()),
+    ]
+}
+
+pub fn task_fields(
+    title: &str,
+    status: &str,
+    description: &str,
+    estimated_hours: i32,
+    priority: i32,
+    due_date: Option<NaiveDate>,
+) -> Fields {
+    vec![
+        ("Title", title.into()),
+        ("Status", status.into()),
+        ("Description", description.into()),
+        ("estimatedHours", estimated_hours.to_string()),
+        ("Priority", priority.to_string()),
+        ("dueDate", due_date.map(|d| d.to_string()).unwrap_or_default()),
+    ]
+}
+
+/// The sprint a task is part of and its due date there, which change together when it moves.
+pub fn task_move_fields(sprint_id: i32, due_date: Option<NaiveDate>) -> Fields {
+    vec![
+        ("SprintID", sprint_id.to_string()),
+        ("dueDate", due_date.map(|d| d.to_string()).unwrap_or_default()),
+    ]
+}
+
+pub fn checklist_item_fields(title: &str, done: bool) -> Fields {
+    vec![("Title", title.into()), ("Done", done.to_string())]
+}
+
+pub fn comment_fields(body: &str) -> Fields {
+    vec![("Body", body.into())]
+}
+
+pub fn member_fields(first_name: &str, last_name: &str, email: &str, phone: &str) -> Fields {
+    vec![
+        ("firstName", first_name.into()),
+        ("lastName", last_name.into()),
+        ("email", email.into()),
+        ("phone", phone.into()),
+    ]
+}
+
+/// The labels of a project, with their colors, as one field of the project.
+pub fn label_fields(labels: &[Label]) -> Fields {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|label| format!("{} ({})", label.name, label.color))
+        .collect();
+    vec![("labels", labels.join(", "))]
+}
+
+/// The statuses and transitions of a workflow, as the fields of its project.
+pub fn workflow_fields(workflow: &Workflow) -> Fields {
+    let transitions: Vec<String> = workflow
+        .transitions
+        .iter()
+        .map(|(from, to)| format!("{}→{}", from, to))
+        .collect();
+    vec![
+        ("statuses", workflow.status_names()),
+        ("transitions", transitions.join(", ")),
+    ]
+}
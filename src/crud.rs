@@ -1,167 +1,2606 @@
+use std::collections::HashMap;
+
+use sqlx::{FromRow, Row};
+
+use crate::audit::{self, Action, Change};
+use crate::db::{insert_returning_id, with_pool, Db};
 use crate::models::*;
-use sqlx::{MySql, MySqlPool, Pool};
+use crate::storage::{
+    check_dependency, check_due_date, check_due_date_in, check_move, check_not_backlog,
+    check_priority, check_transition, check_workflow, StorageError,
+};
+use crate::undo::Operation;
 
-/// Given a SQLPool and ID, this will delete the project and all associated sprints.
-pub async fn delete_project_by_id(pool: &Pool<MySql>, project_id: i32) -> Result<(), sqlx::Error> {
-    let mut transaction = pool.begin().await?;
+/// Deletes a task (bound as the only parameter) along with every row referencing it.
+/// Ordered so that the foreign keys hold after every statement.
+const DELETE_TASK_STATEMENTS: [&str; 9] = [
+    "DELETE FROM TaskComment WHERE TaskID = ?",
+    "DELETE FROM TaskLabel WHERE TaskID = ?",
+    "DELETE FROM ChecklistItem WHERE TaskID = ?",
+    "DELETE FROM TaskDependency WHERE BlockerID = ?",
+    "DELETE FROM TaskDependency WHERE BlockedID = ?",
+    "DELETE FROM WorkLog WHERE TaskID = ?",
+    "DELETE FROM AssignedTo WHERE TaskID = ?",
+    "DELETE FROM PartOf WHERE TaskID = ?",
+    "DELETE FROM Task WHERE TaskID = ?",
+];
 
-    // First, delete related entries from MemberProject. (Assuming such a table exists)
-    sqlx::query("DELETE FROM ContributesTo WHERE ProjectID = ?")
-        .bind(project_id)
-        .execute(&mut *transaction)
-        .await?;
+/// Selects the labels of a project (bound as the only parameter) in the order they were defined.
+const LABELS_OF_PROJECT: &str = "SELECT * FROM Label WHERE ProjectID = ? ORDER BY LabelID";
 
-    // Then, delete related entries from ProjectSprint.
-    sqlx::query("DELETE FROM ProjectSprint WHERE ProjectID = ?")
-        .bind(project_id)
-        .execute(&mut *transaction)
-        .await?;
+/// Recomputes commitedHours of a task (bound twice) as the sum of its work log.
+const ROLL_UP_COMMITED_HOURS: &str = "UPDATE Task SET commitedHours =
+    (SELECT COALESCE(SUM(hours), 0) FROM WorkLog WHERE WorkLog.TaskID = ?) WHERE TaskID = ?";
 
-    // Next, delete the sprints that are associated with the project.
-    sqlx::query("DELETE FROM Sprint WHERE SprintID IN (SELECT SprintID FROM ProjectSprint WHERE ProjectID = ?)")
-        .bind(project_id)
-        .execute(&mut *transaction)
+/// Recomputes the commitedHours of `$task_id` inside the already open `$transaction`,
+/// evaluating to the audit log changes it made.
+macro_rules! roll_up_commited_hours {
+    ($transaction:expr, $task_id:expr) => {{
+        let before: i32 = sqlx::query_scalar("SELECT commitedHours FROM Task WHERE TaskID = ?")
+            .bind($task_id)
+            .fetch_one(&mut *$transaction)
+            .await?;
+
+        sqlx::query(ROLL_UP_COMMITED_HOURS)
+            .bind($task_id)
+            .bind($task_id)
+            .execute(&mut *$transaction)
+            .await?;
+
+        let after: i32 = sqlx::query_scalar("SELECT commitedHours FROM Task WHERE TaskID = ?")
+            .bind($task_id)
+            .fetch_one(&mut *$transaction)
+            .await?;
+
+        Change::updated(
+            RecordKind::Task,
+            $task_id,
+            vec![("commitedHours", before.to_string())],
+            vec![("commitedHours", after.to_string())],
+        )
+    }};
+}
+
+/// Writes the statuses and transitions of `$workflow` for a project inside the already open `$transaction`.
+/// A macro rather than a function so it works with the transaction of any backend.
+macro_rules! insert_workflow {
+    ($transaction:expr, $project_id:expr, $workflow:expr) => {
+        for (position, status) in $workflow.statuses.iter().enumerate() {
+            sqlx::query("INSERT INTO ProjectStatus (ProjectID, Name, Emoji, Position) VALUES (?, ?, ?, ?)")
+                .bind($project_id)
+                .bind(&status.name)
+                .bind(&status.emoji)
+                .bind(position as i32)
+                .execute(&mut *$transaction)
+                .await?;
+        }
+
+        for (from, to) in &$workflow.transitions {
+            sqlx::query("INSERT INTO StatusTransition (ProjectID, FromStatus, ToStatus) VALUES (?, ?, ?)")
+                .bind($project_id)
+                .bind(from)
+                .bind(to)
+                .execute(&mut *$transaction)
+                .await?;
+        }
+    };
+}
+
+pub(crate) use insert_workflow;
+
+/// Creates the backlog of a project inside the already open `$transaction` and links it to the project,
+/// evaluating to its ID. The backlog is dated `$date`, though its dates are never shown.
+macro_rules! insert_backlog {
+    ($transaction:expr, $project_id:expr, $date:expr) => {{
+        let sprint_id = $crate::db::insert_returning_id(
+            sqlx::query("INSERT INTO Sprint (Title, startDate, endDate, BacklogOf) VALUES (?, ?, ?, ?)")
+                .bind($crate::models::Sprint::BACKLOG_TITLE)
+                .bind($date)
+                .bind($date)
+                .bind($project_id),
+            &mut *$transaction,
+            "SprintID",
+        )
         .await?;
 
-    // Finally, delete the project itself.
-    sqlx::query("DELETE FROM Project WHERE ProjectID = ?")
+        sqlx::query("INSERT INTO ProjectSprint (ProjectID, SprintID) VALUES (?, ?)")
+            .bind($project_id)
+            .bind(sprint_id)
+            .execute(&mut *$transaction)
+            .await?;
+
+        sprint_id
+    }};
+}
+
+pub(crate) use insert_backlog;
+
+/// Writes `$changes` to the audit log inside the already open `$transaction`,
+/// stamped with the current time and the acting member.
+macro_rules! record_changes {
+    ($transaction:expr, $changes:expr) => {
+        let changed_at = chrono::Local::now().naive_local();
+        for change in $changes {
+            sqlx::query(
+                "INSERT INTO AuditLog (Entity, EntityID, Action, Field, OldValue, NewValue, changedAt, MemberID)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(change.kind.label())
+            .bind(change.id)
+            .bind(change.action.as_str())
+            .bind(change.field)
+            .bind(change.old_value)
+            .bind(change.new_value)
+            .bind(changed_at)
+            .bind(audit::acting_member())
+            .execute(&mut *$transaction)
+            .await?;
+        }
+    };
+}
+
+/// Deletes the project along with its sprints, their tasks, its workflow and every link between them, in one transaction.
+pub async fn delete_project_by_id(db: &Db, project_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        // Collect what belongs to the project up front, the link tables are cleared before the rows they point at.
+        let sprint_ids: Vec<i32> =
+            sqlx::query_scalar("SELECT SprintID FROM ProjectSprint WHERE ProjectID = ?")
+                .bind(project_id)
+                .fetch_all(&mut *transaction)
+                .await?;
+
+        let task_ids: Vec<i32> = sqlx::query_scalar(
+            "SELECT DISTINCT PartOf.TaskID FROM PartOf
+             INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+             WHERE ProjectSprint.ProjectID = ?",
+        )
         .bind(project_id)
-        .execute(&mut *transaction)
+        .fetch_all(&mut *transaction)
         .await?;
 
-    transaction.commit().await?;
+        let (title, description): (String, String) =
+            sqlx::query_as("SELECT Title, Description FROM Project WHERE ProjectID = ?")
+                .bind(project_id)
+                .fetch_one(&mut *transaction)
+                .await?;
+
+        let mut changes = Change::deleted(
+            RecordKind::Project,
+            project_id,
+            audit::project_fields(&title, &description),
+        );
+        changes.extend(sprint_ids.iter().map(|id| Change::record(RecordKind::Sprint, *id, Action::Delete)));
+        changes.extend(task_ids.iter().map(|id| Change::record(RecordKind::Task, *id, Action::Delete)));
+        record_changes!(transaction, changes);
+
+        // Delete the tasks of every sprint, along with every row referencing them.
+        for task_id in task_ids {
+            for statement in DELETE_TASK_STATEMENTS {
+                sqlx::query(statement)
+                    .bind(task_id)
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+        }
+
+        // Then the sprints themselves, along with their links to the project.
+        for sprint_id in sprint_ids {
+            sqlx::query("DELETE FROM ProjectSprint WHERE SprintID = ?")
+                .bind(sprint_id)
+                .execute(&mut *transaction)
+                .await?;
+
+            sqlx::query("DELETE FROM Sprint WHERE SprintID = ?")
+                .bind(sprint_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM ContributesTo WHERE ProjectID = ?")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM StatusTransition WHERE ProjectID = ?")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM ProjectStatus WHERE ProjectID = ?")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM Label WHERE ProjectID = ?")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        // Finally, delete the project itself.
+        sqlx::query("DELETE FROM Project WHERE ProjectID = ?")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
 
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Fetches all projects, their sprints and the tasks associated with each sprint as a single nested collection.
-pub async fn fetch_projects(pool: &Pool<MySql>) -> Result<Vec<Project>, sqlx::Error> {
-    let raw_projects = sqlx::query_as::<_, RawProject>("SELECT * FROM Project")
+/// The whole tree is loaded with one query per table and assembled in memory. Records in the trash are left out.
+/// The backlog of each project comes first among its sprints.
+pub async fn fetch_projects(db: &Db) -> Result<Vec<Project>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let raw_projects = sqlx::query_as::<_, RawProject>("SELECT * FROM Project WHERE DeletedAt IS NULL")
+            .fetch_all(pool)
+            .await?;
+
+        let mut sprints_by_project: HashMap<i32, Vec<RawSprint>> = HashMap::new();
+        for row in sqlx::query(
+            "SELECT ProjectSprint.ProjectID, Sprint.* FROM Sprint
+             INNER JOIN ProjectSprint ON Sprint.SprintID = ProjectSprint.SprintID
+             WHERE Sprint.DeletedAt IS NULL
+             ORDER BY Sprint.BacklogOf IS NULL, Sprint.SprintID",
+        )
         .fetch_all(pool)
-        .await?;
+        .await?
+        {
+            sprints_by_project
+                .entry(row.try_get("ProjectID")?)
+                .or_default()
+                .push(RawSprint::from_row(&row)?);
+        }
 
-    let mut projects = Vec::new();
+        let mut assignees_by_task: HashMap<i32, Vec<Member>> = HashMap::new();
+        for row in sqlx::query(
+            "SELECT AssignedTo.TaskID, Member.* FROM Member
+             INNER JOIN AssignedTo ON Member.MemberID = AssignedTo.MemberID
+             WHERE Member.DeletedAt IS NULL",
+        )
+        .fetch_all(pool)
+        .await?
+        {
+            assignees_by_task
+                .entry(row.try_get("TaskID")?)
+                .or_default()
+                .push(Member::from_row(&row)?);
+        }
 
-    for raw_project in raw_projects {
-        let raw_sprints = sqlx::query_as::<_, RawSprint>(
-            "SELECT Sprint.* FROM Sprint
-             INNER JOIN ProjectSprint ON Sprint.SprintID = ProjectSprint.SprintID
-             WHERE ProjectSprint.ProjectID = ?",
+        let mut blockers_by_task: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (blocker_id, blocked_id) in sqlx::query_as::<_, (i32, i32)>(
+            "SELECT BlockerID, BlockedID FROM TaskDependency",
         )
-        .bind(raw_project.project_id)
         .fetch_all(pool)
-        .await?;
+        .await?
+        {
+            blockers_by_task.entry(blocked_id).or_default().push(blocker_id);
+        }
 
-        let mut sprints = Vec::new();
+        let mut checklists_by_task: HashMap<i32, Vec<ChecklistItem>> = HashMap::new();
+        for item in sqlx::query_as::<_, ChecklistItem>(
+            "SELECT * FROM ChecklistItem WHERE DeletedAt IS NULL ORDER BY ItemID",
+        )
+        .fetch_all(pool)
+        .await?
+        {
+            checklists_by_task.entry(item.task_id).or_default().push(item);
+        }
 
-        for raw_sprint in raw_sprints {
-            let tasks = sqlx::query_as::<_, Task>(
-                "SELECT Task.* FROM Task
-                 INNER JOIN PartOf ON Task.TaskID = PartOf.TaskID
-                 WHERE PartOf.SprintID = ?",
-            )
-            .bind(raw_sprint.sprint_id)
+        let mut labels_by_project: HashMap<i32, Vec<Label>> = HashMap::new();
+        for label in sqlx::query_as::<_, Label>("SELECT * FROM Label ORDER BY LabelID")
             .fetch_all(pool)
-            .await?;
+            .await?
+        {
+            labels_by_project.entry(label.project_id).or_default().push(label);
+        }
 
-            sprints.push(Sprint {
-                sprint_id: raw_sprint.sprint_id,
-                title: raw_sprint.title,
-                start_date: raw_sprint.start_date,
-                end_date: raw_sprint.end_date,
-                tasks,
-            });
+        let mut labels_by_task: HashMap<i32, Vec<Label>> = HashMap::new();
+        for row in sqlx::query(
+            "SELECT TaskLabel.TaskID, Label.* FROM Label
+             INNER JOIN TaskLabel ON Label.LabelID = TaskLabel.LabelID
+             ORDER BY Label.LabelID",
+        )
+        .fetch_all(pool)
+        .await?
+        {
+            labels_by_task
+                .entry(row.try_get("TaskID")?)
+                .or_default()
+                .push(Label::from_row(&row)?);
         }
 
-        sqlx::query_as::<_, Member>(
-            "SELECT Member.* FROM Member
+        let mut tasks_by_sprint: HashMap<i32, Vec<Task>> = HashMap::new();
+        for row in sqlx::query(
+            "SELECT PartOf.SprintID, Task.* FROM Task
+             INNER JOIN PartOf ON Task.TaskID = PartOf.TaskID
+             WHERE Task.DeletedAt IS NULL",
+        )
+        .fetch_all(pool)
+        .await?
+        {
+            let mut task = Task::from_row(&row)?;
+            task.assignees = assignees_by_task
+                .get(&task.task_id)
+                .cloned()
+                .unwrap_or_default();
+            task.blocked_by = blockers_by_task
+                .get(&task.task_id)
+                .cloned()
+                .unwrap_or_default();
+            task.checklist = checklists_by_task
+                .remove(&task.task_id)
+                .unwrap_or_default();
+            task.labels = labels_by_task
+                .remove(&task.task_id)
+                .unwrap_or_default();
+
+            tasks_by_sprint
+                .entry(row.try_get("SprintID")?)
+                .or_default()
+                .push(task);
+        }
+
+        let mut workflows: HashMap<i32, Workflow> = HashMap::new();
+        for row in sqlx::query("SELECT * FROM ProjectStatus ORDER BY ProjectID, Position")
+            .fetch_all(pool)
+            .await?
+        {
+            workflows
+                .entry(row.try_get("ProjectID")?)
+                .or_insert_with(|| Workflow { statuses: vec![], transitions: vec![] })
+                .statuses
+                .push(StatusDefinition::from_row(&row)?);
+        }
+        for (project_id, from, to) in sqlx::query_as::<_, (i32, String, String)>(
+            "SELECT ProjectID, FromStatus, ToStatus FROM StatusTransition",
+        )
+        .fetch_all(pool)
+        .await?
+        {
+            if let Some(workflow) = workflows.get_mut(&project_id) {
+                workflow.transitions.push((from, to));
+            }
+        }
+
+        let mut members_by_project: HashMap<i32, Vec<Member>> = HashMap::new();
+        for row in sqlx::query(
+            "SELECT ContributesTo.ProjectID, Member.* FROM Member
              INNER JOIN ContributesTo ON Member.MemberID = ContributesTo.MemberID
-             WHERE ContributesTo.ProjectID = ?",
+             WHERE Member.DeletedAt IS NULL",
         )
-        .bind(raw_project.project_id)
         .fetch_all(pool)
-        .await?;
+        .await?
+        {
+            members_by_project
+                .entry(row.try_get("ProjectID")?)
+                .or_default()
+                .push(Member::from_row(&row)?);
+        }
 
-        projects.push(Project {
-            proj_id: raw_project.project_id,
-            title: raw_project.title,
-            desc: raw_project.description,
-            sprints,
-        });
-    }
+        let projects = raw_projects
+            .into_iter()
+            .map(|raw_project| {
+                let sprints = sprints_by_project
+                    .remove(&raw_project.project_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|raw_sprint| Sprint {
+                        tasks: tasks_by_sprint
+                            .get(&raw_sprint.sprint_id)
+                            .cloned()
+                            .unwrap_or_default(),
+                        sprint_id: raw_sprint.sprint_id,
+                        title: raw_sprint.title,
+                        start_date: raw_sprint.start_date,
+                        end_date: raw_sprint.end_date,
+                        version: raw_sprint.version,
+                        backlog: raw_sprint.backlog_of.is_some(),
+                    })
+                    .collect();
+
+                Project {
+                    members: members_by_project
+                        .remove(&raw_project.project_id)
+                        .unwrap_or_default(),
+                    workflow: workflows
+                        .remove(&raw_project.project_id)
+                        .unwrap_or_else(|| Workflow { statuses: vec![], transitions: vec![] }),
+                    labels: labels_by_project
+                        .remove(&raw_project.project_id)
+                        .unwrap_or_default(),
+                    proj_id: raw_project.project_id,
+                    title: raw_project.title,
+                    desc: raw_project.description,
+                    version: raw_project.version,
+                    sprints,
+                }
+            })
+            .collect();
 
-    Ok(projects)
+        Ok(projects)
+    })
 }
 
-pub async fn fetch_members_by_project_id(
-    pool: &MySqlPool,
-    project_id: i32,
-) -> Result<Vec<Member>, sqlx::Error> {
-    let members = sqlx::query_as::<_, Member>(
-        r#"
-        SELECT Member.MemberID, Member.firstName, Member.lastName, Member.email, Member.phone
-        FROM Member
-        INNER JOIN ContributesTo ON Member.MemberID = ContributesTo.MemberID
-        WHERE ContributesTo.ProjectID = ?
-        "#,
-    )
-    .bind(project_id)
-    .fetch_all(pool)
-    .await?;
-
-    Ok(members)
-}
-
-/// Delete a sprint with the provided ID. Deletes the relationship between the project and the sprint as well.
-pub async fn delete_sprint_by_id(pool: &Pool<MySql>, sprint_id: i32) -> Result<(), sqlx::Error> {
-    let mut transaction = pool.begin().await?;
-
-    // First, delete related entries from ProjectSprint. (remove linkage between deleted sprint and project.)
-    sqlx::query("DELETE FROM ProjectSprint WHERE SprintID = ?")
-        .bind(sprint_id)
-        .execute(&mut *transaction)
+/// Creates a new project with the default workflow and an empty backlog. Returns the ID of the new project.
+pub async fn create_project(db: &Db, title: &str, description: &str) -> Result<i32, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let project_id = insert_returning_id(
+            sqlx::query("INSERT INTO Project (Title, Description) VALUES (?, ?)")
+                .bind(title)
+                .bind(description),
+            &mut *transaction,
+            "ProjectID",
+        )
         .await?;
 
-    // Then, we want to remove the sprints association to the project.
-    sqlx::query("DELETE FROM PartOf WHERE SprintID = ?")
-        .bind(sprint_id)
-        .execute(&mut *transaction)
+        insert_workflow!(transaction, project_id, Workflow::default());
+
+        record_changes!(
+            transaction,
+            Change::created(
+                RecordKind::Project,
+                project_id,
+                audit::project_fields(title, description),
+            )
+        );
+
+        let today = chrono::Local::now().date_naive();
+        let backlog_id = insert_backlog!(transaction, project_id, today);
+        let mut fields = audit::sprint_fields(Sprint::BACKLOG_TITLE, today, today);
+        fields.push(("ProjectID", project_id.to_string()));
+        record_changes!(transaction, Change::created(RecordKind::Sprint, backlog_id, fields));
+
+        transaction.commit().await?;
+
+        Ok(project_id)
+    })
+}
+
+/// Fetches the workflow of a project through `$executor`, a pool or an already open transaction.
+macro_rules! fetch_workflow {
+    ($executor:expr, $project_id:expr) => {{
+        let statuses = sqlx::query_as::<_, StatusDefinition>(
+            "SELECT Name, Emoji FROM ProjectStatus WHERE ProjectID = ? ORDER BY Position",
+        )
+        .bind($project_id)
+        .fetch_all($executor)
         .await?;
 
-    // Delete tasks associated with the sprint using the PartOf table.
-    sqlx::query("DELETE FROM Task WHERE TaskID IN (SELECT TaskID FROM PartOf WHERE SprintID = ?)")
-        .bind(sprint_id)
-        .execute(&mut *transaction)
+        let transitions = sqlx::query_as::<_, (String, String)>(
+            "SELECT FromStatus, ToStatus FROM StatusTransition WHERE ProjectID = ?",
+        )
+        .bind($project_id)
+        .fetch_all($executor)
         .await?;
 
-    // Finally, delete the sprint itself.
-    sqlx::query("DELETE FROM Sprint WHERE SprintID = ?")
-        .bind(sprint_id)
-        .execute(&mut *transaction)
+        Workflow {
+            statuses,
+            transitions,
+        }
+    }};
+}
+
+/// Fetches the distinct statuses the tasks of a project are in through `$executor`.
+macro_rules! fetch_project_task_statuses {
+    ($executor:expr, $project_id:expr) => {
+        sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT Task.Status FROM Task
+             INNER JOIN PartOf ON Task.TaskID = PartOf.TaskID
+             INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+             WHERE ProjectSprint.ProjectID = ?",
+        )
+        .bind($project_id)
+        .fetch_all($executor)
+        .await?
+    };
+}
+
+/// Fetches the workflow of a project.
+pub async fn fetch_workflow(db: &Db, project_id: i32) -> Result<Workflow, sqlx::Error> {
+    with_pool!(db, pool => Ok(fetch_workflow!(pool, project_id)))
+}
+
+/// Replaces the statuses and transitions of a project, in one transaction. Refuses, changing nothing,
+/// a workflow that drops a status tasks of the project are in as the transaction sees them.
+pub async fn replace_workflow(
+    db: &Db,
+    project_id: i32,
+    workflow: &Workflow,
+) -> Result<Result<(), Refusal>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let in_use = fetch_project_task_statuses!(&mut *transaction, project_id);
+        if let Err(e) = check_workflow(workflow, &in_use) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Invalid(e)));
+        }
+
+        let before = fetch_workflow!(&mut *transaction, project_id);
+
+        sqlx::query("DELETE FROM StatusTransition WHERE ProjectID = ?")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM ProjectStatus WHERE ProjectID = ?")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        insert_workflow!(transaction, project_id, workflow);
+
+        record_changes!(
+            transaction,
+            Change::updated(
+                RecordKind::Project,
+                project_id,
+                audit::workflow_fields(&before),
+                audit::workflow_fields(workflow),
+            )
+        );
+
+        transaction.commit().await?;
+
+        Ok(Ok(()))
+    })
+}
+
+
+/// Fetches the labels of a project, in the order they were defined.
+pub async fn fetch_labels(db: &Db, project_id: i32) -> Result<Vec<Label>, sqlx::Error> {
+    with_pool!(db, pool => {
+        sqlx::query_as::<_, Label>(LABELS_OF_PROJECT)
+            .bind(project_id)
+            .fetch_all(pool)
+            .await
+    })
+}
+
+pub async fn fetch_label(db: &Db, label_id: i32) -> Result<Label, sqlx::Error> {
+    with_pool!(db, pool => {
+        sqlx::query_as::<_, Label>("SELECT * FROM Label WHERE LabelID = ?")
+            .bind(label_id)
+            .fetch_one(pool)
+            .await
+    })
+}
+
+/// Adds a label to a project, recording the new label list of the project.
+pub async fn create_label(
+    db: &Db,
+    project_id: i32,
+    name: &str,
+    color: &str,
+) -> Result<Label, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let before = sqlx::query_as::<_, Label>(LABELS_OF_PROJECT)
+            .bind(project_id)
+            .fetch_all(&mut *transaction)
+            .await?;
+
+        let label_id = insert_returning_id(
+            sqlx::query("INSERT INTO Label (ProjectID, Name, Color) VALUES (?, ?, ?)")
+                .bind(project_id)
+                .bind(name)
+                .bind(color),
+            &mut *transaction,
+            "LabelID",
+        )
         .await?;
 
-    transaction.commit().await?;
+        let label = Label {
+            label_id,
+            project_id,
+            name: name.into(),
+            color: color.into(),
+        };
+        let mut after = before.clone();
+        after.push(label.clone());
+        record_changes!(
+            transaction,
+            Change::updated(
+                RecordKind::Project,
+                project_id,
+                audit::label_fields(&before),
+                audit::label_fields(&after),
+            )
+        );
+
+        transaction.commit().await?;
 
-    Ok(())
+        Ok(label)
+    })
 }
 
-pub async fn delete_task_by_id(pool: &Pool<MySql>, task_id: i32) -> Result<(), sqlx::Error> {
-    let mut transaction = pool.begin().await?;
+/// Renames or recolors a label, recording the new label list of its project.
+pub async fn update_label(db: &Db, label_id: i32, name: &str, color: &str) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
 
-    // First, delete any entries from the PartOf table that link this task to any sprints.
-    sqlx::query("DELETE FROM PartOf WHERE TaskID = ?")
-        .bind(task_id)
-        .execute(&mut *transaction)
+        let project_id: i32 = sqlx::query_scalar("SELECT ProjectID FROM Label WHERE LabelID = ?")
+            .bind(label_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        let before = sqlx::query_as::<_, Label>(LABELS_OF_PROJECT)
+            .bind(project_id)
+            .fetch_all(&mut *transaction)
+            .await?;
+
+        sqlx::query("UPDATE Label SET Name = ?, Color = ? WHERE LabelID = ?")
+            .bind(name)
+            .bind(color)
+            .bind(label_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let after = sqlx::query_as::<_, Label>(LABELS_OF_PROJECT)
+            .bind(project_id)
+            .fetch_all(&mut *transaction)
+            .await?;
+        record_changes!(
+            transaction,
+            Change::updated(
+                RecordKind::Project,
+                project_id,
+                audit::label_fields(&before),
+                audit::label_fields(&after),
+            )
+        );
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Deletes a label and takes it off every task carrying it, in one transaction.
+pub async fn delete_label_by_id(db: &Db, label_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let project_id: i32 = sqlx::query_scalar("SELECT ProjectID FROM Label WHERE LabelID = ?")
+            .bind(label_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        let before = sqlx::query_as::<_, Label>(LABELS_OF_PROJECT)
+            .bind(project_id)
+            .fetch_all(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM TaskLabel WHERE LabelID = ?")
+            .bind(label_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM Label WHERE LabelID = ?")
+            .bind(label_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let after: Vec<Label> = before
+            .iter()
+            .filter(|label| label.label_id != label_id)
+            .cloned()
+            .collect();
+        record_changes!(
+            transaction,
+            Change::updated(
+                RecordKind::Project,
+                project_id,
+                audit::label_fields(&before),
+                audit::label_fields(&after),
+            )
+        );
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Fetches the ID of the project owning a sprint through `$executor`.
+macro_rules! fetch_project_id_of_sprint {
+    ($executor:expr, $sprint_id:expr) => {
+        sqlx::query_scalar::<_, i32>("SELECT ProjectID FROM ProjectSprint WHERE SprintID = ?")
+            .bind($sprint_id)
+            .fetch_one($executor)
+            .await?
+    };
+}
+
+/// Fetches the ID of the project owning a sprint.
+pub async fn fetch_project_id_of_sprint(db: &Db, sprint_id: i32) -> Result<i32, sqlx::Error> {
+    with_pool!(db, pool => Ok(fetch_project_id_of_sprint!(pool, sprint_id)))
+}
+
+/// Fetches a sprint without its tasks through `$executor`.
+macro_rules! fetch_sprint {
+    ($executor:expr, $sprint_id:expr) => {
+        sqlx::query_as::<_, RawSprint>("SELECT * FROM Sprint WHERE SprintID = ?")
+            .bind($sprint_id)
+            .fetch_one($executor)
+            .await?
+    };
+}
+
+/// Fetches a sprint without its tasks.
+pub async fn fetch_sprint(db: &Db, sprint_id: i32) -> Result<RawSprint, sqlx::Error> {
+    with_pool!(db, pool => Ok(fetch_sprint!(pool, sprint_id)))
+}
+
+/// Fetches the sprint a task is part of, without its tasks, through `$executor`.
+macro_rules! fetch_sprint_of_task {
+    ($executor:expr, $task_id:expr) => {
+        sqlx::query_as::<_, RawSprint>(
+            "SELECT Sprint.* FROM Sprint
+             INNER JOIN PartOf ON Sprint.SprintID = PartOf.SprintID
+             WHERE PartOf.TaskID = ?",
+        )
+        .bind($task_id)
+        .fetch_one($executor)
+        .await?
+    };
+}
+
+/// Fetches the due dates set on the tasks of a sprint through `$executor`.
+macro_rules! fetch_due_dates_of_sprint {
+    ($executor:expr, $sprint_id:expr) => {
+        sqlx::query_scalar::<_, chrono::NaiveDate>(
+            "SELECT Task.dueDate FROM Task
+             INNER JOIN PartOf ON Task.TaskID = PartOf.TaskID
+             WHERE PartOf.SprintID = ? AND Task.dueDate IS NOT NULL",
+        )
+        .bind($sprint_id)
+        .fetch_all($executor)
+        .await?
+    };
+}
+
+/// Checks new dates of a sprint against the due dates of its tasks, as seen through `$executor`.
+/// The dates of a backlog cannot change at all.
+macro_rules! check_sprint_update {
+    ($executor:expr, $sprint_id:expr, $start_date:expr, $end_date:expr) => {{
+        let sprint = fetch_sprint!($executor, $sprint_id);
+        let due_dates = fetch_due_dates_of_sprint!($executor, $sprint_id);
+        check_not_backlog(&sprint).and_then(|()| {
+            due_dates
+                .into_iter()
+                .try_for_each(|due_date| check_due_date(Some(due_date), $start_date, $end_date))
+        })
+    }};
+}
+
+/// Fetches the ID of the project owning a task, along with the task's current status, through `$executor`.
+macro_rules! fetch_project_id_and_status_of_task {
+    ($executor:expr, $task_id:expr) => {
+        sqlx::query_as::<_, (i32, String)>(
+            "SELECT ProjectSprint.ProjectID, Task.Status FROM Task
+             INNER JOIN PartOf ON Task.TaskID = PartOf.TaskID
+             INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+             WHERE Task.TaskID = ?",
+        )
+        .bind($task_id)
+        .fetch_one($executor)
+        .await?
+    };
+}
+
+/// Checks moving a task to `$status` against the workflow of its project, as seen through `$executor`.
+macro_rules! check_status_change {
+    ($executor:expr, $task_id:expr, $status:expr) => {{
+        let (project_id, current) = fetch_project_id_and_status_of_task!($executor, $task_id);
+        check_transition(&fetch_workflow!($executor, project_id), &current, $status)
+    }};
+}
+
+/// Checks moving a task to another sprint, with `$due_date`, against both projects and the sprint's dates,
+/// as seen through `$executor`.
+macro_rules! check_task_move {
+    ($executor:expr, $task_id:expr, $sprint_id:expr, $due_date:expr) => {{
+        let (task_project_id, _) = fetch_project_id_and_status_of_task!($executor, $task_id);
+        let sprint_project_id = fetch_project_id_of_sprint!($executor, $sprint_id);
+        let sprint = fetch_sprint!($executor, $sprint_id);
+        check_move(task_project_id, sprint_project_id).and_then(|()| check_due_date_in(&sprint, $due_date))
+    }};
+}
+
+/// Checks an update of a task against its project and sprint, as seen through `$executor`.
+macro_rules! check_task_update {
+    ($executor:expr, $task_id:expr, $status:expr, $priority:expr, $due_date:expr) => {{
+        let status_change = check_status_change!($executor, $task_id, $status);
+        let sprint = fetch_sprint_of_task!($executor, $task_id);
+        check_priority($priority)
+            .and(status_change)
+            .and_then(|()| check_due_date_in(&sprint, $due_date))
+    }};
+}
+
+/// Updates a project inside the already open `$transaction`, recording the changed fields.
+/// Evaluates to `false`, changing nothing, if the project is no longer at `$version`.
+macro_rules! update_project {
+    ($transaction:expr, $project_id:expr, $version:expr, $title:expr, $description:expr) => {{
+        let (old_title, old_description): (String, String) =
+            sqlx::query_as("SELECT Title, Description FROM Project WHERE ProjectID = ?")
+                .bind($project_id)
+                .fetch_one(&mut *$transaction)
+                .await?;
+
+        let applied = sqlx::query(
+            "UPDATE Project SET Title = ?, Description = ?, Version = Version + 1 WHERE ProjectID = ? AND Version = ?",
+        )
+        .bind($title)
+        .bind($description)
+        .bind($project_id)
+        .bind($version)
+        .execute(&mut *$transaction)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if applied {
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::Project,
+                    $project_id,
+                    audit::project_fields(&old_title, &old_description),
+                    audit::project_fields($title, $description),
+                )
+            );
+        }
+        applied
+    }};
+}
+
+/// Updates a project if it is still at `version`. Returns `false`, changing nothing, if it is not.
+pub async fn update_project(
+    db: &Db,
+    project_id: i32,
+    version: i32,
+    title: &str,
+    description: &str,
+) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if !update_project!(transaction, project_id, version, title, description) {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        transaction.commit().await?;
+
+        Ok(true)
+    })
+}
+
+/// Creates a sprint and links it to the project with the provided ID, in one transaction.
+/// Returns the new sprint, which has no tasks yet.
+pub async fn create_sprint_for_project(
+    db: &Db,
+    project_id: i32,
+    title: &str,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+) -> Result<Sprint, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        // Insert the sprint into the SPRINT table
+        let sprint_id = insert_returning_id(
+            sqlx::query("INSERT INTO Sprint (Title, startDate, endDate) VALUES (?, ?, ?)")
+                .bind(title)
+                .bind(start_date)
+                .bind(end_date),
+            &mut *transaction,
+            "SprintID",
+        )
         .await?;
 
-    // Once the references in PartOf are removed, it's safe to delete the task itself.
-    sqlx::query("DELETE FROM Task WHERE TaskID = ?")
-        .bind(task_id)
-        .execute(&mut *transaction)
+        sqlx::query("INSERT INTO ProjectSprint (ProjectID, SprintID) VALUES (?, ?)")
+            .bind(project_id)
+            .bind(sprint_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let mut fields = audit::sprint_fields(title, start_date, end_date);
+        fields.push(("ProjectID", project_id.to_string()));
+        record_changes!(transaction, Change::created(RecordKind::Sprint, sprint_id, fields));
+
+        transaction.commit().await?;
+
+        Ok(Sprint {
+            sprint_id,
+            title: title.into(),
+            start_date,
+            end_date,
+            version: 0,
+            tasks: Vec::new(),
+            backlog: false,
+        })
+    })
+}
+
+/// Updates a sprint inside the already open `$transaction`, recording the changed fields.
+/// Evaluates to `false`, changing nothing, if the sprint is no longer at `$version`.
+macro_rules! update_sprint {
+    ($transaction:expr, $sprint_id:expr, $version:expr, $title:expr, $start_date:expr, $end_date:expr) => {{
+        let (old_title, old_start_date, old_end_date): (String, chrono::NaiveDate, chrono::NaiveDate) =
+            sqlx::query_as("SELECT Title, startDate, endDate FROM Sprint WHERE SprintID = ?")
+                .bind($sprint_id)
+                .fetch_one(&mut *$transaction)
+                .await?;
+
+        let applied = sqlx::query(
+            "UPDATE Sprint SET Title = ?, startDate = ?, endDate = ?, Version = Version + 1 WHERE SprintID = ? AND Version = ?",
+        )
+        .bind($title)
+        .bind($start_date)
+        .bind($end_date)
+        .bind($sprint_id)
+        .bind($version)
+        .execute(&mut *$transaction)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if applied {
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::Sprint,
+                    $sprint_id,
+                    audit::sprint_fields(&old_title, old_start_date, old_end_date),
+                    audit::sprint_fields($title, $start_date, $end_date),
+                )
+            );
+        }
+        applied
+    }};
+}
+
+/// Updates a sprint if it is still at `version`, checking its new dates against the due dates of its tasks
+/// as the transaction sees them. Changes nothing if the update is refused.
+pub async fn update_sprint(
+    db: &Db,
+    sprint_id: i32,
+    version: i32,
+    title: &str,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+) -> Result<Result<(), Refusal>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if let Err(e) = check_sprint_update!(&mut *transaction, sprint_id, start_date, end_date) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Invalid(e)));
+        }
+
+        if !update_sprint!(transaction, sprint_id, version, title, start_date, end_date) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Conflict));
+        }
+
+        transaction.commit().await?;
+
+        Ok(Ok(()))
+    })
+}
+
+/// Creates a task and links it to the sprint with the provided ID, in one transaction.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_task_in_sprint(
+    db: &Db,
+    sprint_id: i32,
+    title: &str,
+    status: &str,
+    description: &str,
+    estimated_hours: i32,
+    priority: i32,
+    due_date: Option<chrono::NaiveDate>,
+) -> Result<Task, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let task_id = insert_returning_id(
+            sqlx::query(
+                "INSERT INTO Task (Title, Status, Description, commitedHours, estimatedHours, Priority, dueDate)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(title)
+            .bind(status)
+            .bind(description)
+            .bind(0) // Setting commitedHours to 0 initially
+            .bind(estimated_hours)
+            .bind(priority)
+            .bind(due_date),
+            &mut *transaction,
+            "TaskID",
+        )
         .await?;
 
-    // Commit the transaction to ensure all deletions are applied together.
-    transaction.commit().await?;
+        sqlx::query("INSERT INTO PartOf (TaskID, SprintID) VALUES (?, ?)")
+            .bind(task_id)
+            .bind(sprint_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let mut fields = audit::task_fields(title, status, description, estimated_hours, priority, due_date);
+        fields.push(("SprintID", sprint_id.to_string()));
+        record_changes!(transaction, Change::created(RecordKind::Task, task_id, fields));
+
+        transaction.commit().await?;
+
+        Ok(Task {
+            task_id,
+            title: title.into(),
+            status: status.into(),
+            description: description.into(),
+            commited_hours: 0,
+            estimated_hours,
+            priority,
+            due_date,
+            version: 0,
+            assignees: Vec::new(),
+            blocked_by: Vec::new(),
+            checklist: Vec::new(),
+            labels: Vec::new(),
+        })
+    })
+}
+
+/// Updates a task inside the already open `$transaction`, recording the changed fields.
+/// Evaluates to `false`, changing nothing, if the task is no longer at `$version`.
+macro_rules! update_task {
+    ($transaction:expr, $task_id:expr, $version:expr, $title:expr, $status:expr, $description:expr, $estimated_hours:expr, $priority:expr, $due_date:expr) => {{
+        let before = sqlx::query_as::<_, Task>("SELECT * FROM Task WHERE TaskID = ?")
+            .bind($task_id)
+            .fetch_one(&mut *$transaction)
+            .await?;
+
+        let applied = sqlx::query(
+            "UPDATE Task SET Title = ?, Status = ?, Description = ?, estimatedHours = ?, Priority = ?,
+             dueDate = ?, Version = Version + 1
+             WHERE TaskID = ? AND Version = ?",
+        )
+        .bind($title)
+        .bind($status)
+        .bind($description)
+        .bind($estimated_hours)
+        .bind($priority)
+        .bind($due_date)
+        .bind($task_id)
+        .bind($version)
+        .execute(&mut *$transaction)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if applied {
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::Task,
+                    $task_id,
+                    audit::task_fields(
+                        &before.title,
+                        &before.status,
+                        &before.description,
+                        before.estimated_hours,
+                        before.priority,
+                        before.due_date,
+                    ),
+                    audit::task_fields($title, $status, $description, $estimated_hours, $priority, $due_date),
+                )
+            );
+        }
+        applied
+    }};
+}
+
+/// Updates a task if it is still at `version`, checking the update against the task as the transaction
+/// sees it. Changes nothing if the update is refused.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_task(
+    db: &Db,
+    task_id: i32,
+    version: i32,
+    title: &str,
+    status: &str,
+    description: &str,
+    estimated_hours: i32,
+    priority: i32,
+    due_date: Option<chrono::NaiveDate>,
+) -> Result<Result<(), Refusal>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if let Err(e) = check_task_update!(&mut *transaction, task_id, status, priority, due_date) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Invalid(e)));
+        }
+
+        if !update_task!(transaction, task_id, version, title, status, description, estimated_hours, priority, due_date) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Conflict));
+        }
+
+        transaction.commit().await?;
+
+        Ok(Ok(()))
+    })
+}
+
+/// Moves a task to another sprint inside the already open `$transaction`, giving it `$due_date`
+/// and recording both changes. Evaluates to `false`, changing nothing, if the task is no longer at `$version`.
+macro_rules! move_task {
+    ($transaction:expr, $task_id:expr, $version:expr, $sprint_id:expr, $due_date:expr) => {{
+        let (old_sprint_id, old_due_date): (i32, Option<chrono::NaiveDate>) = sqlx::query_as(
+            "SELECT PartOf.SprintID, Task.dueDate FROM Task
+             INNER JOIN PartOf ON Task.TaskID = PartOf.TaskID
+             WHERE Task.TaskID = ?",
+        )
+        .bind($task_id)
+        .fetch_one(&mut *$transaction)
+        .await?;
+
+        let applied = sqlx::query(
+            "UPDATE Task SET dueDate = ?, Version = Version + 1 WHERE TaskID = ? AND Version = ?",
+        )
+        .bind($due_date)
+        .bind($task_id)
+        .bind($version)
+        .execute(&mut *$transaction)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if applied {
+            sqlx::query("UPDATE PartOf SET SprintID = ? WHERE TaskID = ?")
+                .bind($sprint_id)
+                .bind($task_id)
+                .execute(&mut *$transaction)
+                .await?;
+
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::Task,
+                    $task_id,
+                    audit::task_move_fields(old_sprint_id, old_due_date),
+                    audit::task_move_fields($sprint_id, $due_date),
+                )
+            );
+        }
+        applied
+    }};
+}
+
+/// Moves a task to another sprint if it is still at `version`, giving it `due_date` there. The move is
+/// checked against the task and the sprint as the transaction sees them. Changes nothing if it is refused.
+pub async fn move_task(
+    db: &Db,
+    task_id: i32,
+    version: i32,
+    sprint_id: i32,
+    due_date: Option<chrono::NaiveDate>,
+) -> Result<Result<(), Refusal>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if let Err(e) = check_task_move!(&mut *transaction, task_id, sprint_id, due_date) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Invalid(e)));
+        }
+
+        if !move_task!(transaction, task_id, version, sprint_id, due_date) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Conflict));
+        }
+
+        transaction.commit().await?;
+
+        Ok(Ok(()))
+    })
+}
+
+/// Moves a task to another status if it is still at `version` and its workflow allows it, as the
+/// transaction sees them. Changes nothing if the move is refused.
+pub async fn update_task_status(
+    db: &Db,
+    task_id: i32,
+    version: i32,
+    status: &str,
+) -> Result<Result<(), Refusal>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if let Err(e) = check_status_change!(&mut *transaction, task_id, status) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Invalid(e)));
+        }
+
+        let old_status: String = sqlx::query_scalar("SELECT Status FROM Task WHERE TaskID = ?")
+            .bind(task_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+
+        let applied = sqlx::query("UPDATE Task SET Status = ?, Version = Version + 1 WHERE TaskID = ? AND Version = ?")
+            .bind(status)
+            .bind(task_id)
+            .bind(version)
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected()
+            > 0;
+
+        if !applied {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Conflict));
+        }
+
+        record_changes!(
+            transaction,
+            Change::updated(
+                RecordKind::Task,
+                task_id,
+                vec![("Status", old_status)],
+                vec![("Status", status.into())],
+            )
+        );
+
+        transaction.commit().await?;
+
+        Ok(Ok(()))
+    })
+}
+
+/// Adds an item, not done yet, to the end of a task's checklist.
+pub async fn create_checklist_item(
+    db: &Db,
+    task_id: i32,
+    title: &str,
+) -> Result<ChecklistItem, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let item_id = insert_returning_id(
+            sqlx::query("INSERT INTO ChecklistItem (TaskID, Title, Done) VALUES (?, ?, ?)")
+                .bind(task_id)
+                .bind(title)
+                .bind(false),
+            &mut *transaction,
+            "ItemID",
+        )
+        .await?;
+
+        let mut fields = audit::checklist_item_fields(title, false);
+        fields.push(("TaskID", task_id.to_string()));
+        record_changes!(transaction, Change::created(RecordKind::ChecklistItem, item_id, fields));
+
+        transaction.commit().await?;
+
+        Ok(ChecklistItem {
+            item_id,
+            task_id,
+            title: title.into(),
+            done: false,
+            version: 0,
+        })
+    })
+}
+
+/// Updates a checklist item inside the already open `$transaction`, recording the changed fields.
+/// Evaluates to `false`, changing nothing, if the item is no longer at `$version`.
+macro_rules! update_checklist_item {
+    ($transaction:expr, $item_id:expr, $version:expr, $title:expr, $done:expr) => {{
+        let before = sqlx::query_as::<_, ChecklistItem>("SELECT * FROM ChecklistItem WHERE ItemID = ?")
+            .bind($item_id)
+            .fetch_one(&mut *$transaction)
+            .await?;
+
+        let applied = sqlx::query(
+            "UPDATE ChecklistItem SET Title = ?, Done = ?, Version = Version + 1 WHERE ItemID = ? AND Version = ?",
+        )
+        .bind($title)
+        .bind($done)
+        .bind($item_id)
+        .bind($version)
+        .execute(&mut *$transaction)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if applied {
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::ChecklistItem,
+                    $item_id,
+                    audit::checklist_item_fields(&before.title, before.done),
+                    audit::checklist_item_fields($title, $done),
+                )
+            );
+        }
+        applied
+    }};
+}
+
+/// Renames a checklist item or ticks it off if it is still at `version`. Returns `false`, changing nothing, if it is not.
+pub async fn update_checklist_item(
+    db: &Db,
+    item_id: i32,
+    version: i32,
+    title: &str,
+    done: bool,
+) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if !update_checklist_item!(transaction, item_id, version, title, done) {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        transaction.commit().await?;
+
+        Ok(true)
+    })
+}
+
+pub async fn delete_checklist_item_by_id(db: &Db, item_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let item = sqlx::query_as::<_, ChecklistItem>("SELECT * FROM ChecklistItem WHERE ItemID = ?")
+            .bind(item_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        record_changes!(
+            transaction,
+            Change::deleted(
+                RecordKind::ChecklistItem,
+                item_id,
+                audit::checklist_item_fields(&item.title, item.done),
+            )
+        );
+
+        sqlx::query("DELETE FROM ChecklistItem WHERE ItemID = ?")
+            .bind(item_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Fetches every member outside the trash, regardless of the projects they contribute to.
+pub async fn fetch_members(db: &Db) -> Result<Vec<Member>, sqlx::Error> {
+    with_pool!(db, pool => {
+        sqlx::query_as::<_, Member>(
+            "SELECT MemberID, firstName, lastName, email, phone, Version FROM Member WHERE DeletedAt IS NULL",
+        )
+        .fetch_all(pool)
+        .await
+    })
+}
+
+pub async fn create_member(
+    db: &Db,
+    first_name: &str,
+    last_name: &str,
+    email: &str,
+    phone: &str,
+) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let member_id = insert_returning_id(
+            sqlx::query("INSERT INTO Member (firstName, lastName, email, phone) VALUES (?, ?, ?, ?)")
+                .bind(first_name)
+                .bind(last_name)
+                .bind(email)
+                .bind(phone),
+            &mut *transaction,
+            "MemberID",
+        )
+        .await?;
+
+        record_changes!(
+            transaction,
+            Change::created(
+                RecordKind::Member,
+                member_id,
+                audit::member_fields(first_name, last_name, email, phone),
+            )
+        );
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Updates a member if they are still at `version`. Returns `false`, changing nothing, if they are not.
+pub async fn update_member(
+    db: &Db,
+    member_id: i32,
+    version: i32,
+    first_name: &str,
+    last_name: &str,
+    email: &str,
+    phone: &str,
+) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let before = sqlx::query_as::<_, Member>("SELECT * FROM Member WHERE MemberID = ?")
+            .bind(member_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+
+        let applied = sqlx::query(
+            "UPDATE Member SET firstName = ?, lastName = ?, email = ?, phone = ?, Version = Version + 1
+             WHERE MemberID = ? AND Version = ?",
+        )
+        .bind(first_name)
+        .bind(last_name)
+        .bind(email)
+        .bind(phone)
+        .bind(member_id)
+        .bind(version)
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !applied {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        record_changes!(
+            transaction,
+            Change::updated(
+                RecordKind::Member,
+                member_id,
+                audit::member_fields(&before.first_name, &before.last_name, &before.email, &before.phone),
+                audit::member_fields(first_name, last_name, email, phone),
+            )
+        );
+
+        transaction.commit().await?;
+
+        Ok(true)
+    })
+}
+
+/// Deletes a member along with every project membership, task assignment, work log entry and comment they hold.
+pub async fn delete_member_by_id(db: &Db, member_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        // The hours the member logged go with them, so the tasks they worked on need their rollup redone.
+        let logged_task_ids: Vec<i32> =
+            sqlx::query_scalar("SELECT DISTINCT TaskID FROM WorkLog WHERE MemberID = ?")
+                .bind(member_id)
+                .fetch_all(&mut *transaction)
+                .await?;
+
+        let member = sqlx::query_as::<_, Member>("SELECT * FROM Member WHERE MemberID = ?")
+            .bind(member_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        let mut changes = Change::deleted(
+            RecordKind::Member,
+            member_id,
+            audit::member_fields(&member.first_name, &member.last_name, &member.email, &member.phone),
+        );
+
+        sqlx::query("DELETE FROM WorkLog WHERE MemberID = ?")
+            .bind(member_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        for task_id in logged_task_ids {
+            changes.extend(roll_up_commited_hours!(transaction, task_id));
+        }
+
+        sqlx::query("DELETE FROM AssignedTo WHERE MemberID = ?")
+            .bind(member_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM TaskComment WHERE MemberID = ?")
+            .bind(member_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM ContributesTo WHERE MemberID = ?")
+            .bind(member_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM Member WHERE MemberID = ?")
+            .bind(member_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        record_changes!(transaction, changes);
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+pub async fn fetch_members_by_project_id(
+    db: &Db,
+    project_id: i32,
+) -> Result<Vec<Member>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let members = sqlx::query_as::<_, Member>(
+            r#"
+            SELECT Member.MemberID, Member.firstName, Member.lastName, Member.email, Member.phone, Member.Version
+            FROM Member
+            INNER JOIN ContributesTo ON Member.MemberID = ContributesTo.MemberID
+            WHERE ContributesTo.ProjectID = ? AND Member.DeletedAt IS NULL
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    })
+}
+
+/// Adds a member to a project inside the already open `$transaction`, recording the new member list.
+macro_rules! add_project_member {
+    ($transaction:expr, $member_id:expr, $project_id:expr) => {{
+        let before: Vec<i32> = sqlx::query_scalar("SELECT MemberID FROM ContributesTo WHERE ProjectID = ?")
+            .bind($project_id)
+            .fetch_all(&mut *$transaction)
+            .await?;
+
+        sqlx::query("INSERT INTO ContributesTo (MemberID, ProjectID) VALUES (?, ?)")
+            .bind($member_id)
+            .bind($project_id)
+            .execute(&mut *$transaction)
+            .await?;
+
+        let mut after = before.clone();
+        after.push($member_id);
+        record_changes!(
+            $transaction,
+            Change::updated(
+                RecordKind::Project,
+                $project_id,
+                vec![("members", audit::id_list(&before))],
+                vec![("members", audit::id_list(&after))],
+            )
+        );
+    }};
+}
+
+pub async fn add_project_member(db: &Db, member_id: i32, project_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        add_project_member!(transaction, member_id, project_id);
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Removes a member from a project inside the already open `$transaction`, unassigning them from
+/// every task of the project and recording the new member list.
+macro_rules! remove_project_member {
+    ($transaction:expr, $member_id:expr, $project_id:expr) => {{
+        let before: Vec<i32> = sqlx::query_scalar("SELECT MemberID FROM ContributesTo WHERE ProjectID = ?")
+            .bind($project_id)
+            .fetch_all(&mut *$transaction)
+            .await?;
+
+        sqlx::query(
+            "DELETE FROM AssignedTo WHERE MemberID = ? AND TaskID IN (
+                SELECT PartOf.TaskID FROM PartOf
+                INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+                WHERE ProjectSprint.ProjectID = ?
+            )",
+        )
+        .bind($member_id)
+        .bind($project_id)
+        .execute(&mut *$transaction)
+        .await?;
+
+        sqlx::query("DELETE FROM ContributesTo WHERE MemberID = ? AND ProjectID = ?")
+            .bind($member_id)
+            .bind($project_id)
+            .execute(&mut *$transaction)
+            .await?;
+
+        let after: Vec<i32> = before.iter().copied().filter(|m| *m != $member_id).collect();
+        record_changes!(
+            $transaction,
+            Change::updated(
+                RecordKind::Project,
+                $project_id,
+                vec![("members", audit::id_list(&before))],
+                vec![("members", audit::id_list(&after))],
+            )
+        );
+    }};
+}
+
+/// Removes a member from a project, unassigning them from every task of the project.
+pub async fn remove_project_member(
+    db: &Db,
+    member_id: i32,
+    project_id: i32,
+) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        remove_project_member!(transaction, member_id, project_id);
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Replaces the assignees of a task inside the already open `$transaction`, recording the new assignee list.
+/// Evaluates to `false`, leaving the caller to roll back, as soon as a member outside the task's project comes up.
+macro_rules! set_task_assignees {
+    ($transaction:expr, $task_id:expr, $member_ids:expr) => {{
+        let before: Vec<i32> = sqlx::query_scalar("SELECT MemberID FROM AssignedTo WHERE TaskID = ?")
+            .bind($task_id)
+            .fetch_all(&mut *$transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM AssignedTo WHERE TaskID = ?")
+            .bind($task_id)
+            .execute(&mut *$transaction)
+            .await?;
+
+        let mut all_assigned = true;
+        for member_id in $member_ids {
+            // Selecting the row to insert through the project's memberships inserts nothing for outsiders.
+            let inserted = sqlx::query(
+                "INSERT INTO AssignedTo (MemberID, TaskID)
+                 SELECT DISTINCT ContributesTo.MemberID, PartOf.TaskID FROM PartOf
+                 INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+                 INNER JOIN ContributesTo ON ProjectSprint.ProjectID = ContributesTo.ProjectID
+                 WHERE PartOf.TaskID = ? AND ContributesTo.MemberID = ?",
+            )
+            .bind($task_id)
+            .bind(member_id)
+            .execute(&mut *$transaction)
+            .await?
+            .rows_affected();
+
+            if inserted == 0 {
+                all_assigned = false;
+                break;
+            }
+        }
+
+        if all_assigned {
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::Task,
+                    $task_id,
+                    vec![("assignees", audit::id_list(&before))],
+                    vec![("assignees", audit::id_list($member_ids))],
+                )
+            );
+        }
+        all_assigned
+    }};
+}
+
+/// Replaces the assignees of a task with the members in `member_ids`, in one transaction.
+/// Only members of the project owning the task can be assigned to it; if any other member is
+/// in `member_ids`, nothing is changed and `false` is returned.
+pub async fn set_task_assignees(
+    db: &Db,
+    task_id: i32,
+    member_ids: &[i32],
+) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if !set_task_assignees!(transaction, task_id, member_ids) {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        transaction.commit().await?;
+
+        Ok(true)
+    })
+}
+
+/// Replaces the labels of a task inside the already open `$transaction`, recording the new label list.
+/// Evaluates to `false`, leaving the caller to roll back, as soon as a label of another project comes up.
+macro_rules! set_task_labels {
+    ($transaction:expr, $task_id:expr, $label_ids:expr) => {{
+        let before: Vec<i32> = sqlx::query_scalar("SELECT LabelID FROM TaskLabel WHERE TaskID = ?")
+            .bind($task_id)
+            .fetch_all(&mut *$transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM TaskLabel WHERE TaskID = ?")
+            .bind($task_id)
+            .execute(&mut *$transaction)
+            .await?;
+
+        let mut all_labeled = true;
+        for label_id in $label_ids {
+            // As with assignees, labels of other projects select no row to insert.
+            let inserted = sqlx::query(
+                "INSERT INTO TaskLabel (TaskID, LabelID)
+                 SELECT DISTINCT PartOf.TaskID, Label.LabelID FROM PartOf
+                 INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+                 INNER JOIN Label ON ProjectSprint.ProjectID = Label.ProjectID
+                 WHERE PartOf.TaskID = ? AND Label.LabelID = ?",
+            )
+            .bind($task_id)
+            .bind(label_id)
+            .execute(&mut *$transaction)
+            .await?
+            .rows_affected();
+
+            if inserted == 0 {
+                all_labeled = false;
+                break;
+            }
+        }
+
+        if all_labeled {
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::Task,
+                    $task_id,
+                    vec![("labels", audit::id_list(&before))],
+                    vec![("labels", audit::id_list($label_ids))],
+                )
+            );
+        }
+        all_labeled
+    }};
+}
+
+/// Replaces the labels of a task with those in `label_ids`, in one transaction.
+/// Only labels of the project owning the task can be put on it; if any other label is in
+/// `label_ids`, nothing is changed and `false` is returned.
+pub async fn set_task_labels(db: &Db, task_id: i32, label_ids: &[i32]) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if !set_task_labels!(transaction, task_id, label_ids) {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        transaction.commit().await?;
+
+        Ok(true)
+    })
+}
+
+/// Fetches every (blocker, blocked) task dependency, including those of tasks in the trash,
+/// through `$executor`.
+macro_rules! fetch_task_dependencies {
+    ($executor:expr) => {
+        sqlx::query_as::<_, (i32, i32)>("SELECT BlockerID, BlockedID FROM TaskDependency")
+            .fetch_all($executor)
+            .await?
+    };
+}
+
+/// Checks a new dependency against the projects of both tasks and the dependencies already there,
+/// as seen through `$executor`.
+macro_rules! check_new_dependency {
+    ($executor:expr, $blocker_id:expr, $blocked_id:expr) => {{
+        let (blocker_project_id, _) = fetch_project_id_and_status_of_task!($executor, $blocker_id);
+        let (blocked_project_id, _) = fetch_project_id_and_status_of_task!($executor, $blocked_id);
+        check_dependency(
+            &fetch_task_dependencies!($executor),
+            ($blocker_id, blocker_project_id),
+            ($blocked_id, blocked_project_id),
+        )
+    }};
+}
+
+/// Makes `$blocker_id` block `$blocked_id` inside the already open `$transaction`,
+/// recording the new blocker list of the blocked task.
+macro_rules! add_task_dependency {
+    ($transaction:expr, $blocker_id:expr, $blocked_id:expr) => {{
+        let before: Vec<i32> = sqlx::query_scalar("SELECT BlockerID FROM TaskDependency WHERE BlockedID = ?")
+            .bind($blocked_id)
+            .fetch_all(&mut *$transaction)
+            .await?;
+
+        sqlx::query("INSERT INTO TaskDependency (BlockerID, BlockedID) VALUES (?, ?)")
+            .bind($blocker_id)
+            .bind($blocked_id)
+            .execute(&mut *$transaction)
+            .await?;
+
+        let mut after = before.clone();
+        after.push($blocker_id);
+        record_changes!(
+            $transaction,
+            Change::updated(
+                RecordKind::Task,
+                $blocked_id,
+                vec![("blockers", audit::id_list(&before))],
+                vec![("blockers", audit::id_list(&after))],
+            )
+        );
+    }};
+}
+
+/// Makes `blocker_id` block `blocked_id`, in one transaction. Refuses, changing nothing, a dependency
+/// across projects or one closing a cycle with the dependencies as the transaction sees them.
+pub async fn add_task_dependency(
+    db: &Db,
+    blocker_id: i32,
+    blocked_id: i32,
+) -> Result<Result<(), Refusal>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if let Err(e) = check_new_dependency!(&mut *transaction, blocker_id, blocked_id) {
+            transaction.rollback().await?;
+            return Ok(Err(Refusal::Invalid(e)));
+        }
+
+        add_task_dependency!(transaction, blocker_id, blocked_id);
+
+        transaction.commit().await?;
+
+        Ok(Ok(()))
+    })
+}
+
+/// Removes the dependency between two tasks inside the already open `$transaction`,
+/// recording the new blocker list of the blocked task. Evaluates to `false` if there was no such dependency.
+macro_rules! remove_task_dependency {
+    ($transaction:expr, $blocker_id:expr, $blocked_id:expr) => {{
+        let before: Vec<i32> = sqlx::query_scalar("SELECT BlockerID FROM TaskDependency WHERE BlockedID = ?")
+            .bind($blocked_id)
+            .fetch_all(&mut *$transaction)
+            .await?;
+
+        let removed = sqlx::query("DELETE FROM TaskDependency WHERE BlockerID = ? AND BlockedID = ?")
+            .bind($blocker_id)
+            .bind($blocked_id)
+            .execute(&mut *$transaction)
+            .await?
+            .rows_affected()
+            > 0;
+
+        if removed {
+            let after: Vec<i32> = before.iter().copied().filter(|b| *b != $blocker_id).collect();
+            record_changes!(
+                $transaction,
+                Change::updated(
+                    RecordKind::Task,
+                    $blocked_id,
+                    vec![("blockers", audit::id_list(&before))],
+                    vec![("blockers", audit::id_list(&after))],
+                )
+            );
+        }
+        removed
+    }};
+}
+
+/// Removes the dependency between two tasks, in one transaction. Returns `false` if there was none.
+pub async fn remove_task_dependency(db: &Db, blocker_id: i32, blocked_id: i32) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let removed = remove_task_dependency!(transaction, blocker_id, blocked_id);
+
+        transaction.commit().await?;
+
+        Ok(removed)
+    })
+}
+
+/// Deletes the sprint along with its tasks and every link between them, in one transaction.
+pub async fn delete_sprint_by_id(db: &Db, sprint_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        // Collect the tasks of the sprint before their links in PartOf are removed.
+        let task_ids: Vec<i32> = sqlx::query_scalar("SELECT TaskID FROM PartOf WHERE SprintID = ?")
+            .bind(sprint_id)
+            .fetch_all(&mut *transaction)
+            .await?;
+
+        let (title, start_date, end_date): (String, chrono::NaiveDate, chrono::NaiveDate) =
+            sqlx::query_as("SELECT Title, startDate, endDate FROM Sprint WHERE SprintID = ?")
+                .bind(sprint_id)
+                .fetch_one(&mut *transaction)
+                .await?;
+
+        let mut changes = Change::deleted(
+            RecordKind::Sprint,
+            sprint_id,
+            audit::sprint_fields(&title, start_date, end_date),
+        );
+        changes.extend(task_ids.iter().map(|id| Change::record(RecordKind::Task, *id, Action::Delete)));
+        record_changes!(transaction, changes);
+
+        // Delete the tasks, along with every row referencing them.
+        for task_id in task_ids {
+            for statement in DELETE_TASK_STATEMENTS {
+                sqlx::query(statement)
+                    .bind(task_id)
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+        }
+
+        // Remove the linkage between the sprint and its project.
+        sqlx::query("DELETE FROM ProjectSprint WHERE SprintID = ?")
+            .bind(sprint_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        // Finally, delete the sprint itself.
+        sqlx::query("DELETE FROM Sprint WHERE SprintID = ?")
+            .bind(sprint_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+pub async fn delete_task_by_id(db: &Db, task_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let task = sqlx::query_as::<_, Task>("SELECT * FROM Task WHERE TaskID = ?")
+            .bind(task_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        record_changes!(
+            transaction,
+            Change::deleted(
+                RecordKind::Task,
+                task_id,
+                audit::task_fields(
+                    &task.title,
+                    &task.status,
+                    &task.description,
+                    task.estimated_hours,
+                    task.priority,
+                    task.due_date,
+                ),
+            )
+        );
+
+        // First the rows linking the task to sprints and members, then the task itself.
+        for statement in DELETE_TASK_STATEMENTS {
+            sqlx::query(statement)
+                .bind(task_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        // Commit the transaction to ensure all deletions are applied together.
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Fetches the work log of a task, oldest entries first.
+pub async fn fetch_work_log(db: &Db, task_id: i32) -> Result<Vec<WorkLogEntry>, sqlx::Error> {
+    with_pool!(db, pool => {
+        sqlx::query_as::<_, WorkLogEntry>(
+            "SELECT * FROM WorkLog WHERE TaskID = ? ORDER BY workDate, EntryID",
+        )
+        .bind(task_id)
+        .fetch_all(pool)
+        .await
+    })
+}
+
+/// Logs hours worked by a member on a task and adds them to the task's commitedHours, in one transaction.
+/// Only members of the project owning the task can log work on it; for anyone else nothing is
+/// written and `None` is returned.
+pub async fn add_work_log_entry(
+    db: &Db,
+    task_id: i32,
+    member_id: i32,
+    work_date: chrono::NaiveDate,
+    hours: i32,
+    note: &str,
+) -> Result<Option<WorkLogEntry>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        // Only members of the project owning the task can log work on it.
+        let contributes: Option<i32> = sqlx::query_scalar(
+            "SELECT ContributesTo.MemberID FROM PartOf
+             INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+             INNER JOIN ContributesTo ON ProjectSprint.ProjectID = ContributesTo.ProjectID
+             WHERE PartOf.TaskID = ? AND ContributesTo.MemberID = ?
+             LIMIT 1",
+        )
+        .bind(task_id)
+        .bind(member_id)
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        if contributes.is_none() {
+            transaction.rollback().await?;
+            return Ok(None);
+        }
+
+        let entry_id = insert_returning_id(
+            sqlx::query("INSERT INTO WorkLog (TaskID, MemberID, workDate, hours, note) VALUES (?, ?, ?, ?, ?)")
+                .bind(task_id)
+                .bind(member_id)
+                .bind(work_date)
+                .bind(hours)
+                .bind(note),
+            &mut *transaction,
+            "EntryID",
+        )
+        .await?;
+
+        let changes = roll_up_commited_hours!(transaction, task_id);
+        record_changes!(transaction, changes);
+
+        transaction.commit().await?;
+
+        Ok(Some(WorkLogEntry {
+            entry_id,
+            task_id,
+            member_id,
+            work_date,
+            hours,
+            note: note.into(),
+        }))
+    })
+}
+
+/// Removes a work log entry and takes its hours off the task's commitedHours, in one transaction.
+pub async fn remove_work_log_entry(db: &Db, entry_id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let task_id: i32 = sqlx::query_scalar("SELECT TaskID FROM WorkLog WHERE EntryID = ?")
+            .bind(entry_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM WorkLog WHERE EntryID = ?")
+            .bind(entry_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let changes = roll_up_commited_hours!(transaction, task_id);
+        record_changes!(transaction, changes);
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Fetches the comments on a task, oldest first.
+pub async fn fetch_comments(db: &Db, task_id: i32) -> Result<Vec<Comment>, sqlx::Error> {
+    with_pool!(db, pool => {
+        sqlx::query_as::<_, Comment>(
+            "SELECT * FROM TaskComment WHERE TaskID = ? ORDER BY postedAt, CommentID",
+        )
+        .bind(task_id)
+        .fetch_all(pool)
+        .await
+    })
+}
+
+/// Posts a comment by a member on a task.
+pub async fn add_comment(
+    db: &Db,
+    task_id: i32,
+    member_id: i32,
+    body: &str,
+) -> Result<Comment, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        let posted_at = chrono::Local::now().naive_local();
+        let comment_id = insert_returning_id(
+            sqlx::query("INSERT INTO TaskComment (TaskID, MemberID, Body, postedAt) VALUES (?, ?, ?, ?)")
+                .bind(task_id)
+                .bind(member_id)
+                .bind(body)
+                .bind(posted_at),
+            &mut *transaction,
+            "CommentID",
+        )
+        .await?;
+
+        let mut fields = audit::comment_fields(body);
+        fields.push(("TaskID", task_id.to_string()));
+        record_changes!(transaction, Change::created(RecordKind::Comment, comment_id, fields));
+
+        transaction.commit().await?;
+
+        Ok(Comment {
+            comment_id,
+            task_id,
+            member_id,
+            body: body.into(),
+            posted_at,
+            edited_at: None,
+        })
+    })
+}
+
+/// Looks up the author of a comment inside the already open `$transaction`, evaluating to whether it is
+/// `$member_id`. Returns `RowNotFound` from the enclosing function if the comment does not exist.
+macro_rules! is_comment_author {
+    ($transaction:expr, $comment_id:expr, $member_id:expr) => {{
+        let author: i32 = sqlx::query_scalar("SELECT MemberID FROM TaskComment WHERE CommentID = ?")
+            .bind($comment_id)
+            .fetch_one(&mut *$transaction)
+            .await?;
+        author == $member_id
+    }};
+}
+
+/// Changes the body of a comment and stamps it as edited. Only the author can edit a comment;
+/// for anyone else nothing is written and `false` is returned.
+pub async fn update_comment(
+    db: &Db,
+    comment_id: i32,
+    member_id: i32,
+    body: &str,
+) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if !is_comment_author!(transaction, comment_id, member_id) {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        let old_body: String = sqlx::query_scalar("SELECT Body FROM TaskComment WHERE CommentID = ?")
+            .bind(comment_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+
+        sqlx::query("UPDATE TaskComment SET Body = ?, editedAt = ? WHERE CommentID = ?")
+            .bind(body)
+            .bind(chrono::Local::now().naive_local())
+            .bind(comment_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        record_changes!(
+            transaction,
+            Change::updated(
+                RecordKind::Comment,
+                comment_id,
+                audit::comment_fields(&old_body),
+                audit::comment_fields(body),
+            )
+        );
+
+        transaction.commit().await?;
+
+        Ok(true)
+    })
+}
+
+/// Deletes a comment. Only the author can delete a comment; for anyone else nothing is
+/// written and `false` is returned.
+pub async fn delete_comment_by_id(db: &Db, comment_id: i32, member_id: i32) -> Result<bool, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        if !is_comment_author!(transaction, comment_id, member_id) {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        let body: String = sqlx::query_scalar("SELECT Body FROM TaskComment WHERE CommentID = ?")
+            .bind(comment_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        record_changes!(
+            transaction,
+            Change::deleted(RecordKind::Comment, comment_id, audit::comment_fields(&body))
+        );
+
+        sqlx::query("DELETE FROM TaskComment WHERE CommentID = ?")
+            .bind(comment_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(true)
+    })
+}
+
+/// The statements moving each kind of record to the trash and taking it back out, given the table
+/// and ID column it lives in. Spelled out per kind, so every statement is a string literal.
+/// `None` for comments, which never go to the trash.
+fn trash_statements(kind: RecordKind) -> Option<(&'static str, &'static str)> {
+    macro_rules! statements {
+        ($table:literal, $id_column:literal) => {
+            Some((
+                concat!(
+                    "UPDATE ",
+                    $table,
+                    " SET DeletedAt = ? WHERE ",
+                    $id_column,
+                    " = ? AND DeletedAt IS NULL"
+                ),
+                concat!(
+                    "UPDATE ",
+                    $table,
+                    " SET DeletedAt = NULL WHERE ",
+                    $id_column,
+                    " = ? AND DeletedAt IS NOT NULL"
+                ),
+            ))
+        };
+    }
+
+    match kind {
+        RecordKind::Project => statements!("Project", "ProjectID"),
+        RecordKind::Sprint => statements!("Sprint", "SprintID"),
+        RecordKind::Task => statements!("Task", "TaskID"),
+        RecordKind::Member => statements!("Member", "MemberID"),
+        RecordKind::ChecklistItem => statements!("ChecklistItem", "ItemID"),
+        RecordKind::Comment => None,
+    }
+}
+
+/// Moves a record to the trash inside the already open `$transaction`.
+/// Returns `RowNotFound` from the enclosing function if the record does not exist or already is in the trash.
+macro_rules! move_to_trash {
+    ($transaction:expr, $kind:expr, $id:expr) => {{
+        let Some((statement, _)) = trash_statements($kind) else {
+            return Err(sqlx::Error::RowNotFound);
+        };
+
+        let trashed = sqlx::query(statement)
+            .bind(chrono::Local::now().naive_local())
+            .bind($id)
+            .execute(&mut *$transaction)
+            .await?
+            .rows_affected();
+
+        if trashed == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        record_changes!($transaction, [Change::record($kind, $id, Action::Trash)]);
+    }};
+}
+
+/// Stamps a record with the time it was moved to the trash, which hides it from every fetch.
+/// Records under it (the sprints of a project, the tasks of a sprint) are hidden along with it, as they are only
+/// reached through it. Fails with `RowNotFound` if the record does not exist or already is in the trash.
+pub async fn move_to_trash(db: &Db, kind: RecordKind, id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        move_to_trash!(transaction, kind, id);
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Takes a record out of the trash inside the already open `$transaction`.
+/// Returns `RowNotFound` from the enclosing function if the record is not in the trash.
+macro_rules! restore_from_trash {
+    ($transaction:expr, $kind:expr, $id:expr) => {{
+        let Some((_, statement)) = trash_statements($kind) else {
+            return Err(sqlx::Error::RowNotFound);
+        };
+
+        let restored = sqlx::query(statement)
+            .bind($id)
+            .execute(&mut *$transaction)
+            .await?
+            .rows_affected();
+
+        if restored == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        record_changes!($transaction, [Change::record($kind, $id, Action::Restore)]);
+    }};
+}
+
+/// Takes a record out of the trash. Fails with `RowNotFound` if it is not in the trash.
+pub async fn restore_from_trash(db: &Db, kind: RecordKind, id: i32) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        restore_from_trash!(transaction, kind, id);
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
+
+/// Fetches every record in the trash, most recently deleted first.
+pub async fn fetch_trash(db: &Db) -> Result<Vec<TrashedRecord>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut trash = Vec::new();
+
+        for (kind, statement) in [
+            (RecordKind::Project, "SELECT ProjectID, Title, DeletedAt FROM Project WHERE DeletedAt IS NOT NULL"),
+            (RecordKind::Sprint, "SELECT SprintID, Title, DeletedAt FROM Sprint WHERE DeletedAt IS NOT NULL"),
+            (RecordKind::Task, "SELECT TaskID, Title, DeletedAt FROM Task WHERE DeletedAt IS NOT NULL"),
+            (
+                RecordKind::ChecklistItem,
+                "SELECT ItemID, Title, DeletedAt FROM ChecklistItem WHERE DeletedAt IS NOT NULL",
+            ),
+        ] {
+            for (id, title, deleted_at) in sqlx::query_as::<_, (i32, String, chrono::NaiveDateTime)>(statement)
+                .fetch_all(pool)
+                .await?
+            {
+                trash.push(TrashedRecord {
+                    kind,
+                    id,
+                    title,
+                    deleted_at,
+                });
+            }
+        }
+
+        for (id, first_name, last_name, deleted_at) in
+            sqlx::query_as::<_, (i32, String, String, chrono::NaiveDateTime)>(
+                "SELECT MemberID, firstName, lastName, DeletedAt FROM Member WHERE DeletedAt IS NOT NULL",
+            )
+            .fetch_all(pool)
+            .await?
+        {
+            trash.push(TrashedRecord {
+                kind: RecordKind::Member,
+                id,
+                title: format!("{} {}", first_name, last_name),
+                deleted_at,
+            });
+        }
+
+        trash.sort_by_key(|record| std::cmp::Reverse(record.deleted_at));
+        Ok(trash)
+    })
+}
+
+/// Fetches the audit log of a record, newest changes first.
+pub async fn fetch_history(
+    db: &Db,
+    kind: RecordKind,
+    id: i32,
+) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    with_pool!(db, pool => {
+        sqlx::query_as::<_, AuditEntry>(
+            "SELECT * FROM AuditLog WHERE Entity = ? AND EntityID = ? ORDER BY EntryID DESC",
+        )
+        .bind(kind.label())
+        .bind(id)
+        .fetch_all(pool)
+        .await
+    })
+}
+
+/// Fetches the ID of the latest audit log entry, or 0 if nothing has been recorded yet.
+pub async fn fetch_latest_change(db: &Db) -> Result<i32, sqlx::Error> {
+    with_pool!(db, pool => {
+        let entry_id: Option<i32> =
+            sqlx::query_scalar("SELECT EntryID FROM AuditLog ORDER BY EntryID DESC LIMIT 1")
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(entry_id.unwrap_or(0))
+    })
+}
+
+/// Fetches the audit log entries after `entry_id`, oldest first, with the kind and ID of the record each is about.
+pub async fn fetch_changes_since(
+    db: &Db,
+    entry_id: i32,
+) -> Result<Vec<(RecordKind, i32, AuditEntry)>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut changes = Vec::new();
+        for row in sqlx::query("SELECT * FROM AuditLog WHERE EntryID > ? ORDER BY EntryID")
+            .bind(entry_id)
+            .fetch_all(pool)
+            .await?
+        {
+            let entity: String = row.try_get("Entity")?;
+            if let Some(kind) = RecordKind::from_label(&entity) {
+                changes.push((kind, row.try_get("EntityID")?, AuditEntry::from_row(&row)?));
+            }
+        }
+
+        Ok(changes)
+    })
+}
+
+/// Why an update or [`apply_operations`] changed nothing, short of a database error.
+pub enum Refusal {
+    /// One of the records is no longer at the version the operation was made against.
+    Conflict,
+    /// A member outside the task's project was to be assigned to it.
+    Assignment,
+    /// A label of another project was to be put on a task.
+    Labeling,
+    /// A dependency to be removed does not exist (anymore).
+    NotFound,
+    /// The operation would break a rule of TATs, checked against the records as the transaction sees them.
+    Invalid(StorageError),
+}
+
+/// Checks `$operation` against the records as seen inside the already open `$transaction`, so the rules
+/// hold for what is actually written. Evaluates to the refusal of the first rule it would break.
+macro_rules! check_operation {
+    ($transaction:expr, $operation:expr) => {
+        match $operation {
+            Operation::UpdateSprint {
+                sprint_id,
+                start_date,
+                end_date,
+                ..
+            } => check_sprint_update!(&mut *$transaction, *sprint_id, *start_date, *end_date),
+            Operation::UpdateTask {
+                task_id,
+                status,
+                priority,
+                due_date,
+                ..
+            } => check_task_update!(&mut *$transaction, *task_id, status, *priority, *due_date),
+            Operation::MoveTask {
+                task_id,
+                sprint_id,
+                due_date,
+                ..
+            } => check_task_move!(&mut *$transaction, *task_id, *sprint_id, *due_date),
+            Operation::AddTaskDependency {
+                blocker_id,
+                blocked_id,
+            } => check_new_dependency!(&mut *$transaction, *blocker_id, *blocked_id),
+            Operation::Trash {
+                kind: RecordKind::Sprint,
+                id,
+            } => check_not_backlog(&fetch_sprint!(&mut *$transaction, *id)),
+            _ => Ok(()),
+        }
+        .err()
+        .map(Refusal::Invalid)
+    };
+}
+
+/// Applies the operations in order, in one transaction, so either all of them or none take effect.
+/// Each operation is checked right before it is applied, seeing the effect of the ones before it.
+pub async fn apply_operations(
+    db: &Db,
+    operations: &[Operation],
+) -> Result<Result<(), Refusal>, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        for operation in operations {
+            if let Some(refusal) = check_operation!(transaction, operation) {
+                transaction.rollback().await?;
+                return Ok(Err(refusal));
+            }
+
+            let refusal = match operation {
+                Operation::UpdateProject { project_id, version, title, description } => {
+                    (!update_project!(transaction, *project_id, *version, title.as_str(), description.as_str()))
+                        .then_some(Refusal::Conflict)
+                }
+                Operation::UpdateSprint { sprint_id, version, title, start_date, end_date } => {
+                    (!update_sprint!(transaction, *sprint_id, *version, title.as_str(), *start_date, *end_date))
+                        .then_some(Refusal::Conflict)
+                }
+                Operation::UpdateTask { task_id, version, title, status, description, estimated_hours, priority, due_date } => {
+                    (!update_task!(
+                        transaction,
+                        *task_id,
+                        *version,
+                        title.as_str(),
+                        status.as_str(),
+                        description.as_str(),
+                        *estimated_hours,
+                        *priority,
+                        *due_date
+                    ))
+                    .then_some(Refusal::Conflict)
+                }
+                Operation::MoveTask { task_id, version, sprint_id, due_date } => {
+                    (!move_task!(transaction, *task_id, *version, *sprint_id, *due_date))
+                        .then_some(Refusal::Conflict)
+                }
+                Operation::SetTaskAssignees { task_id, member_ids } => {
+                    (!set_task_assignees!(transaction, *task_id, member_ids.as_slice()))
+                        .then_some(Refusal::Assignment)
+                }
+                Operation::SetTaskLabels { task_id, label_ids } => {
+                    (!set_task_labels!(transaction, *task_id, label_ids.as_slice()))
+                        .then_some(Refusal::Labeling)
+                }
+                Operation::UpdateChecklistItem { item_id, version, title, done } => {
+                    (!update_checklist_item!(transaction, *item_id, *version, title.as_str(), *done))
+                        .then_some(Refusal::Conflict)
+                }
+                Operation::AddTaskDependency { blocker_id, blocked_id } => {
+                    add_task_dependency!(transaction, *blocker_id, *blocked_id);
+                    None
+                }
+                Operation::RemoveTaskDependency { blocker_id, blocked_id } => {
+                    (!remove_task_dependency!(transaction, *blocker_id, *blocked_id))
+                        .then_some(Refusal::NotFound)
+                }
+                Operation::AddProjectMember { member_id, project_id } => {
+                    add_project_member!(transaction, *member_id, *project_id);
+                    None
+                }
+                Operation::RemoveProjectMember { member_id, project_id } => {
+                    remove_project_member!(transaction, *member_id, *project_id);
+                    None
+                }
+                Operation::Trash { kind, id } => {
+                    move_to_trash!(transaction, *kind, *id);
+                    None
+                }
+                Operation::Restore { kind, id } => {
+                    restore_from_trash!(transaction, *kind, *id);
+                    None
+                }
+            };
+
+            if let Some(refusal) = refusal {
+                transaction.rollback().await?;
+                return Ok(Err(refusal));
+            }
+        }
+
+        transaction.commit().await?;
 
-    Ok(())
+        Ok(Ok(()))
+    })
 }
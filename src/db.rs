@@ -0,0 +1,338 @@
+use std::str::FromStr;
+
+use sqlx::{
+    database::HasArguments,
+    migrate::{Migrate, Migrator},
+    mysql::{MySqlArguments, MySqlPoolOptions},
+    postgres::{PgArguments, PgPoolOptions},
+    query::Query,
+    sqlite::{SqliteArguments, SqliteConnectOptions, SqlitePoolOptions},
+    Execute, Executor, MySql, MySqlPool, PgPool, Postgres, Sqlite, SqlitePool,
+};
+
+/// Versioned schema migrations, embedded into the binary for each backend.
+static MYSQL_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/mysql");
+static SQLITE_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/sqlite");
+static POSTGRES_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// A connection pool to one of the database backends TATs can run against.
+/// The backend is selected by the scheme of the connection url.
+#[derive(Clone, Debug)]
+pub enum Db {
+    /// A MariaDB/MySQL server, selected by `mariadb://` or `mysql://` urls.
+    MySql(MySqlPool),
+    /// An embedded SQLite file, selected by `sqlite://` urls.
+    Sqlite(SqlitePool),
+    /// A PostgreSQL server, selected by `postgres://` or `postgresql://` urls.
+    Postgres(PgPool),
+}
+
+impl Db {
+    /// Connects to the database at `url` and brings its schema up to date.
+    /// SQLite files are created if they do not exist yet.
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        let db = if url.starts_with("sqlite:") {
+            let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+            Db::Sqlite(SqlitePoolOptions::new().connect_with(options).await?)
+        } else if url.starts_with("postgres:") || url.starts_with("postgresql:") {
+            Db::Postgres(PgPoolOptions::new().connect(url).await?)
+        } else {
+            Db::MySql(MySqlPoolOptions::new().connect(url).await?)
+        };
+
+        db.migrate().await?;
+        Ok(db)
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        match self {
+            Db::MySql(_) => &MYSQL_MIGRATIONS,
+            Db::Sqlite(_) => &SQLITE_MIGRATIONS,
+            Db::Postgres(_) => &POSTGRES_MIGRATIONS,
+        }
+    }
+
+    /// The newest schema version this build of TATs knows how to use.
+    pub fn schema_version(&self) -> i64 {
+        self.migrator().iter().map(|m| m.version).max().unwrap_or(0)
+    }
+
+    /// Applies any pending migrations.
+    /// Refuses to touch a database whose schema was migrated by a newer build of TATs.
+    async fn migrate(&self) -> Result<(), sqlx::Error> {
+        let migrator = self.migrator();
+        let supported = self.schema_version();
+
+        with_pool!(self, pool => {
+            let mut conn = pool.acquire().await?;
+            conn.ensure_migrations_table().await?;
+
+            let applied = conn.list_applied_migrations().await?;
+            if let Some(found) = applied.iter().map(|m| m.version).max() {
+                if found > supported {
+                    return Err(sqlx::Error::Configuration(
+                        format!(
+                            "the database schema is at version {}, but this build of TATs only supports up to version {}. Please upgrade TATs.",
+                            found, supported
+                        )
+                        .into(),
+                    ));
+                }
+            }
+
+            migrator.run(&mut *conn).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Runs an `INSERT` and returns the id it generated, regardless of backend.
+/// MySQL and SQLite report the id of the last insert; Postgres hands it back through `RETURNING`,
+/// which is why the name of the id column is needed.
+pub async fn insert_returning_id<'q, 'c, DB, E>(
+    query: Query<'q, DB, <DB as HasArguments<'q>>::Arguments>,
+    executor: E,
+    id_column: &'static str,
+) -> Result<i32, sqlx::Error>
+where
+    DB: ReturningId,
+    E: Executor<'c, Database = DB>,
+{
+    DB::execute_returning_id(query, executor, id_column).await
+}
+
+/// How a backend reports the id an `INSERT` generated, see [`insert_returning_id`].
+#[allow(async_fn_in_trait)]
+pub trait ReturningId: sqlx::Database {
+    async fn execute_returning_id<'q, 'c, E>(
+        query: Query<'q, Self, <Self as HasArguments<'q>>::Arguments>,
+        executor: E,
+        id_column: &'static str,
+    ) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'c, Database = Self>;
+}
+
+impl ReturningId for MySql {
+    async fn execute_returning_id<'q, 'c, E>(
+        query: Query<'q, MySql, MySqlArguments>,
+        executor: E,
+        _id_column: &'static str,
+    ) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'c, Database = MySql>,
+    {
+        Ok(query.execute(executor).await?.last_insert_id() as i32)
+    }
+}
+
+impl ReturningId for Sqlite {
+    async fn execute_returning_id<'q, 'c, E>(
+        query: Query<'q, Sqlite, SqliteArguments<'q>>,
+        executor: E,
+        _id_column: &'static str,
+    ) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'c, Database = Sqlite>,
+    {
+        Ok(query.execute(executor).await?.last_insert_rowid() as i32)
+    }
+}
+
+impl ReturningId for Postgres {
+    async fn execute_returning_id<'q, 'c, E>(
+        mut query: Query<'q, Postgres, PgArguments>,
+        executor: E,
+        id_column: &'static str,
+    ) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'c, Database = Postgres>,
+    {
+        // The statement was already translated, so only the added column still needs quoting.
+        let sql = format!(
+            "{} RETURNING {}",
+            query.sql(),
+            postgres::translate(id_column)
+        );
+        let arguments = query.take_arguments().unwrap_or_default();
+        sqlx::query_scalar_with(&sql, arguments)
+            .fetch_one(executor)
+            .await
+    }
+}
+
+/// Stand-ins for the `sqlx` query functions, used in place of them for Postgres by [`with_pool`].
+/// Queries are written once, in the dialect MySQL and SQLite share, and translated here for Postgres:
+/// `?` placeholders become numbered ones, and identifiers in mixed case are quoted so Postgres does not
+/// fold them to lower case. Keywords are written in upper case and plain lower case names need no quotes.
+/// Only `'static` strings are accepted, which keeps queries to the ones written into TATs.
+pub mod postgres {
+    use std::{collections::BTreeMap, sync::Mutex};
+
+    use sqlx::{
+        database::HasArguments,
+        query::{Query, QueryAs, QueryScalar},
+        Database, FromRow,
+    };
+
+    pub use sqlx::*;
+
+    /// Every query is translated once and kept for the rest of the run.
+    /// Queries are `'static` strings written into TATs, so there is at most one translation per query in the source.
+    static TRANSLATED: Mutex<BTreeMap<&'static str, &'static str>> = Mutex::new(BTreeMap::new());
+
+    pub fn query<'q, DB>(sql: &'static str) -> Query<'q, DB, <DB as HasArguments<'q>>::Arguments>
+    where
+        DB: Database,
+    {
+        sqlx::query(translate(sql))
+    }
+
+    pub fn query_as<'q, DB, O>(
+        sql: &'static str,
+    ) -> QueryAs<'q, DB, O, <DB as HasArguments<'q>>::Arguments>
+    where
+        DB: Database,
+        O: for<'r> FromRow<'r, DB::Row>,
+    {
+        sqlx::query_as(translate(sql))
+    }
+
+    pub fn query_scalar<'q, DB, O>(
+        sql: &'static str,
+    ) -> QueryScalar<'q, DB, O, <DB as HasArguments<'q>>::Arguments>
+    where
+        DB: Database,
+        (O,): for<'r> FromRow<'r, DB::Row>,
+    {
+        sqlx::query_scalar(translate(sql))
+    }
+
+    pub(super) fn translate(sql: &'static str) -> &'static str {
+        let mut translated = TRANSLATED.lock().unwrap();
+        if let Some(translation) = translated.get(sql) {
+            return translation;
+        }
+        let translation: &'static str = Box::leak(translate_uncached(sql).into_boxed_str());
+        translated.insert(sql, translation);
+        translation
+    }
+
+    fn translate_uncached(sql: &str) -> String {
+        let mut translation = String::with_capacity(sql.len() + 16);
+        let mut placeholders = 0;
+        let mut chars = sql.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                // String literals and quoted names are copied as they are;
+                // a doubled quote simply ends and reopens one.
+                '\'' | '"' => {
+                    translation.push(c);
+                    for quoted in chars.by_ref() {
+                        translation.push(quoted);
+                        if quoted == c {
+                            break;
+                        }
+                    }
+                }
+                '?' => {
+                    placeholders += 1;
+                    translation.push_str(&format!("${}", placeholders));
+                }
+                c if c.is_ascii_alphanumeric() || c == '_' => {
+                    let mut word = String::from(c);
+                    while let Some(&c) = chars.peek() {
+                        if !(c.is_ascii_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        word.push(c);
+                        chars.next();
+                    }
+                    let mixed_case = word.chars().any(|c| c.is_ascii_uppercase())
+                        && word.chars().any(|c| c.is_ascii_lowercase());
+                    if mixed_case {
+                        translation.push_str(&format!("\"{}\"", word));
+                    } else {
+                        translation.push_str(&word);
+                    }
+                }
+                c => translation.push(c),
+            }
+        }
+        translation
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::translate_uncached;
+
+        #[test]
+        fn numbers_placeholders_in_order() {
+            assert_eq!(
+                translate_uncached("DELETE FROM PartOf WHERE TaskID = ? AND SprintID = ?"),
+                "DELETE FROM \"PartOf\" WHERE \"TaskID\" = $1 AND \"SprintID\" = $2"
+            );
+        }
+
+        #[test]
+        fn quotes_mixed_case_names_only() {
+            assert_eq!(
+                translate_uncached("SELECT Task.dueDate, name FROM Task WHERE Version > 0"),
+                "SELECT \"Task\".\"dueDate\", name FROM \"Task\" WHERE \"Version\" > 0"
+            );
+            assert_eq!(
+                translate_uncached("SELECT COUNT(*) FROM ProjectSprint"),
+                "SELECT COUNT(*) FROM \"ProjectSprint\""
+            );
+        }
+
+        #[test]
+        fn leaves_string_literals_alone() {
+            assert_eq!(
+                translate_uncached("UPDATE Task SET Status = 'Done?' WHERE TaskID = ?"),
+                "UPDATE \"Task\" SET \"Status\" = 'Done?' WHERE \"TaskID\" = $1"
+            );
+            assert_eq!(
+                translate_uncached("SELECT 'it''s NotStarted?' FROM Task WHERE Title = ?"),
+                "SELECT 'it''s NotStarted?' FROM \"Task\" WHERE \"Title\" = $1"
+            );
+        }
+
+        #[test]
+        fn leaves_quoted_names_alone() {
+            assert_eq!(
+                translate_uncached(
+                    "SELECT \"TaskID\", \"odd?Name\" FROM \"Task\" WHERE \"Title\" = ?"
+                ),
+                "SELECT \"TaskID\", \"odd?Name\" FROM \"Task\" WHERE \"Title\" = $1"
+            );
+        }
+
+        #[test]
+        fn keeps_names_with_digits_and_underscores_whole() {
+            assert_eq!(
+                translate_uncached("SELECT Member_2.firstName FROM Member AS Member_2 LIMIT 10"),
+                "SELECT \"Member_2\".\"firstName\" FROM \"Member\" AS \"Member_2\" LIMIT 10"
+            );
+        }
+    }
+}
+
+/// Evaluates `$body` with `$pool` bound to the concrete pool held by `$db`.
+/// The body is compiled once per backend, so plain `sqlx` queries can be used inside it.
+/// For Postgres, `sqlx` names [`postgres`] inside the body, which translates the queries on their way.
+macro_rules! with_pool {
+    ($db:expr, $pool:ident => $body:expr) => {
+        match $db {
+            $crate::db::Db::MySql($pool) => $body,
+            $crate::db::Db::Sqlite($pool) => $body,
+            $crate::db::Db::Postgres($pool) => {
+                #[allow(unused_imports)]
+                use $crate::db::postgres as sqlx;
+                $body
+            }
+        }
+    };
+}
+
+pub(crate) use with_pool;
@@ -0,0 +1,568 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::{
+    crud::{insert_backlog, insert_workflow},
+    db::{with_pool, Db},
+    models::Workflow,
+};
+
+/// Everything `tats doctor` found wrong with a database.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Sprints that are not linked to any project.
+    pub orphaned_sprints: Vec<i32>,
+    /// Tasks that are not linked to any sprint.
+    pub orphaned_tasks: Vec<i32>,
+    /// (ProjectID, SprintID) rows of ProjectSprint pointing at a missing project or sprint.
+    pub dangling_project_sprints: Vec<(i32, i32)>,
+    /// (TaskID, SprintID) rows of PartOf pointing at a missing task or sprint.
+    pub dangling_part_of: Vec<(i32, i32)>,
+    /// (MemberID, ProjectID) rows of ContributesTo pointing at a missing member or project.
+    pub dangling_contributes_to: Vec<(i32, i32)>,
+    /// (MemberID, TaskID) rows of AssignedTo pointing at a missing member or task.
+    pub dangling_assigned_to: Vec<(i32, i32)>,
+    /// Work log entries of a missing task or member.
+    pub dangling_work_log: Vec<i32>,
+    /// (BlockerID, BlockedID) rows of TaskDependency pointing at a missing task.
+    pub dangling_dependencies: Vec<(i32, i32)>,
+    /// Comments on a missing task or by a missing member.
+    pub dangling_comments: Vec<i32>,
+    /// Checklist items of a missing task.
+    pub dangling_checklist_items: Vec<i32>,
+    /// Labels of a missing project.
+    pub dangling_labels: Vec<i32>,
+    /// (TaskID, LabelID) rows of TaskLabel pointing at a missing task or label.
+    pub dangling_task_labels: Vec<(i32, i32)>,
+    /// Sprints whose end date lies before their start date.
+    pub inverted_sprints: Vec<(i32, NaiveDate, NaiveDate)>,
+    /// Tasks with negative committed or estimated hours.
+    pub negative_hours: Vec<(i32, i32, i32)>,
+    /// Tasks whose committed hours are not the sum of their work log.
+    pub unbalanced_work_log: Vec<i32>,
+    /// Projects without any task status, in which no task can be created.
+    pub projects_without_workflow: Vec<i32>,
+    /// Projects without a backlog linked to them, leaving their unscheduled tasks nowhere to go.
+    pub projects_without_backlog: Vec<i32>,
+    /// (TaskID, ProjectID, Status) of tasks in a status their project does not define.
+    pub undefined_task_statuses: Vec<(i32, i32, String)>,
+    /// (ProjectID, Name) rows of ProjectStatus pointing at a missing project.
+    pub dangling_project_statuses: Vec<(i32, String)>,
+    /// (ProjectID, FromStatus, ToStatus) rows of StatusTransition pointing at a missing project or status.
+    pub dangling_transitions: Vec<(i32, String, String)>,
+}
+
+impl Report {
+    pub fn is_healthy(&self) -> bool {
+        self.problem_count() == 0
+    }
+
+    pub fn problem_count(&self) -> usize {
+        self.orphaned_sprints.len()
+            + self.orphaned_tasks.len()
+            + self.dangling_project_sprints.len()
+            + self.dangling_part_of.len()
+            + self.dangling_contributes_to.len()
+            + self.dangling_assigned_to.len()
+            + self.dangling_work_log.len()
+            + self.dangling_dependencies.len()
+            + self.dangling_comments.len()
+            + self.dangling_checklist_items.len()
+            + self.dangling_labels.len()
+            + self.dangling_task_labels.len()
+            + self.inverted_sprints.len()
+            + self.negative_hours.len()
+            + self.unbalanced_work_log.len()
+            + self.projects_without_workflow.len()
+            + self.projects_without_backlog.len()
+            + self.undefined_task_statuses.len()
+            + self.dangling_project_statuses.len()
+            + self.dangling_transitions.len()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_healthy() {
+            return writeln!(f, "No problems found.");
+        }
+
+        for sprint_id in &self.orphaned_sprints {
+            writeln!(f, "Sprint #{} does not belong to any project.", sprint_id)?;
+        }
+        for task_id in &self.orphaned_tasks {
+            writeln!(f, "Task #{} does not belong to any sprint.", task_id)?;
+        }
+        for (project_id, sprint_id) in &self.dangling_project_sprints {
+            writeln!(
+                f,
+                "ProjectSprint links project #{} and sprint #{}, but one of them does not exist.",
+                project_id, sprint_id
+            )?;
+        }
+        for (task_id, sprint_id) in &self.dangling_part_of {
+            writeln!(
+                f,
+                "PartOf links task #{} and sprint #{}, but one of them does not exist.",
+                task_id, sprint_id
+            )?;
+        }
+        for (member_id, project_id) in &self.dangling_contributes_to {
+            writeln!(
+                f,
+                "ContributesTo links member #{} and project #{}, but one of them does not exist.",
+                member_id, project_id
+            )?;
+        }
+        for (member_id, task_id) in &self.dangling_assigned_to {
+            writeln!(
+                f,
+                "AssignedTo links member #{} and task #{}, but one of them does not exist.",
+                member_id, task_id
+            )?;
+        }
+        for entry_id in &self.dangling_work_log {
+            writeln!(
+                f,
+                "Work log entry #{} belongs to a task or member that does not exist.",
+                entry_id
+            )?;
+        }
+        for (blocker_id, blocked_id) in &self.dangling_dependencies {
+            writeln!(
+                f,
+                "TaskDependency has task #{} block task #{}, but one of them does not exist.",
+                blocker_id, blocked_id
+            )?;
+        }
+        for comment_id in &self.dangling_comments {
+            writeln!(
+                f,
+                "Comment #{} belongs to a task or member that does not exist.",
+                comment_id
+            )?;
+        }
+        for item_id in &self.dangling_checklist_items {
+            writeln!(
+                f,
+                "Checklist item #{} belongs to a task that does not exist.",
+                item_id
+            )?;
+        }
+        for label_id in &self.dangling_labels {
+            writeln!(f, "Label #{} belongs to a project that does not exist.", label_id)?;
+        }
+        for (task_id, label_id) in &self.dangling_task_labels {
+            writeln!(
+                f,
+                "TaskLabel puts label #{} on task #{}, but one of them does not exist.",
+                label_id, task_id
+            )?;
+        }
+        for (sprint_id, start_date, end_date) in &self.inverted_sprints {
+            writeln!(
+                f,
+                "Sprint #{} ends ({}) before it starts ({}).",
+                sprint_id, end_date, start_date
+            )?;
+        }
+        for (task_id, commited_hours, estimated_hours) in &self.negative_hours {
+            writeln!(
+                f,
+                "Task #{} has negative hours ({}h estimated, {}h completed).",
+                task_id, estimated_hours, commited_hours
+            )?;
+        }
+        for task_id in &self.unbalanced_work_log {
+            writeln!(
+                f,
+                "Task #{} has completed hours that do not add up to its work log.",
+                task_id
+            )?;
+        }
+        for project_id in &self.projects_without_workflow {
+            writeln!(f, "Project #{} does not define any task status.", project_id)?;
+        }
+        for project_id in &self.projects_without_backlog {
+            writeln!(f, "Project #{} does not have a backlog.", project_id)?;
+        }
+        for (task_id, project_id, status) in &self.undefined_task_statuses {
+            writeln!(
+                f,
+                "Task #{} is in status '{}', which project #{} does not define.",
+                task_id, status, project_id
+            )?;
+        }
+        for (project_id, name) in &self.dangling_project_statuses {
+            writeln!(
+                f,
+                "Status '{}' belongs to project #{}, which does not exist.",
+                name, project_id
+            )?;
+        }
+        for (project_id, from, to) in &self.dangling_transitions {
+            writeln!(
+                f,
+                "Transition from '{}' to '{}' of project #{} refers to a project or status that does not exist.",
+                from, to, project_id
+            )?;
+        }
+
+        writeln!(f, "{} problem(s) found.", self.problem_count())
+    }
+}
+
+/// Matches StatusTransition rows whose project or statuses do not exist.
+/// A macro rather than a constant, so the queries using it can be put together with `concat!`.
+macro_rules! dangling_transition {
+    () => {
+        "ProjectID NOT IN (SELECT ProjectID FROM Project)
+    OR NOT EXISTS (SELECT 1 FROM ProjectStatus
+        WHERE ProjectStatus.ProjectID = StatusTransition.ProjectID AND ProjectStatus.Name = FromStatus)
+    OR NOT EXISTS (SELECT 1 FROM ProjectStatus
+        WHERE ProjectStatus.ProjectID = StatusTransition.ProjectID AND ProjectStatus.Name = ToStatus)"
+    };
+}
+
+/// Scans the database for orphaned records, dangling link rows and invalid values.
+pub async fn check(db: &Db) -> Result<Report, sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut report = Report {
+            orphaned_sprints: sqlx::query_scalar(
+                "SELECT SprintID FROM Sprint WHERE SprintID NOT IN (SELECT SprintID FROM ProjectSprint)",
+            )
+            .fetch_all(pool)
+            .await?,
+            ..Default::default()
+        };
+
+        report.orphaned_tasks = sqlx::query_scalar(
+            "SELECT TaskID FROM Task WHERE TaskID NOT IN (SELECT TaskID FROM PartOf)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_project_sprints = sqlx::query_as(
+            "SELECT ProjectID, SprintID FROM ProjectSprint
+             WHERE ProjectID NOT IN (SELECT ProjectID FROM Project)
+             OR SprintID NOT IN (SELECT SprintID FROM Sprint)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_part_of = sqlx::query_as(
+            "SELECT TaskID, SprintID FROM PartOf
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR SprintID NOT IN (SELECT SprintID FROM Sprint)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_contributes_to = sqlx::query_as(
+            "SELECT MemberID, ProjectID FROM ContributesTo
+             WHERE MemberID NOT IN (SELECT MemberID FROM Member)
+             OR ProjectID NOT IN (SELECT ProjectID FROM Project)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_assigned_to = sqlx::query_as(
+            "SELECT MemberID, TaskID FROM AssignedTo
+             WHERE MemberID NOT IN (SELECT MemberID FROM Member)
+             OR TaskID NOT IN (SELECT TaskID FROM Task)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_work_log = sqlx::query_scalar(
+            "SELECT EntryID FROM WorkLog
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR MemberID NOT IN (SELECT MemberID FROM Member)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_dependencies = sqlx::query_as(
+            "SELECT BlockerID, BlockedID FROM TaskDependency
+             WHERE BlockerID NOT IN (SELECT TaskID FROM Task)
+             OR BlockedID NOT IN (SELECT TaskID FROM Task)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_comments = sqlx::query_scalar(
+            "SELECT CommentID FROM TaskComment
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR MemberID NOT IN (SELECT MemberID FROM Member)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_checklist_items = sqlx::query_scalar(
+            "SELECT ItemID FROM ChecklistItem WHERE TaskID NOT IN (SELECT TaskID FROM Task)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_labels = sqlx::query_scalar(
+            "SELECT LabelID FROM Label WHERE ProjectID NOT IN (SELECT ProjectID FROM Project)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_task_labels = sqlx::query_as(
+            "SELECT TaskID, LabelID FROM TaskLabel
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR LabelID NOT IN (SELECT LabelID FROM Label)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.inverted_sprints =
+            sqlx::query_as("SELECT SprintID, startDate, endDate FROM Sprint WHERE endDate < startDate")
+                .fetch_all(pool)
+                .await?;
+
+        report.negative_hours = sqlx::query_as(
+            "SELECT TaskID, commitedHours, estimatedHours FROM Task
+             WHERE commitedHours < 0 OR estimatedHours < 0",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.unbalanced_work_log = sqlx::query_scalar(
+            "SELECT TaskID FROM Task WHERE commitedHours <>
+             (SELECT COALESCE(SUM(hours), 0) FROM WorkLog WHERE WorkLog.TaskID = Task.TaskID)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.projects_without_workflow = sqlx::query_scalar(
+            "SELECT ProjectID FROM Project WHERE ProjectID NOT IN (SELECT ProjectID FROM ProjectStatus)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.projects_without_backlog = sqlx::query_scalar(
+            "SELECT ProjectID FROM Project WHERE ProjectID NOT IN
+             (SELECT ProjectSprint.ProjectID FROM ProjectSprint
+              INNER JOIN Sprint ON ProjectSprint.SprintID = Sprint.SprintID
+              WHERE Sprint.BacklogOf = ProjectSprint.ProjectID)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.undefined_task_statuses = sqlx::query_as(
+            "SELECT Task.TaskID, ProjectSprint.ProjectID, Task.Status FROM Task
+             INNER JOIN PartOf ON Task.TaskID = PartOf.TaskID
+             INNER JOIN ProjectSprint ON PartOf.SprintID = ProjectSprint.SprintID
+             WHERE NOT EXISTS (SELECT 1 FROM ProjectStatus
+                 WHERE ProjectStatus.ProjectID = ProjectSprint.ProjectID AND ProjectStatus.Name = Task.Status)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_project_statuses = sqlx::query_as(
+            "SELECT ProjectID, Name FROM ProjectStatus WHERE ProjectID NOT IN (SELECT ProjectID FROM Project)",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        report.dangling_transitions = sqlx::query_as(concat!(
+            "SELECT ProjectID, FromStatus, ToStatus FROM StatusTransition WHERE ",
+            dangling_transition!()
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(report)
+    })
+}
+
+/// Fixes every problem in `report` inside a single transaction.
+/// Dangling links and orphans are deleted, inverted sprint dates are swapped, negative estimates are reset to 0
+/// and committed hours are recomputed from the work log. Projects without statuses get the default workflow,
+/// projects without a backlog get an empty one, and tasks in an undefined status are moved to the first status
+/// of their project.
+pub async fn repair(db: &Db, report: &Report) -> Result<(), sqlx::Error> {
+    with_pool!(db, pool => {
+        let mut transaction = pool.begin().await?;
+
+        // Links first, so that records which were only held by a dangling link show up as orphans below.
+        sqlx::query(
+            "DELETE FROM ProjectSprint
+             WHERE ProjectID NOT IN (SELECT ProjectID FROM Project)
+             OR SprintID NOT IN (SELECT SprintID FROM Sprint)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM PartOf
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR SprintID NOT IN (SELECT SprintID FROM Sprint)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM ContributesTo
+             WHERE MemberID NOT IN (SELECT MemberID FROM Member)
+             OR ProjectID NOT IN (SELECT ProjectID FROM Project)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM AssignedTo
+             WHERE MemberID NOT IN (SELECT MemberID FROM Member)
+             OR TaskID NOT IN (SELECT TaskID FROM Task)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM WorkLog
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR MemberID NOT IN (SELECT MemberID FROM Member)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM TaskDependency
+             WHERE BlockerID NOT IN (SELECT TaskID FROM Task)
+             OR BlockedID NOT IN (SELECT TaskID FROM Task)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM TaskComment
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR MemberID NOT IN (SELECT MemberID FROM Member)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query("DELETE FROM ChecklistItem WHERE TaskID NOT IN (SELECT TaskID FROM Task)")
+            .execute(&mut *transaction)
+            .await?;
+
+        // Labels of missing projects go along with the rows putting them on tasks.
+        sqlx::query(
+            "DELETE FROM TaskLabel
+             WHERE TaskID NOT IN (SELECT TaskID FROM Task)
+             OR LabelID NOT IN (SELECT LabelID FROM Label WHERE ProjectID IN (SELECT ProjectID FROM Project))",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query("DELETE FROM Label WHERE ProjectID NOT IN (SELECT ProjectID FROM Project)")
+            .execute(&mut *transaction)
+            .await?;
+
+        // Transitions before statuses, as they reference them.
+        sqlx::query(concat!("DELETE FROM StatusTransition WHERE ", dangling_transition!()))
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM ProjectStatus WHERE ProjectID NOT IN (SELECT ProjectID FROM Project)")
+            .execute(&mut *transaction)
+            .await?;
+
+        // Orphaned sprints take their tasks with them, which then get removed as orphaned tasks.
+        sqlx::query("DELETE FROM PartOf WHERE SprintID NOT IN (SELECT SprintID FROM ProjectSprint)")
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM Sprint WHERE SprintID NOT IN (SELECT SprintID FROM ProjectSprint)")
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM AssignedTo WHERE TaskID NOT IN (SELECT TaskID FROM PartOf)")
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM WorkLog WHERE TaskID NOT IN (SELECT TaskID FROM PartOf)")
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query(
+            "DELETE FROM TaskDependency
+             WHERE BlockerID NOT IN (SELECT TaskID FROM PartOf)
+             OR BlockedID NOT IN (SELECT TaskID FROM PartOf)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query("DELETE FROM TaskComment WHERE TaskID NOT IN (SELECT TaskID FROM PartOf)")
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM ChecklistItem WHERE TaskID NOT IN (SELECT TaskID FROM PartOf)")
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM TaskLabel WHERE TaskID NOT IN (SELECT TaskID FROM PartOf)")
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM Task WHERE TaskID NOT IN (SELECT TaskID FROM PartOf)")
+            .execute(&mut *transaction)
+            .await?;
+
+        for (sprint_id, start_date, end_date) in &report.inverted_sprints {
+            sqlx::query("UPDATE Sprint SET startDate = ?, endDate = ?, Version = Version + 1 WHERE SprintID = ?")
+                .bind(end_date)
+                .bind(start_date)
+                .bind(sprint_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        sqlx::query("UPDATE Task SET estimatedHours = 0, Version = Version + 1 WHERE estimatedHours < 0")
+            .execute(&mut *transaction)
+            .await?;
+
+        // Committed hours are the rollup of the work log, which also takes care of negative ones.
+        sqlx::query(
+            "UPDATE Task SET commitedHours =
+             (SELECT COALESCE(SUM(hours), 0) FROM WorkLog WHERE WorkLog.TaskID = Task.TaskID)",
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        for project_id in &report.projects_without_workflow {
+            insert_workflow!(transaction, project_id, Workflow::default());
+        }
+
+        // A backlog left without its link to the project went along with the orphaned sprints above,
+        // so the new one does not clash with it.
+        let today = chrono::Local::now().date_naive();
+        for project_id in &report.projects_without_backlog {
+            insert_backlog!(transaction, project_id, today);
+        }
+
+        // Tasks whose status came back with the default workflow above are left alone.
+        for (task_id, project_id, _) in &report.undefined_task_statuses {
+            sqlx::query(
+                "UPDATE Task SET Status =
+                 (SELECT Name FROM ProjectStatus WHERE ProjectID = ? ORDER BY Position LIMIT 1),
+                 Version = Version + 1
+                 WHERE TaskID = ?
+                 AND NOT EXISTS (SELECT 1 FROM ProjectStatus WHERE ProjectID = ? AND Name = Task.Status)",
+            )
+            .bind(project_id)
+            .bind(task_id)
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    })
+}
@@ -2,7 +2,6 @@ use std::{error::Error, io::stdout, panic};
 
 use dotenv::dotenv;
 use log::LevelFilter;
-use sqlx::mysql::MySqlPoolOptions;
 
 use crossterm::{
     cursor::MoveTo,
@@ -12,9 +11,16 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 
+mod audit;
 mod crud;
+mod db;
+mod doctor;
+#[cfg(test)]
+mod memory;
 mod models;
+mod storage;
 mod ui;
+mod undo;
 
 use models::*;
 
@@ -56,13 +62,56 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     dotenv().ok();
 
-    let db_url = std::env::args().nth(1).expect("You must provide the db url as a cli argument. Example: [mariadb://user:password@ip:port/tats]");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let migrate_only = args.iter().any(|arg| arg == "--migrate-only");
+    let repair = args.iter().any(|arg| arg == "--repair");
+    let mut positional = args.iter().filter(|arg| !arg.starts_with("--")).peekable();
+    let run_doctor = positional.next_if(|arg| *arg == "doctor").is_some();
+    let db_url = positional.next().expect("You must provide the db url as a cli argument. Example: [mariadb://user:password@ip:port/tats], [postgres://user:password@ip:port/tats] or [sqlite://tats.db]");
     println!("Url: {}", db_url);
-    let pool = MySqlPoolOptions::new()
-        .connect(db_url.as_str())
-        .await
-        .unwrap();
 
+    let db = match db::Db::connect(db_url.as_str()).await {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to open the database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if migrate_only {
+        println!("Database schema is up to date (version {}).", db.schema_version());
+        return Ok(());
+    }
+
+    if run_doctor {
+        return run_doctor_command(&db, repair).await;
+    }
+
+    run_app(&db).await
+}
+
+/// `tats doctor <url> [--repair]`: prints every integrity problem found in the database,
+/// and fixes them in a single transaction when `--repair` is passed.
+async fn run_doctor_command(db: &db::Db, repair: bool) -> Result<(), Box<dyn Error>> {
+    let report = doctor::check(db).await?;
+    print!("{}", report);
+
+    if report.is_healthy() {
+        return Ok(());
+    }
+
+    if repair {
+        doctor::repair(db, &report).await?;
+        println!("All problems have been repaired.");
+    } else {
+        println!("Run again with '--repair' to fix them.");
+    }
+
+    Ok(())
+}
+
+/// Takes over the terminal and runs the TUI against the provided storage until the user exits.
+async fn run_app(store: &impl storage::Storage) -> Result<(), Box<dyn Error>> {
     let terminal = init_terminal()?;
 
     setup_panic_hook();
@@ -70,6 +119,6 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // Clear the terminal at the start of the program
     execute!(stdout(), Clear(ClearType::All))?;
 
-    crate::ui::prelude::App::new().run(terminal, &pool).await?;
+    crate::ui::prelude::App::new().run(terminal, store).await?;
     Ok(())
 }
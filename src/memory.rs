@@ -0,0 +1,1489 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use chrono::{Local, NaiveDate};
+
+use crate::{
+    audit::{self, Action, Change},
+    models::*,
+    storage::{
+        check_comment_body, check_dependency, check_due_date, check_due_date_in, check_label_name,
+        check_logged_hours, check_move, check_not_backlog, check_priority, check_status_defined,
+        check_transition, check_workflow, not_comment_author, Storage, StorageError, StorageResult,
+    },
+    undo::Operation,
+};
+
+/// The tables of the in-memory store, laid out like their database counterparts.
+#[derive(Clone, Default)]
+struct Tables {
+    next_id: i32,
+    projects: Vec<RawProject>,
+    sprints: Vec<RawSprint>,
+    tasks: Vec<Task>,
+    members: Vec<Member>,
+    /// (ProjectID, SprintID)
+    project_sprint: Vec<(i32, i32)>,
+    /// (TaskID, SprintID)
+    part_of: Vec<(i32, i32)>,
+    /// (MemberID, ProjectID)
+    contributes_to: Vec<(i32, i32)>,
+    /// (MemberID, TaskID)
+    assigned_to: Vec<(i32, i32)>,
+    /// (BlockerID, BlockedID)
+    task_dependency: Vec<(i32, i32)>,
+    work_log: Vec<WorkLogEntry>,
+    comments: Vec<Comment>,
+    checklist_items: Vec<ChecklistItem>,
+    labels: Vec<Label>,
+    /// (TaskID, LabelID)
+    task_label: Vec<(i32, i32)>,
+    /// The workflow of each project, by ProjectID.
+    workflows: HashMap<i32, Workflow>,
+    /// Records moved to the trash, which stay in their tables but are hidden from every fetch.
+    trash: Vec<TrashedRecord>,
+    /// The audit log, each entry with the kind and ID of the record it is about.
+    audit_log: Vec<(RecordKind, i32, AuditEntry)>,
+}
+
+impl Tables {
+    fn next_id(&mut self) -> i32 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Appends changes to the audit log, stamped with the current time and the acting member.
+    fn record(&mut self, changes: impl IntoIterator<Item = Change>) {
+        let changed_at = Local::now().naive_local();
+        for change in changes {
+            let entry = AuditEntry {
+                entry_id: self.next_id(),
+                action: change.action.as_str().into(),
+                field: change.field.map(Into::into),
+                old_value: change.old_value,
+                new_value: change.new_value,
+                changed_at,
+                member_id: audit::acting_member(),
+            };
+            self.audit_log.push((change.kind, change.id, entry));
+        }
+    }
+
+    fn task_ids_of_sprint(&self, sprint_id: i32) -> Vec<i32> {
+        self.part_of
+            .iter()
+            .filter(|(_, s)| *s == sprint_id)
+            .map(|(t, _)| *t)
+            .collect()
+    }
+
+    /// The projects owning the sprints the task is part of.
+    fn project_ids_of_task(&self, task_id: i32) -> Vec<i32> {
+        self.part_of
+            .iter()
+            .filter(|(t, _)| *t == task_id)
+            .flat_map(|(_, s)| self.project_sprint.iter().filter(move |(_, ps)| ps == s))
+            .map(|(p, _)| *p)
+            .collect()
+    }
+
+    fn workflow_of_sprint(&self, sprint_id: i32) -> StorageResult<&Workflow> {
+        self.project_sprint
+            .iter()
+            .find(|(_, s)| *s == sprint_id)
+            .and_then(|(p, _)| self.workflows.get(p))
+            .ok_or(StorageError::NotFound)
+    }
+
+    fn workflow_of_task(&self, task_id: i32) -> StorageResult<&Workflow> {
+        self.project_ids_of_task(task_id)
+            .first()
+            .and_then(|p| self.workflows.get(p))
+            .ok_or(StorageError::NotFound)
+    }
+
+    fn sprint(&self, sprint_id: i32) -> StorageResult<&RawSprint> {
+        self.sprints
+            .iter()
+            .find(|s| s.sprint_id == sprint_id)
+            .ok_or(StorageError::NotFound)
+    }
+
+    fn sprint_of_task(&self, task_id: i32) -> StorageResult<&RawSprint> {
+        self.part_of
+            .iter()
+            .find(|(t, _)| *t == task_id)
+            .and_then(|(_, s)| self.sprints.iter().find(|sprint| sprint.sprint_id == *s))
+            .ok_or(StorageError::NotFound)
+    }
+
+    /// Checks that the task may move to `status` under the workflow of its project.
+    fn check_task_transition(&self, task_id: i32, status: &str) -> StorageResult<()> {
+        let current = &self
+            .tasks
+            .iter()
+            .find(|t| t.task_id == task_id)
+            .ok_or(StorageError::NotFound)?
+            .status;
+        check_transition(self.workflow_of_task(task_id)?, current, status)
+    }
+
+    fn assignees_of_task(&self, task_id: i32) -> Vec<Member> {
+        self.assigned_to
+            .iter()
+            .filter(|(_, t)| *t == task_id)
+            .filter(|(m, _)| !self.is_trashed(RecordKind::Member, *m))
+            .filter_map(|(m, _)| self.members.iter().find(|member| member.member_id == *m))
+            .cloned()
+            .collect()
+    }
+
+    fn blockers_of_task(&self, task_id: i32) -> Vec<i32> {
+        self.task_dependency
+            .iter()
+            .filter(|(_, blocked)| *blocked == task_id)
+            .map(|(blocker, _)| *blocker)
+            .collect()
+    }
+
+    fn checklist_of_task(&self, task_id: i32) -> Vec<ChecklistItem> {
+        self.checklist_items
+            .iter()
+            .filter(|item| item.task_id == task_id)
+            .filter(|item| !self.is_trashed(RecordKind::ChecklistItem, item.item_id))
+            .cloned()
+            .collect()
+    }
+
+    fn labels_of_project(&self, project_id: i32) -> Vec<Label> {
+        self.labels
+            .iter()
+            .filter(|label| label.project_id == project_id)
+            .cloned()
+            .collect()
+    }
+
+    fn labels_of_task(&self, task_id: i32) -> Vec<Label> {
+        self.labels
+            .iter()
+            .filter(|label| self.task_label.contains(&(task_id, label.label_id)))
+            .cloned()
+            .collect()
+    }
+
+    fn is_trashed(&self, kind: RecordKind, id: i32) -> bool {
+        self.trash.iter().any(|r| r.kind == kind && r.id == id)
+    }
+
+    /// Drops a purged record from the trash.
+    fn forget_trashed(&mut self, kind: RecordKind, id: i32) {
+        self.trash.retain(|r| !(r.kind == kind && r.id == id));
+    }
+
+    /// The title a record is shown with in the trash, or `None` if the record does not exist.
+    fn trash_title(&self, kind: RecordKind, id: i32) -> Option<String> {
+        match kind {
+            RecordKind::Project => self
+                .projects
+                .iter()
+                .find(|p| p.project_id == id)
+                .map(|p| p.title.clone()),
+            RecordKind::Sprint => self
+                .sprints
+                .iter()
+                .find(|s| s.sprint_id == id)
+                .map(|s| s.title.clone()),
+            RecordKind::Task => self
+                .tasks
+                .iter()
+                .find(|t| t.task_id == id)
+                .map(|t| t.title.clone()),
+            RecordKind::Member => self
+                .members
+                .iter()
+                .find(|m| m.member_id == id)
+                .map(|m| format!("{} {}", m.first_name, m.last_name)),
+            RecordKind::ChecklistItem => self
+                .checklist_items
+                .iter()
+                .find(|item| item.item_id == id)
+                .map(|item| item.title.clone()),
+            // Comments are deleted right away and never end up in the trash.
+            RecordKind::Comment => None,
+        }
+    }
+
+    /// Recomputes the committed hours of a task as the sum of its work log, recording the change.
+    fn roll_up_commited_hours(&mut self, task_id: i32) {
+        let total: i32 = self
+            .work_log
+            .iter()
+            .filter(|entry| entry.task_id == task_id)
+            .map(|entry| entry.hours)
+            .sum();
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.task_id == task_id) {
+            let before = std::mem::replace(&mut task.commited_hours, total);
+            self.record(Change::updated(
+                RecordKind::Task,
+                task_id,
+                vec![("commitedHours", before.to_string())],
+                vec![("commitedHours", total.to_string())],
+            ));
+        }
+    }
+
+    fn member_ids_of_project(&self, project_id: i32) -> Vec<i32> {
+        self.contributes_to
+            .iter()
+            .filter(|(_, p)| *p == project_id)
+            .map(|(m, _)| *m)
+            .collect()
+    }
+
+    fn sprint_ids_of_project(&self, project_id: i32) -> Vec<i32> {
+        self.project_sprint
+            .iter()
+            .filter(|(p, _)| *p == project_id)
+            .map(|(_, s)| *s)
+            .collect()
+    }
+
+    fn remove_task(&mut self, task_id: i32) {
+        for item in self.checklist_items.clone() {
+            if item.task_id == task_id {
+                self.remove_checklist_item(item.item_id);
+            }
+        }
+        self.work_log.retain(|entry| entry.task_id != task_id);
+        self.comments.retain(|comment| comment.task_id != task_id);
+        self.assigned_to.retain(|(_, t)| *t != task_id);
+        self.task_label.retain(|(t, _)| *t != task_id);
+        self.task_dependency
+            .retain(|(blocker, blocked)| *blocker != task_id && *blocked != task_id);
+        self.part_of.retain(|(t, _)| *t != task_id);
+        self.tasks.retain(|t| t.task_id != task_id);
+        self.forget_trashed(RecordKind::Task, task_id);
+    }
+
+    fn remove_checklist_item(&mut self, item_id: i32) {
+        self.checklist_items.retain(|item| item.item_id != item_id);
+        self.forget_trashed(RecordKind::ChecklistItem, item_id);
+    }
+
+    fn remove_sprint(&mut self, sprint_id: i32) {
+        for task_id in self.task_ids_of_sprint(sprint_id) {
+            self.remove_task(task_id);
+        }
+        self.project_sprint.retain(|(_, s)| *s != sprint_id);
+        self.sprints.retain(|s| s.sprint_id != sprint_id);
+        self.forget_trashed(RecordKind::Sprint, sprint_id);
+    }
+}
+
+/// A [`Storage`] that keeps everything in memory, so the UI can be tested without a database.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl MemoryStore {
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().expect("In-memory store was poisoned")
+    }
+}
+
+impl Storage for MemoryStore {
+    async fn fetch_projects(&self) -> StorageResult<Vec<Project>> {
+        let tables = self.tables();
+
+        let projects = tables
+            .projects
+            .iter()
+            .filter(|raw_project| !tables.is_trashed(RecordKind::Project, raw_project.project_id))
+            .map(|raw_project| {
+                let sprints = tables
+                    .project_sprint
+                    .iter()
+                    .filter(|(p, _)| *p == raw_project.project_id)
+                    .filter(|(_, s)| !tables.is_trashed(RecordKind::Sprint, *s))
+                    .filter_map(|(_, s)| tables.sprints.iter().find(|rs| rs.sprint_id == *s))
+                    .map(|raw_sprint| Sprint {
+                        sprint_id: raw_sprint.sprint_id,
+                        title: raw_sprint.title.clone(),
+                        start_date: raw_sprint.start_date,
+                        end_date: raw_sprint.end_date,
+                        version: raw_sprint.version,
+                        backlog: raw_sprint.backlog_of.is_some(),
+                        tasks: tables
+                            .task_ids_of_sprint(raw_sprint.sprint_id)
+                            .iter()
+                            .filter(|t| !tables.is_trashed(RecordKind::Task, **t))
+                            .filter_map(|t| tables.tasks.iter().find(|task| task.task_id == *t))
+                            .map(|task| Task {
+                                assignees: tables.assignees_of_task(task.task_id),
+                                blocked_by: tables.blockers_of_task(task.task_id),
+                                checklist: tables.checklist_of_task(task.task_id),
+                                labels: tables.labels_of_task(task.task_id),
+                                ..task.clone()
+                            })
+                            .collect(),
+                    })
+                    .collect();
+
+                let members = tables
+                    .contributes_to
+                    .iter()
+                    .filter(|(_, p)| *p == raw_project.project_id)
+                    .filter(|(m, _)| !tables.is_trashed(RecordKind::Member, *m))
+                    .filter_map(|(m, _)| tables.members.iter().find(|mb| mb.member_id == *m))
+                    .cloned()
+                    .collect();
+
+                Project {
+                    proj_id: raw_project.project_id,
+                    title: raw_project.title.clone(),
+                    desc: raw_project.description.clone(),
+                    version: raw_project.version,
+                    sprints,
+                    members,
+                    workflow: tables
+                        .workflows
+                        .get(&raw_project.project_id)
+                        .cloned()
+                        .unwrap_or_default(),
+                    labels: tables.labels_of_project(raw_project.project_id),
+                }
+            })
+            .collect();
+
+        Ok(projects)
+    }
+
+    async fn create_project(&self, title: &str, description: &str) -> StorageResult<i32> {
+        let mut tables = self.tables();
+        let project_id = tables.next_id();
+        tables.projects.push(RawProject {
+            project_id,
+            title: title.into(),
+            description: description.into(),
+            version: 0,
+        });
+        tables.workflows.insert(project_id, Workflow::default());
+        tables.record(Change::created(
+            RecordKind::Project,
+            project_id,
+            audit::project_fields(title, description),
+        ));
+
+        // The backlog is dated today, though its dates are never shown.
+        let today = Local::now().date_naive();
+        let backlog_id = tables.next_id();
+        tables.sprints.push(RawSprint {
+            sprint_id: backlog_id,
+            title: Sprint::BACKLOG_TITLE.into(),
+            start_date: today,
+            end_date: today,
+            version: 0,
+            backlog_of: Some(project_id),
+        });
+        tables.project_sprint.push((project_id, backlog_id));
+        let mut fields = audit::sprint_fields(Sprint::BACKLOG_TITLE, today, today);
+        fields.push(("ProjectID", project_id.to_string()));
+        tables.record(Change::created(RecordKind::Sprint, backlog_id, fields));
+        Ok(project_id)
+    }
+
+    async fn update_project(
+        &self,
+        project_id: i32,
+        version: i32,
+        title: &str,
+        description: &str,
+    ) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let project = tables
+            .projects
+            .iter_mut()
+            .find(|p| p.project_id == project_id)
+            .ok_or(StorageError::NotFound)?;
+        if project.version != version {
+            return Err(StorageError::Conflict);
+        }
+        project.version += 1;
+        let before = audit::project_fields(&project.title, &project.description);
+        project.title = title.into();
+        project.description = description.into();
+        tables.record(Change::updated(
+            RecordKind::Project,
+            project_id,
+            before,
+            audit::project_fields(title, description),
+        ));
+        Ok(())
+    }
+
+    async fn delete_project(&self, project_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let project = tables
+            .projects
+            .iter()
+            .find(|p| p.project_id == project_id)
+            .ok_or(StorageError::NotFound)?;
+        let mut changes = Change::deleted(
+            RecordKind::Project,
+            project_id,
+            audit::project_fields(&project.title, &project.description),
+        );
+        let sprint_ids = tables.sprint_ids_of_project(project_id);
+        for sprint_id in &sprint_ids {
+            changes.push(Change::record(
+                RecordKind::Sprint,
+                *sprint_id,
+                Action::Delete,
+            ));
+            changes.extend(
+                tables
+                    .task_ids_of_sprint(*sprint_id)
+                    .into_iter()
+                    .map(|task_id| Change::record(RecordKind::Task, task_id, Action::Delete)),
+            );
+        }
+        tables.record(changes);
+        for sprint_id in sprint_ids {
+            tables.remove_sprint(sprint_id);
+        }
+        tables.contributes_to.retain(|(_, p)| *p != project_id);
+        tables.workflows.remove(&project_id);
+        tables.labels.retain(|label| label.project_id != project_id);
+        tables.projects.retain(|p| p.project_id != project_id);
+        tables.forget_trashed(RecordKind::Project, project_id);
+        Ok(())
+    }
+
+    async fn update_workflow(&self, project_id: i32, workflow: &Workflow) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let before = audit::workflow_fields(
+            tables
+                .workflows
+                .get(&project_id)
+                .ok_or(StorageError::NotFound)?,
+        );
+        let sprint_ids = tables.sprint_ids_of_project(project_id);
+        let mut in_use: Vec<String> = Vec::new();
+        for task in &tables.tasks {
+            let in_project = tables
+                .part_of
+                .iter()
+                .any(|(t, s)| *t == task.task_id && sprint_ids.contains(s));
+            if in_project && !in_use.contains(&task.status) {
+                in_use.push(task.status.clone());
+            }
+        }
+        check_workflow(workflow, &in_use)?;
+        tables.workflows.insert(project_id, workflow.clone());
+        tables.record(Change::updated(
+            RecordKind::Project,
+            project_id,
+            before,
+            audit::workflow_fields(workflow),
+        ));
+        Ok(())
+    }
+
+    async fn create_label(&self, project_id: i32, name: &str, color: &str) -> StorageResult<Label> {
+        let mut tables = self.tables();
+        if !tables.projects.iter().any(|p| p.project_id == project_id) {
+            return Err(StorageError::NotFound);
+        }
+        let before = tables.labels_of_project(project_id);
+        check_label_name(&before, name)?;
+
+        let label = Label {
+            label_id: tables.next_id(),
+            project_id,
+            name: name.into(),
+            color: color.into(),
+        };
+        tables.labels.push(label.clone());
+        let after = tables.labels_of_project(project_id);
+        tables.record(Change::updated(
+            RecordKind::Project,
+            project_id,
+            audit::label_fields(&before),
+            audit::label_fields(&after),
+        ));
+        Ok(label)
+    }
+
+    async fn update_label(&self, label_id: i32, name: &str, color: &str) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let project_id = tables
+            .labels
+            .iter()
+            .find(|label| label.label_id == label_id)
+            .ok_or(StorageError::NotFound)?
+            .project_id;
+        let before = tables.labels_of_project(project_id);
+        let others: Vec<Label> = before
+            .iter()
+            .filter(|label| label.label_id != label_id)
+            .cloned()
+            .collect();
+        check_label_name(&others, name)?;
+
+        if let Some(label) = tables.labels.iter_mut().find(|label| label.label_id == label_id) {
+            label.name = name.into();
+            label.color = color.into();
+        }
+        let after = tables.labels_of_project(project_id);
+        tables.record(Change::updated(
+            RecordKind::Project,
+            project_id,
+            audit::label_fields(&before),
+            audit::label_fields(&after),
+        ));
+        Ok(())
+    }
+
+    async fn delete_label(&self, label_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let project_id = tables
+            .labels
+            .iter()
+            .find(|label| label.label_id == label_id)
+            .ok_or(StorageError::NotFound)?
+            .project_id;
+        let before = tables.labels_of_project(project_id);
+        tables.task_label.retain(|(_, l)| *l != label_id);
+        tables.labels.retain(|label| label.label_id != label_id);
+        let after = tables.labels_of_project(project_id);
+        tables.record(Change::updated(
+            RecordKind::Project,
+            project_id,
+            audit::label_fields(&before),
+            audit::label_fields(&after),
+        ));
+        Ok(())
+    }
+
+    async fn create_sprint(
+        &self,
+        project_id: i32,
+        title: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> StorageResult<Sprint> {
+        let mut tables = self.tables();
+        if !tables.projects.iter().any(|p| p.project_id == project_id) {
+            return Err(StorageError::NotFound);
+        }
+        let sprint_id = tables.next_id();
+        tables.sprints.push(RawSprint {
+            sprint_id,
+            title: title.into(),
+            start_date,
+            end_date,
+            version: 0,
+            backlog_of: None,
+        });
+        tables.project_sprint.push((project_id, sprint_id));
+        let mut fields = audit::sprint_fields(title, start_date, end_date);
+        fields.push(("ProjectID", project_id.to_string()));
+        tables.record(Change::created(RecordKind::Sprint, sprint_id, fields));
+        Ok(Sprint {
+            sprint_id,
+            title: title.into(),
+            start_date,
+            end_date,
+            version: 0,
+            tasks: Vec::new(),
+            backlog: false,
+        })
+    }
+
+    async fn update_sprint(
+        &self,
+        sprint_id: i32,
+        version: i32,
+        title: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> StorageResult<()> {
+        let mut tables = self.tables();
+        check_not_backlog(tables.sprint(sprint_id)?)?;
+        for task_id in tables.task_ids_of_sprint(sprint_id) {
+            let due_date = tables
+                .tasks
+                .iter()
+                .find(|t| t.task_id == task_id)
+                .and_then(|t| t.due_date);
+            check_due_date(due_date, start_date, end_date)?;
+        }
+        let sprint = tables
+            .sprints
+            .iter_mut()
+            .find(|s| s.sprint_id == sprint_id)
+            .ok_or(StorageError::NotFound)?;
+        if sprint.version != version {
+            return Err(StorageError::Conflict);
+        }
+        sprint.version += 1;
+        let before = audit::sprint_fields(&sprint.title, sprint.start_date, sprint.end_date);
+        sprint.title = title.into();
+        sprint.start_date = start_date;
+        sprint.end_date = end_date;
+        tables.record(Change::updated(
+            RecordKind::Sprint,
+            sprint_id,
+            before,
+            audit::sprint_fields(title, start_date, end_date),
+        ));
+        Ok(())
+    }
+
+    async fn delete_sprint(&self, sprint_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let sprint = tables.sprint(sprint_id)?;
+        check_not_backlog(sprint)?;
+        let mut changes = Change::deleted(
+            RecordKind::Sprint,
+            sprint_id,
+            audit::sprint_fields(&sprint.title, sprint.start_date, sprint.end_date),
+        );
+        changes.extend(
+            tables
+                .task_ids_of_sprint(sprint_id)
+                .into_iter()
+                .map(|task_id| Change::record(RecordKind::Task, task_id, Action::Delete)),
+        );
+        tables.record(changes);
+        tables.remove_sprint(sprint_id);
+        Ok(())
+    }
+
+    async fn create_task(
+        &self,
+        sprint_id: i32,
+        title: &str,
+        status: &str,
+        description: &str,
+        estimated_hours: i32,
+        priority: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<Task> {
+        check_priority(priority)?;
+        let mut tables = self.tables();
+        check_due_date_in(tables.sprint(sprint_id)?, due_date)?;
+        check_status_defined(tables.workflow_of_sprint(sprint_id)?, status)?;
+        let task = Task {
+            task_id: tables.next_id(),
+            title: title.into(),
+            status: status.into(),
+            description: description.into(),
+            commited_hours: 0,
+            estimated_hours,
+            priority,
+            due_date,
+            version: 0,
+            assignees: Vec::new(),
+            blocked_by: Vec::new(),
+            checklist: Vec::new(),
+            labels: Vec::new(),
+        };
+        tables.part_of.push((task.task_id, sprint_id));
+        tables.tasks.push(task.clone());
+        let mut fields =
+            audit::task_fields(title, status, description, estimated_hours, priority, due_date);
+        fields.push(("SprintID", sprint_id.to_string()));
+        tables.record(Change::created(RecordKind::Task, task.task_id, fields));
+        Ok(task)
+    }
+
+    async fn update_task(
+        &self,
+        task_id: i32,
+        version: i32,
+        title: &str,
+        status: &str,
+        description: &str,
+        estimated_hours: i32,
+        priority: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<()> {
+        check_priority(priority)?;
+        let mut tables = self.tables();
+        tables.check_task_transition(task_id, status)?;
+        check_due_date_in(tables.sprint_of_task(task_id)?, due_date)?;
+        let task = tables
+            .tasks
+            .iter_mut()
+            .find(|t| t.task_id == task_id)
+            .ok_or(StorageError::NotFound)?;
+        if task.version != version {
+            return Err(StorageError::Conflict);
+        }
+        task.version += 1;
+        let before = audit::task_fields(
+            &task.title,
+            &task.status,
+            &task.description,
+            task.estimated_hours,
+            task.priority,
+            task.due_date,
+        );
+        task.title = title.into();
+        task.status = status.into();
+        task.description = description.into();
+        task.estimated_hours = estimated_hours;
+        task.priority = priority;
+        task.due_date = due_date;
+        tables.record(Change::updated(
+            RecordKind::Task,
+            task_id,
+            before,
+            audit::task_fields(
+                title,
+                status,
+                description,
+                estimated_hours,
+                priority,
+                due_date,
+            ),
+        ));
+        Ok(())
+    }
+
+    async fn update_task_status(
+        &self,
+        task_id: i32,
+        version: i32,
+        status: &str,
+    ) -> StorageResult<()> {
+        let mut tables = self.tables();
+        tables.check_task_transition(task_id, status)?;
+        let task = tables
+            .tasks
+            .iter_mut()
+            .find(|t| t.task_id == task_id)
+            .ok_or(StorageError::NotFound)?;
+        if task.version != version {
+            return Err(StorageError::Conflict);
+        }
+        task.version += 1;
+        let before = std::mem::replace(&mut task.status, status.into());
+        tables.record(Change::updated(
+            RecordKind::Task,
+            task_id,
+            vec![("Status", before)],
+            vec![("Status", status.into())],
+        ));
+        Ok(())
+    }
+
+    async fn move_task(
+        &self,
+        task_id: i32,
+        version: i32,
+        sprint_id: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let old_sprint_id = tables.sprint_of_task(task_id)?.sprint_id;
+        let task_project_id = *tables
+            .project_ids_of_task(task_id)
+            .first()
+            .ok_or(StorageError::NotFound)?;
+        let sprint_project_id = tables
+            .project_sprint
+            .iter()
+            .find(|(_, s)| *s == sprint_id)
+            .map(|(p, _)| *p)
+            .ok_or(StorageError::NotFound)?;
+        check_move(task_project_id, sprint_project_id)?;
+        check_due_date_in(tables.sprint(sprint_id)?, due_date)?;
+        let task = tables
+            .tasks
+            .iter_mut()
+            .find(|t| t.task_id == task_id)
+            .ok_or(StorageError::NotFound)?;
+        if task.version != version {
+            return Err(StorageError::Conflict);
+        }
+        task.version += 1;
+        let old_due_date = std::mem::replace(&mut task.due_date, due_date);
+        for link in tables.part_of.iter_mut().filter(|(t, _)| *t == task_id) {
+            link.1 = sprint_id;
+        }
+        tables.record(Change::updated(
+            RecordKind::Task,
+            task_id,
+            audit::task_move_fields(old_sprint_id, old_due_date),
+            audit::task_move_fields(sprint_id, due_date),
+        ));
+        Ok(())
+    }
+
+    async fn delete_task(&self, task_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let task = tables
+            .tasks
+            .iter()
+            .find(|t| t.task_id == task_id)
+            .ok_or(StorageError::NotFound)?;
+        let before = audit::task_fields(
+            &task.title,
+            &task.status,
+            &task.description,
+            task.estimated_hours,
+            task.priority,
+            task.due_date,
+        );
+        tables.record(Change::deleted(RecordKind::Task, task_id, before));
+        tables.remove_task(task_id);
+        Ok(())
+    }
+
+    async fn set_task_assignees(&self, task_id: i32, member_ids: &[i32]) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let project_ids = tables.project_ids_of_task(task_id);
+        let is_project_member = |member_id: &i32| {
+            tables
+                .contributes_to
+                .iter()
+                .any(|(m, p)| m == member_id && project_ids.contains(p))
+        };
+        if !member_ids.iter().all(is_project_member) {
+            return Err(StorageError::Invalid(
+                "only members of the task's project can be assigned to it".into(),
+            ));
+        }
+
+        let before: Vec<i32> = tables
+            .assigned_to
+            .iter()
+            .filter(|(_, t)| *t == task_id)
+            .map(|(m, _)| *m)
+            .collect();
+        tables.assigned_to.retain(|(_, t)| *t != task_id);
+        tables
+            .assigned_to
+            .extend(member_ids.iter().map(|member_id| (*member_id, task_id)));
+        tables.record(Change::updated(
+            RecordKind::Task,
+            task_id,
+            vec![("assignees", audit::id_list(&before))],
+            vec![("assignees", audit::id_list(member_ids))],
+        ));
+        Ok(())
+    }
+
+    async fn set_task_labels(&self, task_id: i32, label_ids: &[i32]) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let project_ids = tables.project_ids_of_task(task_id);
+        let is_project_label = |label_id: &i32| {
+            tables
+                .labels
+                .iter()
+                .any(|label| label.label_id == *label_id && project_ids.contains(&label.project_id))
+        };
+        if !label_ids.iter().all(is_project_label) {
+            return Err(StorageError::Invalid(
+                "only labels of the task's project can be put on it".into(),
+            ));
+        }
+
+        let before: Vec<i32> = tables
+            .task_label
+            .iter()
+            .filter(|(t, _)| *t == task_id)
+            .map(|(_, l)| *l)
+            .collect();
+        tables.task_label.retain(|(t, _)| *t != task_id);
+        tables
+            .task_label
+            .extend(label_ids.iter().map(|label_id| (task_id, *label_id)));
+        tables.record(Change::updated(
+            RecordKind::Task,
+            task_id,
+            vec![("labels", audit::id_list(&before))],
+            vec![("labels", audit::id_list(label_ids))],
+        ));
+        Ok(())
+    }
+
+    async fn add_task_dependency(&self, blocker_id: i32, blocked_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let project_of = |task_id: i32| {
+            tables
+                .project_ids_of_task(task_id)
+                .first()
+                .copied()
+                .ok_or(StorageError::NotFound)
+        };
+        check_dependency(
+            &tables.task_dependency,
+            (blocker_id, project_of(blocker_id)?),
+            (blocked_id, project_of(blocked_id)?),
+        )?;
+
+        let before = tables.blockers_of_task(blocked_id);
+        tables.task_dependency.push((blocker_id, blocked_id));
+        let after = tables.blockers_of_task(blocked_id);
+        tables.record(Change::updated(
+            RecordKind::Task,
+            blocked_id,
+            vec![("blockers", audit::id_list(&before))],
+            vec![("blockers", audit::id_list(&after))],
+        ));
+        Ok(())
+    }
+
+    async fn remove_task_dependency(&self, blocker_id: i32, blocked_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        if !tables.task_dependency.contains(&(blocker_id, blocked_id)) {
+            return Err(StorageError::NotFound);
+        }
+
+        let before = tables.blockers_of_task(blocked_id);
+        tables
+            .task_dependency
+            .retain(|link| *link != (blocker_id, blocked_id));
+        let after = tables.blockers_of_task(blocked_id);
+        tables.record(Change::updated(
+            RecordKind::Task,
+            blocked_id,
+            vec![("blockers", audit::id_list(&before))],
+            vec![("blockers", audit::id_list(&after))],
+        ));
+        Ok(())
+    }
+
+    async fn create_checklist_item(&self, task_id: i32, title: &str) -> StorageResult<ChecklistItem> {
+        let mut tables = self.tables();
+        if !tables.tasks.iter().any(|t| t.task_id == task_id) {
+            return Err(StorageError::NotFound);
+        }
+        let item = ChecklistItem {
+            item_id: tables.next_id(),
+            task_id,
+            title: title.into(),
+            done: false,
+            version: 0,
+        };
+        tables.checklist_items.push(item.clone());
+        let mut fields = audit::checklist_item_fields(title, false);
+        fields.push(("TaskID", task_id.to_string()));
+        tables.record(Change::created(RecordKind::ChecklistItem, item.item_id, fields));
+        Ok(item)
+    }
+
+    async fn update_checklist_item(
+        &self,
+        item_id: i32,
+        version: i32,
+        title: &str,
+        done: bool,
+    ) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let item = tables
+            .checklist_items
+            .iter_mut()
+            .find(|item| item.item_id == item_id)
+            .ok_or(StorageError::NotFound)?;
+        if item.version != version {
+            return Err(StorageError::Conflict);
+        }
+        item.version += 1;
+        let before = audit::checklist_item_fields(&item.title, item.done);
+        item.title = title.into();
+        item.done = done;
+        tables.record(Change::updated(
+            RecordKind::ChecklistItem,
+            item_id,
+            before,
+            audit::checklist_item_fields(title, done),
+        ));
+        Ok(())
+    }
+
+    async fn delete_checklist_item(&self, item_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let item = tables
+            .checklist_items
+            .iter()
+            .find(|item| item.item_id == item_id)
+            .ok_or(StorageError::NotFound)?;
+        let before = audit::checklist_item_fields(&item.title, item.done);
+        tables.record(Change::deleted(RecordKind::ChecklistItem, item_id, before));
+        tables.remove_checklist_item(item_id);
+        Ok(())
+    }
+
+    async fn fetch_members(&self) -> StorageResult<Vec<Member>> {
+        let tables = self.tables();
+        Ok(tables
+            .members
+            .iter()
+            .filter(|m| !tables.is_trashed(RecordKind::Member, m.member_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn create_member(
+        &self,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        phone: &str,
+    ) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let member_id = tables.next_id();
+        tables.members.push(Member {
+            member_id,
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            email: email.into(),
+            phone: phone.into(),
+            version: 0,
+        });
+        tables.record(Change::created(
+            RecordKind::Member,
+            member_id,
+            audit::member_fields(first_name, last_name, email, phone),
+        ));
+        Ok(())
+    }
+
+    async fn update_member(
+        &self,
+        member_id: i32,
+        version: i32,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        phone: &str,
+    ) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let member = tables
+            .members
+            .iter_mut()
+            .find(|m| m.member_id == member_id)
+            .ok_or(StorageError::NotFound)?;
+        if member.version != version {
+            return Err(StorageError::Conflict);
+        }
+        member.version += 1;
+        let before = audit::member_fields(
+            &member.first_name,
+            &member.last_name,
+            &member.email,
+            &member.phone,
+        );
+        member.first_name = first_name.into();
+        member.last_name = last_name.into();
+        member.email = email.into();
+        member.phone = phone.into();
+        tables.record(Change::updated(
+            RecordKind::Member,
+            member_id,
+            before,
+            audit::member_fields(first_name, last_name, email, phone),
+        ));
+        Ok(())
+    }
+
+    async fn delete_member(&self, member_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let member = tables
+            .members
+            .iter()
+            .find(|m| m.member_id == member_id)
+            .ok_or(StorageError::NotFound)?;
+        let before = audit::member_fields(
+            &member.first_name,
+            &member.last_name,
+            &member.email,
+            &member.phone,
+        );
+        tables.record(Change::deleted(RecordKind::Member, member_id, before));
+        let logged_task_ids: Vec<i32> = tables
+            .work_log
+            .iter()
+            .filter(|entry| entry.member_id == member_id)
+            .map(|entry| entry.task_id)
+            .collect();
+        tables.work_log.retain(|entry| entry.member_id != member_id);
+        for task_id in logged_task_ids {
+            tables.roll_up_commited_hours(task_id);
+        }
+        tables.assigned_to.retain(|(m, _)| *m != member_id);
+        tables.comments.retain(|comment| comment.member_id != member_id);
+        tables.contributes_to.retain(|(m, _)| *m != member_id);
+        tables.members.retain(|m| m.member_id != member_id);
+        tables.forget_trashed(RecordKind::Member, member_id);
+        Ok(())
+    }
+
+    async fn move_to_trash(&self, kind: RecordKind, id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        if tables.is_trashed(kind, id) {
+            return Err(StorageError::NotFound);
+        }
+        if kind == RecordKind::Sprint {
+            check_not_backlog(tables.sprint(id)?)?;
+        }
+        let title = tables.trash_title(kind, id).ok_or(StorageError::NotFound)?;
+        tables.trash.push(TrashedRecord {
+            kind,
+            id,
+            title,
+            deleted_at: Local::now().naive_local(),
+        });
+        tables.record([Change::record(kind, id, Action::Trash)]);
+        Ok(())
+    }
+
+    async fn restore_from_trash(&self, kind: RecordKind, id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        if !tables.is_trashed(kind, id) {
+            return Err(StorageError::NotFound);
+        }
+        tables.forget_trashed(kind, id);
+        tables.record([Change::record(kind, id, Action::Restore)]);
+        Ok(())
+    }
+
+    async fn fetch_trash(&self) -> StorageResult<Vec<TrashedRecord>> {
+        let mut trash = self.tables().trash.clone();
+        trash.sort_by_key(|record| std::cmp::Reverse(record.deleted_at));
+        Ok(trash)
+    }
+
+    async fn fetch_project_members(&self, project_id: i32) -> StorageResult<Vec<Member>> {
+        let tables = self.tables();
+        Ok(tables
+            .contributes_to
+            .iter()
+            .filter(|(_, p)| *p == project_id)
+            .filter(|(m, _)| !tables.is_trashed(RecordKind::Member, *m))
+            .filter_map(|(m, _)| tables.members.iter().find(|member| member.member_id == *m))
+            .cloned()
+            .collect())
+    }
+
+    async fn add_project_member(&self, member_id: i32, project_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        if !tables.members.iter().any(|m| m.member_id == member_id)
+            || !tables.projects.iter().any(|p| p.project_id == project_id)
+        {
+            return Err(StorageError::NotFound);
+        }
+        let before = tables.member_ids_of_project(project_id);
+        if !tables.contributes_to.contains(&(member_id, project_id)) {
+            tables.contributes_to.push((member_id, project_id));
+        }
+        let after = tables.member_ids_of_project(project_id);
+        tables.record(Change::updated(
+            RecordKind::Project,
+            project_id,
+            vec![("members", audit::id_list(&before))],
+            vec![("members", audit::id_list(&after))],
+        ));
+        Ok(())
+    }
+
+    async fn remove_project_member(&self, member_id: i32, project_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let before = tables.member_ids_of_project(project_id);
+        let project_task_ids: Vec<i32> = tables
+            .assigned_to
+            .iter()
+            .filter(|(m, t)| {
+                *m == member_id && tables.project_ids_of_task(*t).contains(&project_id)
+            })
+            .map(|(_, t)| *t)
+            .collect();
+        tables
+            .assigned_to
+            .retain(|(m, t)| *m != member_id || !project_task_ids.contains(t));
+        tables
+            .contributes_to
+            .retain(|link| *link != (member_id, project_id));
+        let after = tables.member_ids_of_project(project_id);
+        tables.record(Change::updated(
+            RecordKind::Project,
+            project_id,
+            vec![("members", audit::id_list(&before))],
+            vec![("members", audit::id_list(&after))],
+        ));
+        Ok(())
+    }
+
+    async fn fetch_work_log(&self, task_id: i32) -> StorageResult<Vec<WorkLogEntry>> {
+        let mut entries: Vec<WorkLogEntry> = self
+            .tables()
+            .work_log
+            .iter()
+            .filter(|entry| entry.task_id == task_id)
+            .cloned()
+            .collect();
+        entries.sort_by_key(|entry| (entry.work_date, entry.entry_id));
+        Ok(entries)
+    }
+
+    async fn add_work_log_entry(
+        &self,
+        task_id: i32,
+        member_id: i32,
+        work_date: NaiveDate,
+        hours: i32,
+        note: &str,
+    ) -> StorageResult<WorkLogEntry> {
+        check_logged_hours(hours)?;
+
+        let mut tables = self.tables();
+        let project_ids = tables.project_ids_of_task(task_id);
+        if !tables
+            .contributes_to
+            .iter()
+            .any(|(m, p)| *m == member_id && project_ids.contains(p))
+        {
+            return Err(StorageError::Invalid(
+                "only members of the task's project can log work on it".into(),
+            ));
+        }
+
+        let entry = WorkLogEntry {
+            entry_id: tables.next_id(),
+            task_id,
+            member_id,
+            work_date,
+            hours,
+            note: note.into(),
+        };
+        tables.work_log.push(entry.clone());
+        tables.roll_up_commited_hours(task_id);
+        Ok(entry)
+    }
+
+    async fn remove_work_log_entry(&self, entry_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let task_id = tables
+            .work_log
+            .iter()
+            .find(|entry| entry.entry_id == entry_id)
+            .ok_or(StorageError::NotFound)?
+            .task_id;
+        tables.work_log.retain(|entry| entry.entry_id != entry_id);
+        tables.roll_up_commited_hours(task_id);
+        Ok(())
+    }
+
+    async fn fetch_comments(&self, task_id: i32) -> StorageResult<Vec<Comment>> {
+        let mut comments: Vec<Comment> = self
+            .tables()
+            .comments
+            .iter()
+            .filter(|comment| comment.task_id == task_id)
+            .cloned()
+            .collect();
+        comments.sort_by_key(|comment| (comment.posted_at, comment.comment_id));
+        Ok(comments)
+    }
+
+    async fn add_comment(&self, task_id: i32, member_id: i32, body: &str) -> StorageResult<Comment> {
+        check_comment_body(body)?;
+
+        let mut tables = self.tables();
+        if !tables.tasks.iter().any(|t| t.task_id == task_id)
+            || !tables.members.iter().any(|m| m.member_id == member_id)
+        {
+            return Err(StorageError::NotFound);
+        }
+
+        let comment = Comment {
+            comment_id: tables.next_id(),
+            task_id,
+            member_id,
+            body: body.into(),
+            posted_at: Local::now().naive_local(),
+            edited_at: None,
+        };
+        tables.comments.push(comment.clone());
+        let mut fields = audit::comment_fields(body);
+        fields.push(("TaskID", task_id.to_string()));
+        tables.record(Change::created(RecordKind::Comment, comment.comment_id, fields));
+        Ok(comment)
+    }
+
+    async fn update_comment(&self, comment_id: i32, member_id: i32, body: &str) -> StorageResult<()> {
+        check_comment_body(body)?;
+
+        let mut tables = self.tables();
+        let comment = tables
+            .comments
+            .iter_mut()
+            .find(|comment| comment.comment_id == comment_id)
+            .ok_or(StorageError::NotFound)?;
+        if comment.member_id != member_id {
+            return Err(not_comment_author());
+        }
+        let old_body = std::mem::replace(&mut comment.body, body.into());
+        comment.edited_at = Some(Local::now().naive_local());
+        tables.record(Change::updated(
+            RecordKind::Comment,
+            comment_id,
+            audit::comment_fields(&old_body),
+            audit::comment_fields(body),
+        ));
+        Ok(())
+    }
+
+    async fn delete_comment(&self, comment_id: i32, member_id: i32) -> StorageResult<()> {
+        let mut tables = self.tables();
+        let comment = tables
+            .comments
+            .iter()
+            .find(|comment| comment.comment_id == comment_id)
+            .ok_or(StorageError::NotFound)?;
+        if comment.member_id != member_id {
+            return Err(not_comment_author());
+        }
+        let before = audit::comment_fields(&comment.body);
+        tables.comments.retain(|comment| comment.comment_id != comment_id);
+        tables.record(Change::deleted(RecordKind::Comment, comment_id, before));
+        Ok(())
+    }
+
+    async fn fetch_history(&self, kind: RecordKind, id: i32) -> StorageResult<Vec<AuditEntry>> {
+        Ok(self
+            .tables()
+            .audit_log
+            .iter()
+            .rev()
+            .filter(|(k, i, _)| *k == kind && *i == id)
+            .map(|(_, _, entry)| entry.clone())
+            .collect())
+    }
+
+    async fn fetch_latest_change(&self) -> StorageResult<i32> {
+        Ok(self
+            .tables()
+            .audit_log
+            .last()
+            .map_or(0, |(_, _, entry)| entry.entry_id))
+    }
+
+    async fn fetch_changes_since(
+        &self,
+        entry_id: i32,
+    ) -> StorageResult<Vec<(RecordKind, i32, AuditEntry)>> {
+        Ok(self
+            .tables()
+            .audit_log
+            .iter()
+            .filter(|(_, _, entry)| entry.entry_id > entry_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn apply(&self, operations: &[Operation]) -> StorageResult<()> {
+        // Every operation checks its own rules; put the tables back as they were if one of them fails.
+        let snapshot = self.tables().clone();
+        for operation in operations {
+            let result = match operation {
+                Operation::UpdateProject {
+                    project_id,
+                    version,
+                    title,
+                    description,
+                } => {
+                    self.update_project(*project_id, *version, title, description)
+                        .await
+                }
+                Operation::UpdateSprint {
+                    sprint_id,
+                    version,
+                    title,
+                    start_date,
+                    end_date,
+                } => {
+                    self.update_sprint(*sprint_id, *version, title, *start_date, *end_date)
+                        .await
+                }
+                Operation::UpdateTask {
+                    task_id,
+                    version,
+                    title,
+                    status,
+                    description,
+                    estimated_hours,
+                    priority,
+                    due_date,
+                } => {
+                    self.update_task(
+                        *task_id,
+                        *version,
+                        title,
+                        status,
+                        description,
+                        *estimated_hours,
+                        *priority,
+                        *due_date,
+                    )
+                    .await
+                }
+                Operation::MoveTask {
+                    task_id,
+                    version,
+                    sprint_id,
+                    due_date,
+                } => {
+                    self.move_task(*task_id, *version, *sprint_id, *due_date)
+                        .await
+                }
+                Operation::SetTaskAssignees {
+                    task_id,
+                    member_ids,
+                } => self.set_task_assignees(*task_id, member_ids).await,
+                Operation::SetTaskLabels { task_id, label_ids } => {
+                    self.set_task_labels(*task_id, label_ids).await
+                }
+                Operation::UpdateChecklistItem {
+                    item_id,
+                    version,
+                    title,
+                    done,
+                } => {
+                    self.update_checklist_item(*item_id, *version, title, *done)
+                        .await
+                }
+                Operation::AddTaskDependency {
+                    blocker_id,
+                    blocked_id,
+                } => self.add_task_dependency(*blocker_id, *blocked_id).await,
+                Operation::RemoveTaskDependency {
+                    blocker_id,
+                    blocked_id,
+                } => self.remove_task_dependency(*blocker_id, *blocked_id).await,
+                Operation::AddProjectMember {
+                    member_id,
+                    project_id,
+                } => self.add_project_member(*member_id, *project_id).await,
+                Operation::RemoveProjectMember {
+                    member_id,
+                    project_id,
+                } => self.remove_project_member(*member_id, *project_id).await,
+                Operation::Trash { kind, id } => self.move_to_trash(*kind, *id).await,
+                Operation::Restore { kind, id } => self.restore_from_trash(*kind, *id).await,
+            };
+            if let Err(e) = result {
+                *self.tables() = snapshot;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
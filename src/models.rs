@@ -1,4 +1,4 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use sqlx::FromRow;
 
 pub struct Project {
@@ -6,6 +6,70 @@ pub struct Project {
     pub title: String,
     pub desc: String,
     pub sprints: Vec<Sprint>,
+    pub members: Vec<Member>,
+    pub workflow: Workflow,
+    /// The labels tasks of the project can carry, in the order they were defined.
+    pub labels: Vec<Label>,
+    /// Bumped by every update, so edits based on an older version can be refused.
+    pub version: i32,
+}
+
+impl Project {
+    /// Finds a task of the project by ID, in whichever sprint it is.
+    pub fn task(&self, task_id: i32) -> Option<&Task> {
+        self.sprints
+            .iter()
+            .flat_map(|s| &s.tasks)
+            .find(|t| t.task_id == task_id)
+    }
+
+    /// The tasks `task` waits for. Blockers in the trash are left out.
+    pub fn blockers(&self, task: &Task) -> Vec<&Task> {
+        task.blocked_by
+            .iter()
+            .filter_map(|blocker_id| self.task(*blocker_id))
+            .collect()
+    }
+
+    /// The tasks waiting for the task with the provided ID.
+    pub fn blocked_tasks(&self, task_id: i32) -> Vec<&Task> {
+        self.sprints
+            .iter()
+            .flat_map(|s| &s.tasks)
+            .filter(|t| t.blocked_by.contains(&task_id))
+            .collect()
+    }
+
+    /// Whether any of the tasks `task` waits for is not done yet.
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        self.blockers(task)
+            .iter()
+            .any(|blocker| !self.workflow.is_done(&blocker.status))
+    }
+
+    /// Whether `task` is not done although its due date lies before `today`.
+    pub fn is_overdue(&self, task: &Task, today: NaiveDate) -> bool {
+        task.due_date.is_some_and(|due| due < today) && !self.workflow.is_done(&task.status)
+    }
+
+    /// Whether `task` is not done and due from `today` on, within [`Task::DUE_SOON_DAYS`] days.
+    pub fn is_due_soon(&self, task: &Task, today: NaiveDate) -> bool {
+        task.due_date.is_some_and(|due| {
+            due >= today && (due - today).num_days() <= Task::DUE_SOON_DAYS
+        }) && !self.workflow.is_done(&task.status)
+    }
+
+    /// The overdue tasks of the project with their sprints, the longest overdue first.
+    pub fn overdue_tasks(&self, today: NaiveDate) -> Vec<(&Sprint, &Task)> {
+        let mut overdue: Vec<(&Sprint, &Task)> = self
+            .sprints
+            .iter()
+            .flat_map(|s| s.tasks.iter().map(move |t| (s, t)))
+            .filter(|(_, t)| self.is_overdue(t, today))
+            .collect();
+        overdue.sort_by_key(|(_, t)| (t.due_date, t.priority, t.task_id));
+        overdue
+    }
 }
 
 #[derive(Debug, FromRow, Clone)]
@@ -20,6 +84,8 @@ pub struct Member {
     pub email: String,
     #[sqlx(rename = "phone")]
     pub phone: String,
+    #[sqlx(rename = "Version")]
+    pub version: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -29,9 +95,18 @@ pub struct Sprint {
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
     pub tasks: Vec<Task>,
+    pub version: i32,
+    /// Whether this is the backlog of its project, holding the tasks not scheduled yet.
+    /// The backlog comes first among the sprints of a project and its dates mean nothing.
+    pub backlog: bool,
+}
+
+impl Sprint {
+    /// The title every backlog is created with.
+    pub const BACKLOG_TITLE: &'static str = "Backlog";
 }
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, FromRow, Clone)]
 pub struct RawSprint {
     #[sqlx(rename = "SprintID")]
     pub sprint_id: i32,
@@ -41,6 +116,11 @@ pub struct RawSprint {
     pub start_date: NaiveDate,
     #[sqlx(rename = "endDate")]
     pub end_date: NaiveDate,
+    #[sqlx(rename = "Version")]
+    pub version: i32,
+    /// The project this sprint is the backlog of, if it is one.
+    #[sqlx(rename = "BacklogOf")]
+    pub backlog_of: Option<i32>,
 }
 
 #[derive(Debug, FromRow, Clone)]
@@ -57,9 +137,315 @@ pub struct Task {
     pub commited_hours: i32,
     #[sqlx(rename = "estimatedHours")]
     pub estimated_hours: i32,
+    /// How urgent the task is, from 0 (P0, the most urgent) to 3 (P3).
+    #[sqlx(rename = "Priority")]
+    pub priority: i32,
+    /// The day the task has to be done by, within its sprint.
+    #[sqlx(rename = "dueDate")]
+    pub due_date: Option<NaiveDate>,
+    #[sqlx(rename = "Version")]
+    pub version: i32,
+    /// Members of the owning project working on this task.
+    #[sqlx(skip)]
+    pub assignees: Vec<Member>,
+    /// IDs of the tasks of the same project that have to be done before this one.
+    #[sqlx(skip)]
+    pub blocked_by: Vec<i32>,
+    /// The smaller steps the task is broken into, in the order they were added.
+    #[sqlx(skip)]
+    pub checklist: Vec<ChecklistItem>,
+    /// Labels of the owning project the task is marked with.
+    #[sqlx(skip)]
+    pub labels: Vec<Label>,
+}
+
+impl Task {
+    /// The priority tasks start at unless another one is picked.
+    pub const DEFAULT_PRIORITY: i32 = 2;
+    pub const LOWEST_PRIORITY: i32 = 3;
+    /// How many days ahead a due date counts as soon.
+    pub const DUE_SOON_DAYS: i64 = 3;
+
+    /// The priority as shown to users, like "P1".
+    pub fn priority_label(priority: i32) -> String {
+        format!("P{}", priority)
+    }
+
+    /// Reads a priority typed as "P1", "p1" or just "1".
+    pub fn parse_priority(input: &str) -> Option<i32> {
+        let input = input.trim();
+        let number = input
+            .strip_prefix(['P', 'p'])
+            .unwrap_or(input);
+        number
+            .parse()
+            .ok()
+            .filter(|priority| (0..=Task::LOWEST_PRIORITY).contains(priority))
+    }
+
+    /// P0 and P1 tasks are the ones that matter most, and are highlighted.
+    pub fn is_high_priority(&self) -> bool {
+        self.priority <= 1
+    }
+
+    /// Parses a due date as typed into a form: empty for none, or YYYY-MM-DD.
+    pub fn parse_due_date(input: &str) -> Option<Option<NaiveDate>> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Some(None);
+        }
+        NaiveDate::parse_from_str(input, "%Y-%m-%d").ok().map(Some)
+    }
+
+    /// How many of the task's checklist items are done.
+    pub fn checklist_done(&self) -> usize {
+        self.checklist.iter().filter(|item| item.done).count()
+    }
+
+    /// Whether the task carries a label by every one of the names.
+    pub fn has_labels(&self, names: &[String]) -> bool {
+        names
+            .iter()
+            .all(|name| self.labels.iter().any(|label| label.name == *name))
+    }
+}
+
+/// A label of a project, such as bug or frontend. The color is a name or hex code like "red" or "#ff8800".
+#[derive(Debug, FromRow, Clone, PartialEq, Eq)]
+pub struct Label {
+    #[sqlx(rename = "LabelID")]
+    pub label_id: i32,
+    #[sqlx(rename = "ProjectID")]
+    pub project_id: i32,
+    #[sqlx(rename = "Name")]
+    pub name: String,
+    #[sqlx(rename = "Color")]
+    pub color: String,
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct ChecklistItem {
+    #[sqlx(rename = "ItemID")]
+    pub item_id: i32,
+    #[sqlx(rename = "TaskID")]
+    pub task_id: i32,
+    #[sqlx(rename = "Title")]
+    pub title: String,
+    #[sqlx(rename = "Done")]
+    pub done: bool,
+    #[sqlx(rename = "Version")]
+    pub version: i32,
+}
+
+/// A task status defined by a project, shown with its emoji.
+#[derive(Debug, FromRow, Clone, PartialEq, Eq)]
+pub struct StatusDefinition {
+    #[sqlx(rename = "Name")]
+    pub name: String,
+    #[sqlx(rename = "Emoji")]
+    pub emoji: String,
+}
+
+/// The ordered task statuses of a project and the transitions allowed between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflow {
+    pub statuses: Vec<StatusDefinition>,
+    /// Allowed (from, to) status pairs. Staying in the same status is always allowed.
+    pub transitions: Vec<(String, String)>,
+}
+
+impl Default for Workflow {
+    /// The workflow every new project starts out with: not started, in progress and completed,
+    /// with every transition between them allowed.
+    fn default() -> Self {
+        let statuses: Vec<StatusDefinition> = [("NotStarted", "⏳"), ("InProgress", "🚧"), ("Completed", "✅")]
+            .into_iter()
+            .map(|(name, emoji)| StatusDefinition {
+                name: name.into(),
+                emoji: emoji.into(),
+            })
+            .collect();
+
+        let transitions = statuses
+            .iter()
+            .flat_map(|from| {
+                statuses
+                    .iter()
+                    .filter(move |to| to.name != from.name)
+                    .map(move |to| (from.name.clone(), to.name.clone()))
+            })
+            .collect();
+
+        Workflow {
+            statuses,
+            transitions,
+        }
+    }
 }
 
-#[derive(Debug, FromRow)]
+impl Workflow {
+    /// The status new tasks start in.
+    pub fn initial(&self) -> Option<&str> {
+        self.statuses.first().map(|s| s.name.as_str())
+    }
+
+    pub fn emoji(&self, status: &str) -> &str {
+        self.statuses
+            .iter()
+            .find(|s| s.name == status)
+            .map(|s| s.emoji.as_str())
+            .unwrap_or("❓")
+    }
+
+    pub fn can_transition(&self, from: &str, to: &str) -> bool {
+        from == to
+            || self
+                .transitions
+                .iter()
+                .any(|(f, t)| f == from && t == to)
+    }
+
+    /// Tasks count as done once they reach the last status of the workflow.
+    pub fn is_done(&self, status: &str) -> bool {
+        self.statuses.last().is_some_and(|s| s.name == status)
+    }
+
+    /// The first status after `from`, in workflow order and wrapping around, that `from` may move to.
+    pub fn next(&self, from: &str) -> Option<&str> {
+        let idx = self.statuses.iter().position(|s| s.name == from).unwrap_or(0);
+        (1..self.statuses.len())
+            .map(|offset| &self.statuses[(idx + offset) % self.statuses.len()].name)
+            .find(|to| self.can_transition(from, to))
+            .map(|to| to.as_str())
+    }
+
+    /// Finds the status named by `input`, ignoring case, spaces and underscores ("in progress" is `InProgress`).
+    pub fn parse(&self, input: &str) -> Option<&str> {
+        let normalize = |s: &str| -> String {
+            s.chars()
+                .filter(|c| !c.is_whitespace() && *c != '_')
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+
+        let wanted = normalize(input);
+        self.statuses
+            .iter()
+            .find(|s| normalize(&s.name) == wanted)
+            .map(|s| s.name.as_str())
+    }
+
+    /// The status names joined with '/', as a hint for forms.
+    pub fn status_names(&self) -> String {
+        let names: Vec<&str> = self.statuses.iter().map(|s| s.name.as_str()).collect();
+        names.join("/")
+    }
+}
+
+/// Hours a member worked on a task on a given day.
+#[derive(Debug, FromRow, Clone)]
+pub struct WorkLogEntry {
+    #[sqlx(rename = "EntryID")]
+    pub entry_id: i32,
+    #[sqlx(rename = "TaskID")]
+    pub task_id: i32,
+    #[sqlx(rename = "MemberID")]
+    pub member_id: i32,
+    #[sqlx(rename = "workDate")]
+    pub work_date: NaiveDate,
+    pub hours: i32,
+    pub note: String,
+}
+
+/// A comment a member posted on a task.
+#[derive(Debug, FromRow, Clone)]
+pub struct Comment {
+    #[sqlx(rename = "CommentID")]
+    pub comment_id: i32,
+    #[sqlx(rename = "TaskID")]
+    pub task_id: i32,
+    /// The author, the only member who can edit or delete the comment.
+    #[sqlx(rename = "MemberID")]
+    pub member_id: i32,
+    #[sqlx(rename = "Body")]
+    pub body: String,
+    #[sqlx(rename = "postedAt")]
+    pub posted_at: NaiveDateTime,
+    /// When the author last changed the body, if ever.
+    #[sqlx(rename = "editedAt")]
+    pub edited_at: Option<NaiveDateTime>,
+}
+
+/// The kinds of records whose changes are kept in the audit log.
+/// All of them but comments, which are deleted right away, can be moved to the trash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordKind {
+    Project,
+    Sprint,
+    Task,
+    Member,
+    ChecklistItem,
+    Comment,
+}
+
+impl RecordKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RecordKind::Project => "Project",
+            RecordKind::Sprint => "Sprint",
+            RecordKind::Task => "Task",
+            RecordKind::Member => "Member",
+            RecordKind::ChecklistItem => "ChecklistItem",
+            RecordKind::Comment => "Comment",
+        }
+    }
+
+    /// The kind a label written by [`RecordKind::label`] stands for.
+    pub fn from_label(label: &str) -> Option<RecordKind> {
+        match label {
+            "Project" => Some(RecordKind::Project),
+            "Sprint" => Some(RecordKind::Sprint),
+            "Task" => Some(RecordKind::Task),
+            "Member" => Some(RecordKind::Member),
+            "ChecklistItem" => Some(RecordKind::ChecklistItem),
+            "Comment" => Some(RecordKind::Comment),
+            _ => None,
+        }
+    }
+}
+
+/// A record in the trash. It stays hidden until it is restored or purged.
+#[derive(Debug, Clone)]
+pub struct TrashedRecord {
+    pub kind: RecordKind,
+    pub id: i32,
+    /// The title of the record, or the full name of a member.
+    pub title: String,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// One change recorded in the audit log. Changes to a single field carry its old and new value;
+/// changes to a record as a whole (moving it to the trash, for example) have no field.
+#[derive(Debug, FromRow, Clone)]
+pub struct AuditEntry {
+    #[sqlx(rename = "EntryID")]
+    pub entry_id: i32,
+    #[sqlx(rename = "Action")]
+    pub action: String,
+    #[sqlx(rename = "Field")]
+    pub field: Option<String>,
+    #[sqlx(rename = "OldValue")]
+    pub old_value: Option<String>,
+    #[sqlx(rename = "NewValue")]
+    pub new_value: Option<String>,
+    #[sqlx(rename = "changedAt")]
+    pub changed_at: NaiveDateTime,
+    /// The member TATs was acting as when the change was made, if any.
+    #[sqlx(rename = "MemberID")]
+    pub member_id: Option<i32>,
+}
+
+#[derive(Debug, FromRow, Clone)]
 pub struct RawProject {
     #[sqlx(rename = "ProjectID")]
     pub project_id: i32,
@@ -67,4 +453,6 @@ pub struct RawProject {
     pub title: String,
     #[sqlx(rename = "Description")]
     pub description: String,
+    #[sqlx(rename = "Version")]
+    pub version: i32,
 }
@@ -0,0 +1,1126 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::{crud, db::Db, models::*, undo::Operation};
+
+/// An error raised while reading from or writing to a [`Storage`].
+#[derive(Debug)]
+pub enum StorageError {
+    /// The underlying database reported an error.
+    Database(sqlx::Error),
+    /// The record being changed does not exist (anymore).
+    NotFound,
+    /// The change was refused because it would break a rule of TATs.
+    Invalid(String),
+    /// The record was updated by someone else since the version the change was based on.
+    Conflict,
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Database(e) => write!(f, "{}", e),
+            StorageError::NotFound => write!(f, "the record no longer exists"),
+            StorageError::Invalid(reason) => write!(f, "{}", reason),
+            StorageError::Conflict => write!(f, "someone else changed the record in the meantime"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<sqlx::Error> for StorageError {
+    fn from(e: sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::RowNotFound => StorageError::NotFound,
+            e => StorageError::Database(e),
+        }
+    }
+}
+
+impl From<crud::Refusal> for StorageError {
+    fn from(refusal: crud::Refusal) -> Self {
+        match refusal {
+            crud::Refusal::Conflict => StorageError::Conflict,
+            crud::Refusal::Assignment => StorageError::Invalid(
+                "only members of the task's project can be assigned to it".into(),
+            ),
+            crud::Refusal::Labeling => {
+                StorageError::Invalid("only labels of the task's project can be put on it".into())
+            }
+            crud::Refusal::NotFound => StorageError::NotFound,
+            crud::Refusal::Invalid(e) => e,
+        }
+    }
+}
+
+impl From<StorageError> for std::io::Error {
+    fn from(e: StorageError) -> Self {
+        std::io::Error::other(e)
+    }
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// Turns the outcome of a versioned update into a conflict if the record was not at the expected version.
+fn updated(applied: bool) -> StorageResult<()> {
+    if applied {
+        Ok(())
+    } else {
+        Err(StorageError::Conflict)
+    }
+}
+
+/// Work log entries must record some actual work.
+pub(crate) fn check_logged_hours(hours: i32) -> StorageResult<()> {
+    if hours > 0 {
+        Ok(())
+    } else {
+        Err(StorageError::Invalid("logged hours must be more than 0".into()))
+    }
+}
+
+/// Due dates fall within the sprint of their task, from `start_date` to `end_date`.
+pub(crate) fn check_due_date(
+    due_date: Option<NaiveDate>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> StorageResult<()> {
+    match due_date {
+        Some(due_date) if due_date < start_date || due_date > end_date => Err(StorageError::Invalid(
+            format!("due dates must fall within the sprint, from {} to {}", start_date, end_date),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks a task's due date against the sprint it is in. Tasks in the backlog are not scheduled yet,
+/// so they cannot be due.
+pub(crate) fn check_due_date_in(sprint: &RawSprint, due_date: Option<NaiveDate>) -> StorageResult<()> {
+    if sprint.backlog_of.is_some() && due_date.is_some() {
+        return Err(StorageError::Invalid(
+            "tasks in the backlog are not scheduled, so they cannot be due".into(),
+        ));
+    }
+    check_due_date(due_date, sprint.start_date, sprint.end_date)
+}
+
+/// The backlog of a project lives as long as the project, and has no dates or title to change.
+pub(crate) fn check_not_backlog(sprint: &RawSprint) -> StorageResult<()> {
+    if sprint.backlog_of.is_some() {
+        Err(StorageError::Invalid(
+            "the backlog of a project cannot be edited or deleted".into(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Tasks only move between the sprints of their own project.
+pub(crate) fn check_move(task_project_id: i32, sprint_project_id: i32) -> StorageResult<()> {
+    if task_project_id == sprint_project_id {
+        Ok(())
+    } else {
+        Err(StorageError::Invalid(
+            "tasks can only move to sprints of their own project".into(),
+        ))
+    }
+}
+
+/// Comments must say something.
+pub(crate) fn check_comment_body(body: &str) -> StorageResult<()> {
+    if body.trim().is_empty() {
+        Err(StorageError::Invalid("comments cannot be empty".into()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Refusal of a change to a comment by someone other than its author.
+pub(crate) fn not_comment_author() -> StorageError {
+    StorageError::Invalid("only the author of a comment can change it".into())
+}
+
+/// Task priorities run from P0 to P3.
+pub(crate) fn check_priority(priority: i32) -> StorageResult<()> {
+    if (0..=Task::LOWEST_PRIORITY).contains(&priority) {
+        Ok(())
+    } else {
+        Err(StorageError::Invalid(format!(
+            "priorities run from P0 to {}",
+            Task::priority_label(Task::LOWEST_PRIORITY)
+        )))
+    }
+}
+
+/// Tasks can only be in a status defined by the workflow of their project.
+pub(crate) fn check_status_defined(workflow: &Workflow, status: &str) -> StorageResult<()> {
+    if workflow.statuses.iter().any(|s| s.name == status) {
+        Ok(())
+    } else {
+        Err(StorageError::Invalid(format!(
+            "'{}' is not a status of this project ({})",
+            status,
+            workflow.status_names()
+        )))
+    }
+}
+
+/// Tasks can only move between statuses along the transitions of their project's workflow.
+pub(crate) fn check_transition(workflow: &Workflow, from: &str, to: &str) -> StorageResult<()> {
+    check_status_defined(workflow, to)?;
+    if workflow.can_transition(from, to) {
+        Ok(())
+    } else {
+        Err(StorageError::Invalid(format!(
+            "tasks cannot move from {} to {} in this project",
+            from, to
+        )))
+    }
+}
+
+/// A task can only block other tasks of its own project, and never itself, directly or through other tasks.
+/// `dependencies` are the existing (blocker, blocked) links.
+pub(crate) fn check_dependency(
+    dependencies: &[(i32, i32)],
+    (blocker_id, blocker_project_id): (i32, i32),
+    (blocked_id, blocked_project_id): (i32, i32),
+) -> StorageResult<()> {
+    if blocker_project_id != blocked_project_id {
+        return Err(StorageError::Invalid(
+            "tasks can only depend on tasks of the same project".into(),
+        ));
+    }
+    if blocker_id == blocked_id {
+        return Err(StorageError::Invalid("a task cannot block itself".into()));
+    }
+    if dependencies.contains(&(blocker_id, blocked_id)) {
+        return Err(StorageError::Invalid(format!(
+            "task #{} already blocks task #{}",
+            blocker_id, blocked_id
+        )));
+    }
+
+    // The new link closes a cycle if the blocker already waits for the blocked task.
+    let mut waited_for = vec![blocked_id];
+    let mut idx = 0;
+    while idx < waited_for.len() {
+        let task_id = waited_for[idx];
+        for (blocker, blocked) in dependencies {
+            if *blocker == task_id && !waited_for.contains(blocked) {
+                waited_for.push(*blocked);
+            }
+        }
+        idx += 1;
+    }
+    if waited_for.contains(&blocker_id) {
+        return Err(StorageError::Invalid(format!(
+            "task #{} already waits for task #{}, so it cannot block it",
+            blocker_id, blocked_id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Label names cannot be empty and are unique within their project.
+/// `labels` are the other labels of the project, leaving out the one being renamed.
+pub(crate) fn check_label_name(labels: &[Label], name: &str) -> StorageResult<()> {
+    if name.trim().is_empty() {
+        return Err(StorageError::Invalid("label names cannot be empty".into()));
+    }
+    if labels.iter().any(|label| label.name == name) {
+        return Err(StorageError::Invalid(format!(
+            "the project already has a label named '{}'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// A workflow needs at least one status, unique non-empty names and transitions between its own statuses.
+/// `in_use` are the statuses tasks of the project are currently in, none of which may be removed.
+pub(crate) fn check_workflow(workflow: &Workflow, in_use: &[String]) -> StorageResult<()> {
+    if workflow.statuses.is_empty() {
+        return Err(StorageError::Invalid("a workflow needs at least one status".into()));
+    }
+
+    for (idx, status) in workflow.statuses.iter().enumerate() {
+        if status.name.trim().is_empty() {
+            return Err(StorageError::Invalid("status names cannot be empty".into()));
+        }
+        if workflow.statuses[..idx].iter().any(|s| s.name == status.name) {
+            return Err(StorageError::Invalid(format!(
+                "status '{}' is defined twice",
+                status.name
+            )));
+        }
+    }
+
+    for (from, to) in &workflow.transitions {
+        check_status_defined(workflow, from)?;
+        check_status_defined(workflow, to)?;
+    }
+
+    if let Some(status) = in_use
+        .iter()
+        .find(|status| check_status_defined(workflow, status).is_err())
+    {
+        return Err(StorageError::Invalid(format!(
+            "status '{}' is still used by tasks of this project",
+            status
+        )));
+    }
+
+    Ok(())
+}
+
+/// Every read and write the UI performs on projects, sprints, tasks, members and memberships.
+/// The UI only talks to this trait, so its tests can run against the in-memory store.
+/// Updates take the version of the record they are based on, and fail with [`StorageError::Conflict`]
+/// if the record has moved on since.
+// The trait is only used inside TATs, so the futures not being `Send` is fine.
+#[allow(async_fn_in_trait)]
+pub trait Storage: Clone {
+    /// Fetches all projects, their sprints and the tasks of each sprint as a single nested collection.
+    /// Records in the trash, and everything under them, are left out. The backlog of each project
+    /// comes first among its sprints.
+    async fn fetch_projects(&self) -> StorageResult<Vec<Project>>;
+    /// Creates a project with the default workflow and an empty backlog, and returns its ID.
+    async fn create_project(&self, title: &str, description: &str) -> StorageResult<i32>;
+    async fn update_project(
+        &self,
+        project_id: i32,
+        version: i32,
+        title: &str,
+        description: &str,
+    ) -> StorageResult<()>;
+    /// Permanently deletes the project along with its sprints and memberships.
+    async fn delete_project(&self, project_id: i32) -> StorageResult<()>;
+    /// Replaces the statuses and transitions of a project.
+    /// Statuses that tasks of the project are still in cannot be removed.
+    async fn update_workflow(&self, project_id: i32, workflow: &Workflow) -> StorageResult<()>;
+
+    /// Adds a label to a project. Label names are unique within their project.
+    async fn create_label(&self, project_id: i32, name: &str, color: &str) -> StorageResult<Label>;
+    async fn update_label(&self, label_id: i32, name: &str, color: &str) -> StorageResult<()>;
+    /// Permanently deletes a label, taking it off every task carrying it.
+    async fn delete_label(&self, label_id: i32) -> StorageResult<()>;
+
+    /// Creates a sprint and links it to the project with the provided ID.
+    /// Either both are written or neither is.
+    async fn create_sprint(
+        &self,
+        project_id: i32,
+        title: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> StorageResult<Sprint>;
+    /// Updates a sprint. The due dates of its tasks have to stay within the new dates.
+    /// The backlog of a project cannot be updated.
+    async fn update_sprint(
+        &self,
+        sprint_id: i32,
+        version: i32,
+        title: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> StorageResult<()>;
+    /// Permanently deletes the sprint along with its tasks. The backlog of a project cannot be deleted.
+    async fn delete_sprint(&self, sprint_id: i32) -> StorageResult<()>;
+
+    /// Creates a task and links it to the sprint with the provided ID.
+    /// Either both are written or neither is. The status must be defined by the sprint's project,
+    /// and the due date has to fall within the sprint. Tasks in the backlog cannot be due.
+    #[allow(clippy::too_many_arguments)]
+    async fn create_task(
+        &self,
+        sprint_id: i32,
+        title: &str,
+        status: &str,
+        description: &str,
+        estimated_hours: i32,
+        priority: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<Task>;
+    /// Updates a task. A status change must be an allowed transition of the task's project,
+    /// and the due date has to fall within the task's sprint.
+    #[allow(clippy::too_many_arguments)]
+    async fn update_task(
+        &self,
+        task_id: i32,
+        version: i32,
+        title: &str,
+        status: &str,
+        description: &str,
+        estimated_hours: i32,
+        priority: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<()>;
+    async fn update_task_status(&self, task_id: i32, version: i32, status: &str)
+        -> StorageResult<()>;
+    /// Moves a task to another sprint of its project, or its backlog, giving it `due_date` there.
+    /// The due date has to fall within the new sprint, and tasks moving to the backlog cannot be due.
+    async fn move_task(
+        &self,
+        task_id: i32,
+        version: i32,
+        sprint_id: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<()>;
+    /// Permanently deletes the task.
+    async fn delete_task(&self, task_id: i32) -> StorageResult<()>;
+    /// Replaces the assignees of a task. Only members of the project owning the task can be assigned.
+    async fn set_task_assignees(&self, task_id: i32, member_ids: &[i32]) -> StorageResult<()>;
+    /// Replaces the labels of a task. Only labels of the project owning the task can be put on it.
+    async fn set_task_labels(&self, task_id: i32, label_ids: &[i32]) -> StorageResult<()>;
+    /// Makes one task block another of the same project, in whichever sprints they are.
+    /// Links that would make tasks wait for each other, directly or through other tasks, are refused.
+    async fn add_task_dependency(&self, blocker_id: i32, blocked_id: i32) -> StorageResult<()>;
+    async fn remove_task_dependency(&self, blocker_id: i32, blocked_id: i32) -> StorageResult<()>;
+    /// Adds an item, not done yet, to the end of a task's checklist.
+    async fn create_checklist_item(&self, task_id: i32, title: &str) -> StorageResult<ChecklistItem>;
+    /// Renames a checklist item or ticks it off.
+    async fn update_checklist_item(
+        &self,
+        item_id: i32,
+        version: i32,
+        title: &str,
+        done: bool,
+    ) -> StorageResult<()>;
+    /// Permanently deletes a checklist item.
+    async fn delete_checklist_item(&self, item_id: i32) -> StorageResult<()>;
+
+    /// Fetches every member outside the trash, regardless of the projects they contribute to.
+    async fn fetch_members(&self) -> StorageResult<Vec<Member>>;
+    async fn create_member(
+        &self,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        phone: &str,
+    ) -> StorageResult<()>;
+    async fn update_member(
+        &self,
+        member_id: i32,
+        version: i32,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        phone: &str,
+    ) -> StorageResult<()>;
+    /// Permanently deletes a member along with every project membership, task assignment and work log entry they hold.
+    async fn delete_member(&self, member_id: i32) -> StorageResult<()>;
+
+    /// Moves a record to the trash, hiding it and everything under it until it is restored.
+    /// The backlog of a project only goes to the trash along with the project.
+    async fn move_to_trash(&self, kind: RecordKind, id: i32) -> StorageResult<()>;
+    /// Takes a record out of the trash, showing it again.
+    async fn restore_from_trash(&self, kind: RecordKind, id: i32) -> StorageResult<()>;
+    /// Fetches every record in the trash, most recently deleted first.
+    async fn fetch_trash(&self) -> StorageResult<Vec<TrashedRecord>>;
+
+    /// Fetches the changes made to a record, newest first.
+    async fn fetch_history(&self, kind: RecordKind, id: i32) -> StorageResult<Vec<AuditEntry>>;
+    /// Fetches the ID of the latest audit log entry, or 0 if nothing has been recorded yet.
+    /// Every change goes through the audit log, so this tells whether anything changed since.
+    async fn fetch_latest_change(&self) -> StorageResult<i32>;
+    /// Fetches the audit log entries after `entry_id`, oldest first, with the kind and ID of the record
+    /// each of them is about.
+    async fn fetch_changes_since(
+        &self,
+        entry_id: i32,
+    ) -> StorageResult<Vec<(RecordKind, i32, AuditEntry)>>;
+
+    /// Applies the operations in order, under the same rules as the methods they stand for.
+    /// Either all of them take effect or, if one fails, none do.
+    async fn apply(&self, operations: &[Operation]) -> StorageResult<()>;
+
+    async fn fetch_project_members(&self, project_id: i32) -> StorageResult<Vec<Member>>;
+    async fn add_project_member(&self, member_id: i32, project_id: i32) -> StorageResult<()>;
+    /// Removes a member from a project, unassigning them from every task of the project.
+    async fn remove_project_member(&self, member_id: i32, project_id: i32) -> StorageResult<()>;
+
+    /// Fetches the work log of a task, oldest entries first.
+    async fn fetch_work_log(&self, task_id: i32) -> StorageResult<Vec<WorkLogEntry>>;
+    /// Logs hours worked on a task, which are added to its committed hours.
+    /// Only members of the project owning the task can log work on it.
+    async fn add_work_log_entry(
+        &self,
+        task_id: i32,
+        member_id: i32,
+        work_date: NaiveDate,
+        hours: i32,
+        note: &str,
+    ) -> StorageResult<WorkLogEntry>;
+    /// Removes a work log entry, taking its hours off the task's committed hours.
+    async fn remove_work_log_entry(&self, entry_id: i32) -> StorageResult<()>;
+
+    /// Fetches the comments on a task, oldest first.
+    async fn fetch_comments(&self, task_id: i32) -> StorageResult<Vec<Comment>>;
+    /// Posts a comment by `member_id` on a task.
+    async fn add_comment(&self, task_id: i32, member_id: i32, body: &str) -> StorageResult<Comment>;
+    /// Changes the body of a comment. Only its author, `member_id`, can do so.
+    async fn update_comment(&self, comment_id: i32, member_id: i32, body: &str) -> StorageResult<()>;
+    /// Deletes a comment. Only its author, `member_id`, can do so.
+    async fn delete_comment(&self, comment_id: i32, member_id: i32) -> StorageResult<()>;
+}
+
+impl Storage for Db {
+    async fn fetch_projects(&self) -> StorageResult<Vec<Project>> {
+        Ok(crud::fetch_projects(self).await?)
+    }
+
+    async fn create_project(&self, title: &str, description: &str) -> StorageResult<i32> {
+        Ok(crud::create_project(self, title, description).await?)
+    }
+
+    async fn update_project(
+        &self,
+        project_id: i32,
+        version: i32,
+        title: &str,
+        description: &str,
+    ) -> StorageResult<()> {
+        updated(crud::update_project(self, project_id, version, title, description).await?)
+    }
+
+    async fn delete_project(&self, project_id: i32) -> StorageResult<()> {
+        Ok(crud::delete_project_by_id(self, project_id).await?)
+    }
+
+    async fn update_workflow(&self, project_id: i32, workflow: &Workflow) -> StorageResult<()> {
+        Ok(crud::replace_workflow(self, project_id, workflow).await??)
+    }
+
+    async fn create_label(&self, project_id: i32, name: &str, color: &str) -> StorageResult<Label> {
+        check_label_name(&crud::fetch_labels(self, project_id).await?, name)?;
+        Ok(crud::create_label(self, project_id, name, color).await?)
+    }
+
+    async fn update_label(&self, label_id: i32, name: &str, color: &str) -> StorageResult<()> {
+        let project_id = crud::fetch_label(self, label_id).await?.project_id;
+        let mut others = crud::fetch_labels(self, project_id).await?;
+        others.retain(|label| label.label_id != label_id);
+        check_label_name(&others, name)?;
+        Ok(crud::update_label(self, label_id, name, color).await?)
+    }
+
+    async fn delete_label(&self, label_id: i32) -> StorageResult<()> {
+        Ok(crud::delete_label_by_id(self, label_id).await?)
+    }
+
+    async fn create_sprint(
+        &self,
+        project_id: i32,
+        title: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> StorageResult<Sprint> {
+        Ok(crud::create_sprint_for_project(self, project_id, title, start_date, end_date).await?)
+    }
+
+    async fn update_sprint(
+        &self,
+        sprint_id: i32,
+        version: i32,
+        title: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> StorageResult<()> {
+        Ok(crud::update_sprint(self, sprint_id, version, title, start_date, end_date).await??)
+    }
+
+    async fn delete_sprint(&self, sprint_id: i32) -> StorageResult<()> {
+        check_not_backlog(&crud::fetch_sprint(self, sprint_id).await?)?;
+        Ok(crud::delete_sprint_by_id(self, sprint_id).await?)
+    }
+
+    async fn create_task(
+        &self,
+        sprint_id: i32,
+        title: &str,
+        status: &str,
+        description: &str,
+        estimated_hours: i32,
+        priority: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<Task> {
+        check_priority(priority)?;
+        let project_id = crud::fetch_project_id_of_sprint(self, sprint_id).await?;
+        check_status_defined(&crud::fetch_workflow(self, project_id).await?, status)?;
+        check_due_date_in(&crud::fetch_sprint(self, sprint_id).await?, due_date)?;
+        Ok(crud::create_task_in_sprint(
+            self,
+            sprint_id,
+            title,
+            status,
+            description,
+            estimated_hours,
+            priority,
+            due_date,
+        )
+        .await?)
+    }
+
+    async fn update_task(
+        &self,
+        task_id: i32,
+        version: i32,
+        title: &str,
+        status: &str,
+        description: &str,
+        estimated_hours: i32,
+        priority: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<()> {
+        Ok(crud::update_task(
+            self,
+            task_id,
+            version,
+            title,
+            status,
+            description,
+            estimated_hours,
+            priority,
+            due_date,
+        )
+        .await??)
+    }
+
+    async fn update_task_status(
+        &self,
+        task_id: i32,
+        version: i32,
+        status: &str,
+    ) -> StorageResult<()> {
+        Ok(crud::update_task_status(self, task_id, version, status).await??)
+    }
+
+    async fn move_task(
+        &self,
+        task_id: i32,
+        version: i32,
+        sprint_id: i32,
+        due_date: Option<NaiveDate>,
+    ) -> StorageResult<()> {
+        Ok(crud::move_task(self, task_id, version, sprint_id, due_date).await??)
+    }
+
+    async fn delete_task(&self, task_id: i32) -> StorageResult<()> {
+        Ok(crud::delete_task_by_id(self, task_id).await?)
+    }
+
+    async fn set_task_assignees(&self, task_id: i32, member_ids: &[i32]) -> StorageResult<()> {
+        if crud::set_task_assignees(self, task_id, member_ids).await? {
+            Ok(())
+        } else {
+            Err(StorageError::Invalid(
+                "only members of the task's project can be assigned to it".into(),
+            ))
+        }
+    }
+
+    async fn set_task_labels(&self, task_id: i32, label_ids: &[i32]) -> StorageResult<()> {
+        if crud::set_task_labels(self, task_id, label_ids).await? {
+            Ok(())
+        } else {
+            Err(StorageError::Invalid(
+                "only labels of the task's project can be put on it".into(),
+            ))
+        }
+    }
+
+    async fn add_task_dependency(&self, blocker_id: i32, blocked_id: i32) -> StorageResult<()> {
+        Ok(crud::add_task_dependency(self, blocker_id, blocked_id).await??)
+    }
+
+    async fn remove_task_dependency(&self, blocker_id: i32, blocked_id: i32) -> StorageResult<()> {
+        if crud::remove_task_dependency(self, blocker_id, blocked_id).await? {
+            Ok(())
+        } else {
+            Err(StorageError::NotFound)
+        }
+    }
+
+    async fn create_checklist_item(&self, task_id: i32, title: &str) -> StorageResult<ChecklistItem> {
+        Ok(crud::create_checklist_item(self, task_id, title).await?)
+    }
+
+    async fn update_checklist_item(
+        &self,
+        item_id: i32,
+        version: i32,
+        title: &str,
+        done: bool,
+    ) -> StorageResult<()> {
+        updated(crud::update_checklist_item(self, item_id, version, title, done).await?)
+    }
+
+    async fn delete_checklist_item(&self, item_id: i32) -> StorageResult<()> {
+        Ok(crud::delete_checklist_item_by_id(self, item_id).await?)
+    }
+
+    async fn fetch_members(&self) -> StorageResult<Vec<Member>> {
+        Ok(crud::fetch_members(self).await?)
+    }
+
+    async fn create_member(
+        &self,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        phone: &str,
+    ) -> StorageResult<()> {
+        Ok(crud::create_member(self, first_name, last_name, email, phone).await?)
+    }
+
+    async fn update_member(
+        &self,
+        member_id: i32,
+        version: i32,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        phone: &str,
+    ) -> StorageResult<()> {
+        updated(
+            crud::update_member(self, member_id, version, first_name, last_name, email, phone)
+                .await?,
+        )
+    }
+
+    async fn delete_member(&self, member_id: i32) -> StorageResult<()> {
+        Ok(crud::delete_member_by_id(self, member_id).await?)
+    }
+
+    async fn move_to_trash(&self, kind: RecordKind, id: i32) -> StorageResult<()> {
+        if kind == RecordKind::Sprint {
+            check_not_backlog(&crud::fetch_sprint(self, id).await?)?;
+        }
+        Ok(crud::move_to_trash(self, kind, id).await?)
+    }
+
+    async fn restore_from_trash(&self, kind: RecordKind, id: i32) -> StorageResult<()> {
+        Ok(crud::restore_from_trash(self, kind, id).await?)
+    }
+
+    async fn fetch_trash(&self) -> StorageResult<Vec<TrashedRecord>> {
+        Ok(crud::fetch_trash(self).await?)
+    }
+
+    async fn fetch_history(&self, kind: RecordKind, id: i32) -> StorageResult<Vec<AuditEntry>> {
+        Ok(crud::fetch_history(self, kind, id).await?)
+    }
+
+    async fn fetch_latest_change(&self) -> StorageResult<i32> {
+        Ok(crud::fetch_latest_change(self).await?)
+    }
+
+    async fn fetch_changes_since(
+        &self,
+        entry_id: i32,
+    ) -> StorageResult<Vec<(RecordKind, i32, AuditEntry)>> {
+        Ok(crud::fetch_changes_since(self, entry_id).await?)
+    }
+
+    async fn apply(&self, operations: &[Operation]) -> StorageResult<()> {
+        Ok(crud::apply_operations(self, operations).await??)
+    }
+
+    async fn fetch_project_members(&self, project_id: i32) -> StorageResult<Vec<Member>> {
+        Ok(crud::fetch_members_by_project_id(self, project_id).await?)
+    }
+
+    async fn add_project_member(&self, member_id: i32, project_id: i32) -> StorageResult<()> {
+        Ok(crud::add_project_member(self, member_id, project_id).await?)
+    }
+
+    async fn remove_project_member(&self, member_id: i32, project_id: i32) -> StorageResult<()> {
+        Ok(crud::remove_project_member(self, member_id, project_id).await?)
+    }
+
+    async fn fetch_work_log(&self, task_id: i32) -> StorageResult<Vec<WorkLogEntry>> {
+        Ok(crud::fetch_work_log(self, task_id).await?)
+    }
+
+    async fn add_work_log_entry(
+        &self,
+        task_id: i32,
+        member_id: i32,
+        work_date: NaiveDate,
+        hours: i32,
+        note: &str,
+    ) -> StorageResult<WorkLogEntry> {
+        check_logged_hours(hours)?;
+        crud::add_work_log_entry(self, task_id, member_id, work_date, hours, note)
+            .await?
+            .ok_or_else(|| {
+                StorageError::Invalid("only members of the task's project can log work on it".into())
+            })
+    }
+
+    async fn remove_work_log_entry(&self, entry_id: i32) -> StorageResult<()> {
+        Ok(crud::remove_work_log_entry(self, entry_id).await?)
+    }
+
+    async fn fetch_comments(&self, task_id: i32) -> StorageResult<Vec<Comment>> {
+        Ok(crud::fetch_comments(self, task_id).await?)
+    }
+
+    async fn add_comment(&self, task_id: i32, member_id: i32, body: &str) -> StorageResult<Comment> {
+        check_comment_body(body)?;
+        Ok(crud::add_comment(self, task_id, member_id, body).await?)
+    }
+
+    async fn update_comment(&self, comment_id: i32, member_id: i32, body: &str) -> StorageResult<()> {
+        check_comment_body(body)?;
+        if crud::update_comment(self, comment_id, member_id, body).await? {
+            Ok(())
+        } else {
+            Err(not_comment_author())
+        }
+    }
+
+    async fn delete_comment(&self, comment_id: i32, member_id: i32) -> StorageResult<()> {
+        if crud::delete_comment_by_id(self, comment_id, member_id).await? {
+            Ok(())
+        } else {
+            Err(not_comment_author())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::with_pool;
+    use crate::memory::MemoryStore;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    /// Shrinks a sprint and then gives its task a due date past the new end, in one batch.
+    /// The second operation has to be checked against the shrunk sprint, refusing the whole batch.
+    async fn checks_see_earlier_operations(store: impl Storage) {
+        let project_id = store.create_project("Demo", "").await.unwrap();
+        let sprint = store
+            .create_sprint(project_id, "S1", date(1), date(14))
+            .await
+            .unwrap();
+        let task = store
+            .create_task(sprint.sprint_id, "T1", "NotStarted", "", 3, 2, None)
+            .await
+            .unwrap();
+
+        let result = store
+            .apply(&[
+                Operation::UpdateSprint {
+                    sprint_id: sprint.sprint_id,
+                    version: sprint.version,
+                    title: "S1".into(),
+                    start_date: date(1),
+                    end_date: date(5),
+                },
+                Operation::UpdateTask {
+                    task_id: task.task_id,
+                    version: task.version,
+                    title: "T1".into(),
+                    status: "NotStarted".into(),
+                    description: String::new(),
+                    estimated_hours: 3,
+                    priority: 2,
+                    due_date: Some(date(10)),
+                },
+            ])
+            .await;
+        assert!(matches!(result, Err(StorageError::Invalid(_))));
+
+        let projects = store.fetch_projects().await.unwrap();
+        let project = projects.iter().find(|p| p.proj_id == project_id).unwrap();
+        let sprint = project.sprints.iter().find(|s| !s.backlog).unwrap();
+        assert_eq!(sprint.end_date, date(14));
+        assert_eq!(sprint.tasks[0].due_date, None);
+
+        store.delete_project(project_id).await.unwrap();
+    }
+
+    /// Posts, edits and deletes a comment, each of which has to show up in the audit log.
+    async fn comments_are_audited(store: impl Storage) {
+        let project_id = store.create_project("Demo", "").await.unwrap();
+        let sprint = store
+            .create_sprint(project_id, "S1", date(1), date(14))
+            .await
+            .unwrap();
+        let task = store
+            .create_task(sprint.sprint_id, "T1", "NotStarted", "", 3, 2, None)
+            .await
+            .unwrap();
+        let email = format!("author-{}@example.com", task.task_id);
+        store
+            .create_member("Ann", "Author", &email, "")
+            .await
+            .unwrap();
+        let members = store.fetch_members().await.unwrap();
+        let member_id = members.iter().find(|m| m.email == email).unwrap().member_id;
+
+        let latest = store.fetch_latest_change().await.unwrap();
+        let comment = store
+            .add_comment(task.task_id, member_id, "First")
+            .await
+            .unwrap();
+        store
+            .update_comment(comment.comment_id, member_id, "Edited")
+            .await
+            .unwrap();
+        store
+            .delete_comment(comment.comment_id, member_id)
+            .await
+            .unwrap();
+
+        let changes: Vec<_> = store
+            .fetch_changes_since(latest)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|(kind, id, _)| (*kind, *id) == (RecordKind::Comment, comment.comment_id))
+            .map(|(_, _, entry)| (entry.action, entry.field, entry.old_value, entry.new_value))
+            .collect();
+        let change = |action: &str, field: &str, old: Option<&str>, new: Option<&str>| {
+            (
+                action.to_string(),
+                Some(field.to_string()),
+                old.map(Into::into),
+                new.map(Into::into),
+            )
+        };
+        assert_eq!(
+            changes,
+            vec![
+                change("create", "Body", None, Some("First")),
+                change("create", "TaskID", None, Some(&task.task_id.to_string())),
+                change("update", "Body", Some("First"), Some("Edited")),
+                change("delete", "Body", Some("Edited"), None),
+            ]
+        );
+
+        store.delete_member(member_id).await.unwrap();
+        store.delete_project(project_id).await.unwrap();
+    }
+
+    /// Writes statuses straight to the database, past the checks of [`Storage`]: the database itself
+    /// has to refuse a status the task's project does not define, also when the task moves to another project.
+    async fn undefined_statuses_are_refused(db: Db) {
+        let project_id = db.create_project("Demo", "").await.unwrap();
+        let sprint = db
+            .create_sprint(project_id, "S1", date(1), date(14))
+            .await
+            .unwrap();
+        let task = db
+            .create_task(sprint.sprint_id, "T1", "NotStarted", "", 3, 2, None)
+            .await
+            .unwrap();
+        let other_project_id = db.create_project("Other", "").await.unwrap();
+        let other_workflow = Workflow {
+            statuses: vec![StatusDefinition {
+                name: "Todo".into(),
+                emoji: "📝".into(),
+            }],
+            transitions: Vec::new(),
+        };
+        db.update_workflow(other_project_id, &other_workflow)
+            .await
+            .unwrap();
+        let other_sprint = db
+            .create_sprint(other_project_id, "S1", date(1), date(14))
+            .await
+            .unwrap();
+
+        let (db, task_id) = (&db, task.task_id);
+        let set_status = |status: &'static str| async move {
+            with_pool!(db, pool => sqlx::query("UPDATE Task SET Status = ? WHERE TaskID = ?")
+                .bind(status)
+                .bind(task_id)
+                .execute(pool)
+                .await
+                .map(|_| ()))
+        };
+        assert!(set_status("Todo").await.is_err());
+        set_status("Completed").await.unwrap();
+        let moved = with_pool!(db, pool => sqlx::query("UPDATE PartOf SET SprintID = ? WHERE TaskID = ?")
+            .bind(other_sprint.sprint_id)
+            .bind(task_id)
+            .execute(pool)
+            .await
+            .map(|_| ()));
+        assert!(moved.is_err());
+
+        db.delete_project(project_id).await.unwrap();
+        db.delete_project(other_project_id).await.unwrap();
+    }
+
+    /// The PostgreSQL database the Postgres tests run against, from `TATS_TEST_POSTGRES_URL`.
+    /// They are skipped if it is not set. Each run adds its own projects, so any scratch database will do.
+    fn postgres_url() -> Option<String> {
+        let url = std::env::var("TATS_TEST_POSTGRES_URL").ok();
+        if url.is_none() {
+            eprintln!("TATS_TEST_POSTGRES_URL is not set, skipping");
+        }
+        url
+    }
+
+    #[tokio::test]
+    async fn memory_store_checks_each_operation_of_a_batch() {
+        checks_see_earlier_operations(MemoryStore::default()).await;
+    }
+
+    #[tokio::test]
+    async fn database_checks_each_operation_of_a_batch_inside_the_transaction() {
+        let path = std::env::temp_dir().join(format!("tats-apply-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Db::connect(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        checks_see_earlier_operations(db).await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn memory_store_audits_comments() {
+        comments_are_audited(MemoryStore::default()).await;
+    }
+
+    #[tokio::test]
+    async fn database_audits_comments() {
+        let path = std::env::temp_dir().join(format!("tats-comments-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Db::connect(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        comments_are_audited(db).await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn database_refuses_undefined_statuses() {
+        let path = std::env::temp_dir().join(format!("tats-statuses-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Db::connect(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        undefined_statuses_are_refused(db).await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn postgres_refuses_undefined_statuses() {
+        let Some(url) = postgres_url() else { return };
+        undefined_statuses_are_refused(Db::connect(&url).await.unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn postgres_audits_comments() {
+        let Some(url) = postgres_url() else { return };
+        comments_are_audited(Db::connect(&url).await.unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn postgres_checks_each_operation_of_a_batch_inside_the_transaction() {
+        let Some(url) = postgres_url() else { return };
+        checks_see_earlier_operations(Db::connect(&url).await.unwrap()).await;
+    }
+
+    /// Goes through the queries that differ the most once translated for Postgres:
+    /// inserts returning their ID, versioned updates, the trash and the audit log.
+    #[tokio::test]
+    async fn postgres_round_trip() {
+        let Some(url) = postgres_url() else { return };
+        let db = Db::connect(&url).await.unwrap();
+
+        let project_id = db
+            .create_project("Round trip", "'quoted?' text")
+            .await
+            .unwrap();
+        let sprint = db
+            .create_sprint(project_id, "S1", date(1), date(14))
+            .await
+            .unwrap();
+        let task = db
+            .create_task(
+                sprint.sprint_id,
+                "T1",
+                "NotStarted",
+                "",
+                3,
+                2,
+                Some(date(7)),
+            )
+            .await
+            .unwrap();
+        let item = db
+            .create_checklist_item(task.task_id, "Item")
+            .await
+            .unwrap();
+        db.update_task(
+            task.task_id,
+            task.version,
+            "T1",
+            "InProgress",
+            "",
+            3,
+            1,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            db.update_task(
+                task.task_id,
+                task.version,
+                "T1",
+                "InProgress",
+                "",
+                3,
+                1,
+                None
+            )
+            .await,
+            Err(StorageError::Conflict)
+        ));
+
+        let project =
+            |projects: Vec<Project>| projects.into_iter().find(|p| p.proj_id == project_id);
+        let fetched = project(db.fetch_projects().await.unwrap()).unwrap();
+        assert_eq!(fetched.desc, "'quoted?' text");
+        assert!(fetched.sprints[0].backlog);
+        let fetched_task = &fetched.sprints[1].tasks[0];
+        assert_eq!(
+            (fetched_task.status.as_str(), fetched_task.priority),
+            ("InProgress", 1)
+        );
+        assert_eq!(fetched_task.checklist[0].item_id, item.item_id);
+
+        let latest = db.fetch_latest_change().await.unwrap();
+        let records = [
+            (RecordKind::ChecklistItem, item.item_id),
+            (RecordKind::Task, task.task_id),
+            (RecordKind::Sprint, sprint.sprint_id),
+            (RecordKind::Project, project_id),
+        ];
+        for (kind, id) in records {
+            db.move_to_trash(kind, id).await.unwrap();
+            db.restore_from_trash(kind, id).await.unwrap();
+        }
+        // Other tests may be writing to the same database, so only the changes to these records count.
+        let changes = db.fetch_changes_since(latest).await.unwrap();
+        let ours = changes
+            .iter()
+            .filter(|(kind, id, _)| records.contains(&(*kind, *id)));
+        assert_eq!(ours.count(), 2 * records.len());
+
+        db.delete_project(project_id).await.unwrap();
+        assert!(project(db.fetch_projects().await.unwrap()).is_none());
+    }
+}
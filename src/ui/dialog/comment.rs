@@ -0,0 +1,271 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+use crate::{audit, storage::Storage, Comment, Member, Task};
+
+use super::{
+    confirm_delete::ConfirmDelete,
+    create::{CreateRecordDialog, CreateResults},
+    error::DisplayWindow,
+};
+
+/// Lists the comments on a task, oldest first, and lets the acting member post comments
+/// and edit or delete their own.
+pub struct CommentDialog {
+    cursor: usize,
+    comments: Vec<Comment>,
+    members: Vec<Member>,
+}
+
+impl CommentDialog {
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        task: &Task,
+    ) -> std::io::Result<()> {
+        let mut diag = CommentDialog {
+            cursor: 0,
+            comments: store.fetch_comments(task.task_id).await?,
+            members: store.fetch_members().await?,
+        };
+        // Start at the newest comment.
+        diag.cursor = diag.comments.len().saturating_sub(1);
+
+        loop {
+            diag.draw(terminal, task)?;
+
+            let len = diag.comments.len();
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down => diag.cursor = (diag.cursor + 1) % (len + 1),
+                        KeyCode::Up => {
+                            diag.cursor = if diag.cursor > 0 {
+                                diag.cursor - 1
+                            } else {
+                                len
+                            };
+                        }
+                        KeyCode::Char('c') => {
+                            diag.write_comment(terminal, store, task, None).await?
+                        }
+                        KeyCode::Enter if diag.cursor == len => {
+                            diag.write_comment(terminal, store, task, None).await?
+                        }
+                        KeyCode::Enter | KeyCode::Char('e') if diag.cursor < len => {
+                            let comment = diag.comments[diag.cursor].clone();
+                            diag.write_comment(terminal, store, task, Some(comment))
+                                .await?
+                        }
+                        KeyCode::Char('d') if diag.cursor < len => {
+                            diag.delete_comment(terminal, store, task).await?
+                        }
+                        KeyCode::Esc => return Ok(()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// The acting member, who comments are posted as. Tells the user to pick one if nobody is acting.
+    async fn author(terminal: &mut Terminal<impl Backend>) -> std::io::Result<Option<i32>> {
+        let author = audit::acting_member();
+        if author.is_none() {
+            DisplayWindow::run(
+                terminal,
+                "Comments are posted as the member you are acting as. Pick one under 'Acting as' in the main menu first.".into(),
+            )
+            .await?;
+        }
+        Ok(author)
+    }
+
+    /// Asks for the body of a new comment, or of `comment` if one is given.
+    async fn write_comment(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        task: &Task,
+        comment: Option<Comment>,
+    ) -> std::io::Result<()> {
+        let Some(author) = Self::author(terminal).await? else {
+            return Ok(());
+        };
+        if comment.as_ref().is_some_and(|c| c.member_id != author) {
+            return DisplayWindow::run(terminal, "You can only edit your own comments.".into())
+                .await;
+        }
+
+        let fields = vec!["Comment".into()];
+        let validator = |d: &CreateRecordDialog| !d.entries()[0].trim().is_empty();
+        let mut dialog = match &comment {
+            Some(comment) => {
+                CreateRecordDialog::new_edit(fields, vec![comment.body.clone()], validator)
+            }
+            None => CreateRecordDialog::new(fields, validator),
+        };
+        let CreateResults::Create(data) = dialog.run(terminal).await? else {
+            return Ok(());
+        };
+
+        let body = data[0].trim();
+        let result = match &comment {
+            Some(comment) => store.update_comment(comment.comment_id, author, body).await,
+            None => store
+                .add_comment(task.task_id, author, body)
+                .await
+                .map(|_| ()),
+        };
+        if let Err(e) = result {
+            DisplayWindow::run(terminal, format!("Failed to save comment: {}", e)).await?;
+        }
+
+        self.comments = store.fetch_comments(task.task_id).await?;
+        if comment.is_none() {
+            self.cursor = self.comments.len().saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    async fn delete_comment(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        task: &Task,
+    ) -> std::io::Result<()> {
+        let Some(author) = Self::author(terminal).await? else {
+            return Ok(());
+        };
+        let comment = &self.comments[self.cursor];
+        if comment.member_id != author {
+            return DisplayWindow::run(terminal, "You can only delete your own comments.".into())
+                .await;
+        }
+        if !ConfirmDelete::run(terminal).await {
+            return Ok(());
+        }
+
+        if let Err(e) = store.delete_comment(comment.comment_id, author).await {
+            DisplayWindow::run(terminal, format!("Failed to delete comment: {}", e)).await?;
+        }
+
+        self.comments = store.fetch_comments(task.task_id).await?;
+        self.cursor = self.cursor.min(self.comments.len());
+        Ok(())
+    }
+
+    /// The name of a comment's author. Authors are never missing, as deleting a member deletes their comments.
+    fn author_name(&self, comment: &Comment) -> String {
+        self.members
+            .iter()
+            .find(|m| m.member_id == comment.member_id)
+            .map(|m| format!("{} {}", m.first_name, m.last_name))
+            .unwrap_or_else(|| format!("Member #{}", comment.member_id))
+    }
+
+    /// Breaks `text` into lines of at most `width` characters, at spaces where possible.
+    fn wrap(text: &str, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            let mut word = word.to_string();
+            while word.chars().count() > width {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let rest = word.split_off(word.char_indices().nth(width).unwrap().0);
+                lines.push(std::mem::replace(&mut word, rest));
+            }
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>, task: &Task) -> std::io::Result<()> {
+        let author = audit::acting_member();
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Comment "),
+                Span::styled("<C> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Edit "),
+                Span::styled("<Enter> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Delete "),
+                Span::styled("<D> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Return "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            // Leave room for the borders and the highlight symbol.
+            let width = (frame.size().width as usize).saturating_sub(4).max(10);
+            let items: Vec<ListItem> = self
+                .comments
+                .iter()
+                .map(|comment| {
+                    let mut header = format!(
+                        "{} | {}",
+                        self.author_name(comment),
+                        comment.posted_at.format("%Y-%m-%d %H:%M")
+                    );
+                    if let Some(edited_at) = comment.edited_at {
+                        header
+                            .push_str(&format!(" (edited {})", edited_at.format("%Y-%m-%d %H:%M")));
+                    }
+                    if Some(comment.member_id) == author {
+                        header.push_str(" (you)");
+                    }
+                    let mut lines = vec![Line::styled(
+                        header,
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )];
+                    lines.extend(Self::wrap(&comment.body, width).into_iter().map(Line::raw));
+                    lines.push(Line::raw(""));
+                    ListItem::new(lines)
+                })
+                .chain(std::iter::once(
+                    ListItem::new("Add Comment +").style(Style::default().fg(Color::Green)),
+                ))
+                .collect();
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(format!(
+                            "Comments on '{}' ({})",
+                            task.title,
+                            self.comments.len()
+                        ))
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, frame.size(), &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
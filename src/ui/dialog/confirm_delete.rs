@@ -2,7 +2,7 @@ use crossterm::event::{read, Event, KeyCode, KeyEventKind};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Layout},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Terminal,
 };
 
@@ -12,6 +12,11 @@ pub struct ConfirmDelete {
 
 impl ConfirmDelete {
     pub async fn run(terminal: &mut Terminal<impl Backend>) -> bool {
+        ConfirmDelete::run_with_report(terminal, None).await
+    }
+
+    /// Like [`ConfirmDelete::run`], but tells the user what else is going to be removed along with the record.
+    pub async fn run_with_report(terminal: &mut Terminal<impl Backend>, report: Option<&str>) -> bool {
         let mut diag = ConfirmDelete { cursor: 0 };
 
         loop {
@@ -22,10 +27,20 @@ impl ConfirmDelete {
                         ListItem::new("Cancel"),
                     ]);
 
+                    let report_height = if report.is_some() { 3 } else { 0 };
                     let chunks = Layout::default()
-                        .constraints([Constraint::Percentage(100)].as_ref())
+                        .constraints([Constraint::Length(report_height), Constraint::Min(0)].as_ref())
                         .split(frame.size());
 
+                    if let Some(report) = report {
+                        frame.render_widget(
+                            Paragraph::new(report)
+                                .wrap(Wrap { trim: true })
+                                .block(Block::default().borders(Borders::ALL)),
+                            chunks[0],
+                        );
+                    }
+
                     let mut list_state = ListState::default();
 
                     let action_list = options
@@ -40,7 +55,7 @@ impl ConfirmDelete {
                             ratatui::style::Style::default().fg(ratatui::style::Color::Yellow),
                         );
                     list_state.select(Some(diag.cursor));
-                    frame.render_stateful_widget(action_list, chunks[0], &mut list_state);
+                    frame.render_stateful_widget(action_list, chunks[1], &mut list_state);
                 })
                 .expect("Failed to render");
 
@@ -54,7 +69,7 @@ impl ConfirmDelete {
                             diag.cursor = if diag.cursor > 0 { diag.cursor - 1 } else { 1 };
                         }
                         KeyCode::Enter => {
-                            let _ = match diag.cursor {
+                            match diag.cursor {
                                 0 => {
                                     return true;
                                 }
@@ -0,0 +1,144 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+const ACTIONS: [&str; 3] = ["Overwrite with yours", "Reload theirs", "Save merge"];
+
+/// Shown when someone else changed a record while it was being edited. Lists the fields on which
+/// both versions differ, so the user can overwrite the other version, reload it or merge the two.
+pub struct ConflictDialog<'a> {
+    cursor: usize,
+    field_names: &'a [&'a str],
+    mine: &'a [String],
+    theirs: &'a [String],
+    /// Indices of the fields that differ, each with whether the merge keeps the user's value.
+    fields: Vec<(usize, bool)>,
+}
+
+impl<'a> ConflictDialog<'a> {
+    /// `mine` and `theirs` hold the values of the fields named in `field_names`, in that order.
+    /// Returns the values to save, or `None` if the user chose to reload the record instead.
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        title: &str,
+        field_names: &'a [&'a str],
+        mine: &'a [String],
+        theirs: &'a [String],
+    ) -> std::io::Result<Option<Vec<String>>> {
+        let mut diag = ConflictDialog {
+            cursor: 0,
+            field_names,
+            mine,
+            theirs,
+            fields: (0..field_names.len())
+                .filter(|&i| mine[i] != theirs[i])
+                .map(|i| (i, true))
+                .collect(),
+        };
+
+        loop {
+            diag.draw(terminal, title)?;
+
+            let rows = diag.fields.len() + ACTIONS.len();
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down => diag.cursor = (diag.cursor + 1) % rows,
+                        KeyCode::Up => {
+                            diag.cursor = if diag.cursor > 0 {
+                                diag.cursor - 1
+                            } else {
+                                rows - 1
+                            };
+                        }
+                        KeyCode::Char(' ') | KeyCode::Enter if diag.cursor < diag.fields.len() => {
+                            diag.fields[diag.cursor].1 = !diag.fields[diag.cursor].1;
+                        }
+                        KeyCode::Enter => match diag.cursor - diag.fields.len() {
+                            0 => return Ok(Some(diag.mine.to_vec())),
+                            1 => return Ok(None),
+                            _ => return Ok(Some(diag.merged())),
+                        },
+                        KeyCode::Esc => return Ok(None),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Their values, with the user's on the fields picked to keep them.
+    fn merged(&self) -> Vec<String> {
+        let mut merged = self.theirs.to_vec();
+        for &(i, keep_mine) in &self.fields {
+            if keep_mine {
+                merged[i] = self.mine[i].clone();
+            }
+        }
+        merged
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>, title: &str) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Keep yours or theirs "),
+                Span::styled("<Space> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Pick "),
+                Span::styled("<Enter> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Reload "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> =
+                self.fields
+                    .iter()
+                    .map(|&(i, keep_mine)| {
+                        let (yours, theirs) = if keep_mine {
+                            ("[x]", "[ ]")
+                        } else {
+                            ("[ ]", "[x]")
+                        };
+                        ListItem::new(format!(
+                            "{}: {} yours '{}' {} theirs '{}'",
+                            self.field_names[i], yours, self.mine[i], theirs, self.theirs[i]
+                        ))
+                    })
+                    .chain(ACTIONS.iter().map(|action| {
+                        ListItem::new(*action).style(Style::default().fg(Color::Green))
+                    }))
+                    .collect();
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(format!("{} was changed by someone else", title))
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100)])
+                .split(frame.size());
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
@@ -11,8 +11,9 @@ use ratatui::{
 pub struct CreateRecordDialog {
     entries: Vec<String>,
     labels: Vec<String>,
-    validate: fn(&CreateRecordDialog) -> bool,
+    validate: Box<dyn Fn(&CreateRecordDialog) -> bool>,
     cursor: usize,
+    invalid: bool,
 }
 
 pub enum CreateResults {
@@ -21,29 +22,39 @@ pub enum CreateResults {
 }
 
 impl CreateRecordDialog {
-    pub fn new(labels: Vec<String>, validator: fn(&CreateRecordDialog) -> bool) -> Self {
+    pub fn new(
+        labels: Vec<String>,
+        validator: impl Fn(&CreateRecordDialog) -> bool + 'static,
+    ) -> Self {
         let entries = vec![String::new(); labels.len()];
         CreateRecordDialog {
             labels,
             entries,
-            validate: validator,
+            validate: Box::new(validator),
             cursor: 0,
+            invalid: false,
         }
     }
 
     pub fn new_edit(
         labels: Vec<String>,
         current_data: Vec<String>,
-        validator: fn(&CreateRecordDialog) -> bool,
+        validator: impl Fn(&CreateRecordDialog) -> bool + 'static,
     ) -> Self {
         CreateRecordDialog {
             labels,
             entries: current_data,
-            validate: validator,
+            validate: Box::new(validator),
             cursor: 0,
+            invalid: false,
         }
     }
 
+    /// The values currently typed into the fields, in the order of their labels.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
     pub async fn run<B: Backend>(
         &mut self,
         terminal: &mut Terminal<B>,
@@ -51,27 +62,25 @@ impl CreateRecordDialog {
         loop {
             self.draw(terminal)?;
 
-            match event::read()? {
-                Event::Key(KeyEvent { code, .. }) => match code {
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                match code {
                     KeyCode::Char(c) => {
                         self.entries[self.cursor].push(c);
                     }
                     KeyCode::Enter => {
                         if self.cursor == self.entries.len() {
-                            if (self.validate)(&self) {
+                            if (self.validate)(self) {
                                 return Ok(CreateResults::Create(self.entries.clone()));
                             } else {
-                                // Do nothing until we have some way to visualize an error.
+                                self.invalid = true;
                             }
                         } else {
                             // Move cursor to submit button or wrap around
                             self.cursor = (self.cursor + 1) % (self.entries.len() + 1);
                         }
                     }
-                    KeyCode::Backspace => {
-                        if !self.entries[self.cursor].is_empty() {
-                            self.entries[self.cursor].pop();
-                        }
+                    KeyCode::Backspace if !self.entries[self.cursor].is_empty() => {
+                        self.entries[self.cursor].pop();
                     }
                     KeyCode::Down => {
                         self.cursor = (self.cursor + 1) % (self.entries.len() + 1);
@@ -87,8 +96,7 @@ impl CreateRecordDialog {
                         return Ok(CreateResults::Quit);
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
         }
     }
@@ -102,9 +110,15 @@ impl CreateRecordDialog {
                 .map(|(label, entry)| ListItem::new(format!("{}: {}", label, entry)))
                 .collect();
 
+            let submit = if self.invalid {
+                ListItem::new("Submit (some fields are invalid, please check them)")
+                    .style(Style::default().fg(Color::Red))
+            } else {
+                ListItem::new("Submit")
+            };
             let items = items
                 .into_iter()
-                .chain(std::iter::once(ListItem::new("Submit")))
+                .chain(std::iter::once(submit))
                 .collect::<Vec<_>>();
 
             let list = List::new(items)
@@ -0,0 +1,297 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+use crate::{
+    storage::Storage,
+    undo::{Edit, Operation, UndoStack},
+    Project, Task,
+};
+
+use super::{error::DisplayWindow, picker::PickerDialog};
+
+const ACTIONS: [&str; 2] = ["Add Blocker +", "Block Another Task +"];
+
+/// Lists the tasks the selected task waits for (upstream) and the tasks waiting for it (downstream),
+/// and lets the user link and unlink tasks of the same project.
+pub struct DependencyDialog {
+    cursor: usize,
+    project: Project,
+    task_id: i32,
+    /// (blocker, blocked) links shown in the list, upstream ones first.
+    links: Vec<(i32, i32)>,
+}
+
+impl DependencyDialog {
+    /// Every dependency change is pushed onto `undo`.
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        project_id: i32,
+        task_id: i32,
+        undo: &mut UndoStack,
+    ) -> std::io::Result<()> {
+        let Some(project) = Self::fetch_project(store, project_id).await? else {
+            return Ok(());
+        };
+        let mut diag = DependencyDialog {
+            cursor: 0,
+            project,
+            task_id,
+            links: Vec::new(),
+        };
+        diag.collect_links();
+
+        loop {
+            let Some(task) = diag.project.task(diag.task_id).cloned() else {
+                return Ok(());
+            };
+            diag.draw(terminal, &task)?;
+
+            let rows = diag.links.len() + ACTIONS.len();
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down => diag.cursor = (diag.cursor + 1) % rows,
+                        KeyCode::Up => {
+                            diag.cursor = if diag.cursor > 0 {
+                                diag.cursor - 1
+                            } else {
+                                rows - 1
+                            };
+                        }
+                        KeyCode::Enter if diag.cursor == diag.links.len() => {
+                            diag.add_link(terminal, store, undo, &task, true).await?
+                        }
+                        KeyCode::Enter if diag.cursor > diag.links.len() => {
+                            diag.add_link(terminal, store, undo, &task, false).await?
+                        }
+                        KeyCode::Char('d') if diag.cursor < diag.links.len() => {
+                            diag.remove_link(terminal, store, undo).await?
+                        }
+                        KeyCode::Esc => return Ok(()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    async fn fetch_project(
+        store: &impl Storage,
+        project_id: i32,
+    ) -> std::io::Result<Option<Project>> {
+        Ok(store
+            .fetch_projects()
+            .await?
+            .into_iter()
+            .find(|p| p.proj_id == project_id))
+    }
+
+    async fn reload(&mut self, store: &impl Storage) -> std::io::Result<()> {
+        if let Some(project) = Self::fetch_project(store, self.project.proj_id).await? {
+            self.project = project;
+        }
+        self.collect_links();
+        self.cursor = self.cursor.min(self.links.len());
+        Ok(())
+    }
+
+    fn collect_links(&mut self) {
+        let upstream = self
+            .project
+            .task(self.task_id)
+            .map(|task| self.project.blockers(task))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|blocker| (blocker.task_id, self.task_id));
+        let downstream = self
+            .project
+            .blocked_tasks(self.task_id)
+            .into_iter()
+            .map(|blocked| (self.task_id, blocked.task_id));
+        self.links = upstream.chain(downstream).collect();
+    }
+
+    fn title_of(&self, task_id: i32) -> String {
+        self.project
+            .task(task_id)
+            .map(|t| t.title.clone())
+            .unwrap_or_else(|| format!("#{}", task_id))
+    }
+
+    /// Lets the user pick another task of the project to block `task` (if `upstream`) or to be blocked by it.
+    async fn add_link(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        undo: &mut UndoStack,
+        task: &Task,
+        upstream: bool,
+    ) -> std::io::Result<()> {
+        let options: Vec<(i32, String)> = self
+            .project
+            .sprints
+            .iter()
+            .flat_map(|s| s.tasks.iter().map(move |t| (s, t)))
+            .filter(|(_, t)| t.task_id != task.task_id)
+            .filter(|(_, t)| {
+                let link = if upstream {
+                    (t.task_id, task.task_id)
+                } else {
+                    (task.task_id, t.task_id)
+                };
+                !self.links.contains(&link)
+            })
+            .map(|(s, t)| (t.task_id, format!("Task #{}: {} ({})", t.task_id, t.title, s.title)))
+            .collect();
+        if options.is_empty() {
+            return DisplayWindow::run(terminal, "There are no other tasks to link.".into()).await;
+        }
+
+        let prompt = if upstream {
+            format!("Which task does '{}' wait for?", task.title)
+        } else {
+            format!("Which task waits for '{}'?", task.title)
+        };
+        let Some(other_id) = PickerDialog::run(terminal, &prompt, options).await? else {
+            return Ok(());
+        };
+
+        let (blocker_id, blocked_id) = if upstream {
+            (other_id, task.task_id)
+        } else {
+            (task.task_id, other_id)
+        };
+        match store.add_task_dependency(blocker_id, blocked_id).await {
+            Ok(()) => undo.push(Edit {
+                summary: format!(
+                    "make task '{}' block task '{}'",
+                    self.title_of(blocker_id),
+                    self.title_of(blocked_id)
+                ),
+                undo: vec![Operation::RemoveTaskDependency {
+                    blocker_id,
+                    blocked_id,
+                }],
+                redo: vec![Operation::AddTaskDependency {
+                    blocker_id,
+                    blocked_id,
+                }],
+            }),
+            Err(e) => {
+                DisplayWindow::run(terminal, format!("Failed to link tasks: {}", e)).await?;
+            }
+        }
+
+        self.reload(store).await
+    }
+
+    async fn remove_link(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        undo: &mut UndoStack,
+    ) -> std::io::Result<()> {
+        let (blocker_id, blocked_id) = self.links[self.cursor];
+        match store.remove_task_dependency(blocker_id, blocked_id).await {
+            Ok(()) => undo.push(Edit {
+                summary: format!(
+                    "stop task '{}' blocking task '{}'",
+                    self.title_of(blocker_id),
+                    self.title_of(blocked_id)
+                ),
+                undo: vec![Operation::AddTaskDependency {
+                    blocker_id,
+                    blocked_id,
+                }],
+                redo: vec![Operation::RemoveTaskDependency {
+                    blocker_id,
+                    blocked_id,
+                }],
+            }),
+            Err(e) => {
+                DisplayWindow::run(terminal, format!("Failed to unlink tasks: {}", e)).await?;
+            }
+        }
+
+        self.reload(store).await
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>, task: &Task) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Add "),
+                Span::styled("<Enter> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Remove Link "),
+                Span::styled("<D> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Return "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = self
+                .links
+                .iter()
+                .map(|(blocker_id, blocked_id)| {
+                    let (direction, other_id) = if *blocked_id == self.task_id {
+                        ("Blocked by", *blocker_id)
+                    } else {
+                        ("Blocks", *blocked_id)
+                    };
+                    let text = match self.project.task(other_id) {
+                        Some(other) => format!(
+                            "{} Task #{}: {} - {} {}",
+                            direction,
+                            other.task_id,
+                            other.title,
+                            other.status,
+                            self.project.workflow.emoji(&other.status)
+                        ),
+                        None => format!("{} Task #{}", direction, other_id),
+                    };
+                    ListItem::new(text)
+                })
+                .chain(ACTIONS.iter().map(|action| {
+                    ListItem::new(*action).style(Style::default().fg(Color::Green))
+                }))
+                .collect();
+
+            let title = if self.project.is_blocked(task) {
+                format!("Dependencies of '{}' (blocked)", task.title)
+            } else {
+                format!("Dependencies of '{}'", task.title)
+            };
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(title)
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100)])
+                .split(frame.size());
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+use crate::{storage::Storage, AuditEntry, Member, RecordKind};
+
+/// Lists the recorded changes to a project, sprint or task, newest first.
+pub struct HistoryDialog {
+    cursor: usize,
+    entries: Vec<AuditEntry>,
+    members: Vec<Member>,
+}
+
+impl HistoryDialog {
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        kind: RecordKind,
+        id: i32,
+        title: &str,
+    ) -> std::io::Result<()> {
+        let mut diag = HistoryDialog {
+            cursor: 0,
+            entries: store.fetch_history(kind, id).await?,
+            members: store.fetch_members().await?,
+        };
+
+        loop {
+            diag.draw(terminal, kind, title)?;
+
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down if !diag.entries.is_empty() => {
+                            diag.cursor = (diag.cursor + 1) % diag.entries.len();
+                        }
+                        KeyCode::Up if !diag.entries.is_empty() => {
+                            diag.cursor = if diag.cursor > 0 {
+                                diag.cursor - 1
+                            } else {
+                                diag.entries.len() - 1
+                            };
+                        }
+                        KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Who made a change. Members deleted since keep their entries, but are no longer in `members`.
+    fn actor(&self, entry: &AuditEntry) -> String {
+        match entry.member_id {
+            Some(member_id) => self
+                .members
+                .iter()
+                .find(|m| m.member_id == member_id)
+                .map(|m| format!("{} {}", m.first_name, m.last_name))
+                .unwrap_or_else(|| format!("Member #{}", member_id)),
+            None => "Nobody".into(),
+        }
+    }
+
+    fn describe(entry: &AuditEntry) -> String {
+        let Some(field) = &entry.field else {
+            return entry.action.clone();
+        };
+        let value = |value: &Option<String>| match value.as_deref() {
+            Some("") => "(empty)".to_string(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+        match (&entry.old_value, &entry.new_value) {
+            (Some(_), Some(_)) => format!(
+                "{} {}: {} → {}",
+                entry.action,
+                field,
+                value(&entry.old_value),
+                value(&entry.new_value)
+            ),
+            (None, new_value) => format!("{} {}: {}", entry.action, field, value(new_value)),
+            (old_value, None) => format!("{} {}: {}", entry.action, field, value(old_value)),
+        }
+    }
+
+    fn draw(
+        &self,
+        terminal: &mut Terminal<impl Backend>,
+        kind: RecordKind,
+        title: &str,
+    ) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Return "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = if self.entries.is_empty() {
+                vec![ListItem::new("No changes have been recorded yet.")]
+            } else {
+                self.entries
+                    .iter()
+                    .map(|entry| {
+                        ListItem::new(format!(
+                            "{} | {} | {}",
+                            entry.changed_at.format("%Y-%m-%d %H:%M"),
+                            self.actor(entry),
+                            Self::describe(entry)
+                        ))
+                    })
+                    .collect()
+            };
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(format!("History of {} '{}'", kind.label(), title))
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let mut list_state = ListState::default();
+            if !self.entries.is_empty() {
+                list_state.select(Some(self.cursor));
+            }
+            frame.render_stateful_widget(list, frame.size(), &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use std::str::FromStr;
+
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+use crate::{storage::Storage, Label, Project};
+
+use super::{
+    create::{CreateRecordDialog, CreateResults},
+    error::DisplayWindow,
+};
+
+/// The color a label is drawn in. Colors that cannot be parsed, written by an older client
+/// for example, fall back to the terminal's default.
+fn label_color(label: &Label) -> Color {
+    Color::from_str(&label.color).unwrap_or(Color::Reset)
+}
+
+/// A label as shown next to task titles, like "[bug]" in the label's color.
+pub fn label_span(label: &Label) -> Span<'static> {
+    Span::styled(
+        format!("[{}]", label.name),
+        Style::default().fg(label_color(label)),
+    )
+}
+
+/// Lists the labels of a project and lets the user add, edit and remove them.
+/// Every change is written right away.
+pub struct LabelDialog {
+    cursor: usize,
+    project_id: i32,
+    project_title: String,
+    labels: Vec<Label>,
+}
+
+impl LabelDialog {
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        project: &Project,
+    ) -> std::io::Result<()> {
+        let mut diag = LabelDialog {
+            cursor: 0,
+            project_id: project.proj_id,
+            project_title: project.title.clone(),
+            labels: project.labels.clone(),
+        };
+
+        loop {
+            diag.draw(terminal)?;
+
+            let len = diag.labels.len();
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down => diag.cursor = (diag.cursor + 1) % (len + 1),
+                        KeyCode::Up => {
+                            diag.cursor = if diag.cursor > 0 { diag.cursor - 1 } else { len };
+                        }
+                        KeyCode::Char('c') => diag.edit_label(terminal, store, None).await?,
+                        KeyCode::Enter if diag.cursor == len => {
+                            diag.edit_label(terminal, store, None).await?
+                        }
+                        KeyCode::Enter | KeyCode::Char('e') if diag.cursor < len => {
+                            let label = diag.labels[diag.cursor].clone();
+                            diag.edit_label(terminal, store, Some(label)).await?
+                        }
+                        KeyCode::Char('d') if diag.cursor < len => {
+                            let label_id = diag.labels[diag.cursor].label_id;
+                            if let Err(e) = store.delete_label(label_id).await {
+                                DisplayWindow::run(terminal, format!("Failed to remove label: {}", e))
+                                    .await?;
+                            }
+                            diag.reload(store).await?;
+                        }
+                        KeyCode::Esc => return Ok(()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    async fn reload(&mut self, store: &impl Storage) -> std::io::Result<()> {
+        if let Some(project) = store
+            .fetch_projects()
+            .await?
+            .into_iter()
+            .find(|p| p.proj_id == self.project_id)
+        {
+            self.labels = project.labels;
+        }
+        self.cursor = self.cursor.min(self.labels.len());
+        Ok(())
+    }
+
+    /// Asks for the name and color of a new label, or of `label` if one is given.
+    async fn edit_label(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        label: Option<Label>,
+    ) -> std::io::Result<()> {
+        let fields = vec!["Name".into(), "Color (red, light blue, #ff8800, ...)".into()];
+        let validator = |d: &CreateRecordDialog| {
+            !d.entries()[0].trim().is_empty() && Color::from_str(d.entries()[1].trim()).is_ok()
+        };
+        let mut dialog = match &label {
+            Some(label) => CreateRecordDialog::new_edit(
+                fields,
+                vec![label.name.clone(), label.color.clone()],
+                validator,
+            ),
+            None => CreateRecordDialog::new(fields, validator),
+        };
+        let CreateResults::Create(data) = dialog.run(terminal).await? else {
+            return Ok(());
+        };
+
+        let (name, color) = (data[0].trim(), data[1].trim());
+        let result = match &label {
+            Some(label) => store.update_label(label.label_id, name, color).await,
+            None => store
+                .create_label(self.project_id, name, color)
+                .await
+                .map(|_| ()),
+        };
+        if let Err(e) = result {
+            DisplayWindow::run(terminal, format!("Failed to save label: {}", e)).await?;
+        }
+
+        self.reload(store).await
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Add Label "),
+                Span::styled("<C> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Edit "),
+                Span::styled("<Enter> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Remove "),
+                Span::styled("<D> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Return "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = self
+                .labels
+                .iter()
+                .map(|label| {
+                    ListItem::new(Line::from(vec![
+                        label_span(label),
+                        Span::raw(format!(" {}", label.color)),
+                    ]))
+                })
+                .chain(std::iter::once(
+                    ListItem::new("Add Label +").style(Style::default().fg(Color::Green)),
+                ))
+                .collect();
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(format!("Labels of '{}'", self.project_title))
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100)])
+                .split(frame.size());
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
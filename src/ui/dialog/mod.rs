@@ -1,13 +1,29 @@
+mod comment;
 mod confirm_delete;
+mod conflict;
 mod create;
+mod dependency;
 mod error;
+mod history;
+mod label;
+mod picker;
 mod project;
 mod sprint;
+mod work_log;
+mod workflow;
 
 pub mod prelude {
+    pub use super::comment::CommentDialog;
     pub use super::confirm_delete::ConfirmDelete;
+    pub use super::conflict::ConflictDialog;
     pub use super::create::*;
+    pub use super::dependency::DependencyDialog;
     pub use super::error::DisplayWindow;
+    pub use super::history::HistoryDialog;
+    pub use super::label::{label_span, LabelDialog};
+    pub use super::picker::{MultiPickerDialog, PickerDialog};
     pub use super::project::*;
     pub use super::sprint::*;
+    pub use super::work_log::WorkLogDialog;
+    pub use super::workflow::WorkflowDialog;
 }
@@ -0,0 +1,206 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+/// A dialog to pick any number of options out of a list, each identified by its record ID.
+pub struct MultiPickerDialog {
+    cursor: usize,
+    options: Vec<(i32, String)>,
+    picked: Vec<i32>,
+}
+
+impl MultiPickerDialog {
+    /// Runs the picker with the IDs in `picked` already selected.
+    /// Returns the picked IDs, or `None` if the user backed out with Esc.
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        title: &str,
+        options: Vec<(i32, String)>,
+        picked: Vec<i32>,
+    ) -> std::io::Result<Option<Vec<i32>>> {
+        let mut diag = MultiPickerDialog {
+            cursor: 0,
+            options,
+            picked,
+        };
+
+        loop {
+            diag.draw(terminal, title)?;
+
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down => {
+                            diag.cursor = (diag.cursor + 1) % (diag.options.len() + 1);
+                        }
+                        KeyCode::Up => {
+                            diag.cursor = if diag.cursor > 0 {
+                                diag.cursor - 1
+                            } else {
+                                diag.options.len()
+                            };
+                        }
+                        KeyCode::Char(' ') if diag.cursor < diag.options.len() => {
+                            diag.toggle(diag.options[diag.cursor].0);
+                        }
+                        KeyCode::Enter if diag.cursor < diag.options.len() => {
+                            diag.toggle(diag.options[diag.cursor].0);
+                        }
+                        KeyCode::Enter => return Ok(Some(diag.picked)),
+                        KeyCode::Esc => return Ok(None),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn toggle(&mut self, id: i32) {
+        if let Some(idx) = self.picked.iter().position(|picked| *picked == id) {
+            self.picked.remove(idx);
+        } else {
+            self.picked.push(id);
+        }
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>, title: &str) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Toggle "),
+                Span::styled("<Space> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Cancel "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = self
+                .options
+                .iter()
+                .map(|(id, text)| {
+                    let mark = if self.picked.contains(id) { "[x]" } else { "[ ]" };
+                    ListItem::new(format!("{} {}", mark, text))
+                })
+                .chain(std::iter::once(
+                    ListItem::new("Done").style(Style::default().fg(Color::Green)),
+                ))
+                .collect();
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(title)
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100)])
+                .split(frame.size());
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
+
+/// A dialog to pick a single option out of a list, each identified by its record ID.
+pub struct PickerDialog {
+    cursor: usize,
+    options: Vec<(i32, String)>,
+}
+
+impl PickerDialog {
+    /// Returns the ID of the picked option, or `None` if the user backed out with Esc.
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        title: &str,
+        options: Vec<(i32, String)>,
+    ) -> std::io::Result<Option<i32>> {
+        let mut diag = PickerDialog { cursor: 0, options };
+
+        loop {
+            diag.draw(terminal, title)?;
+
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down if !diag.options.is_empty() => {
+                            diag.cursor = (diag.cursor + 1) % diag.options.len();
+                        }
+                        KeyCode::Up if !diag.options.is_empty() => {
+                            diag.cursor = if diag.cursor > 0 {
+                                diag.cursor - 1
+                            } else {
+                                diag.options.len() - 1
+                            };
+                        }
+                        KeyCode::Enter if !diag.options.is_empty() => {
+                            return Ok(Some(diag.options[diag.cursor].0));
+                        }
+                        KeyCode::Esc => return Ok(None),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>, title: &str) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Pick "),
+                Span::styled("<Enter> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Cancel "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = self
+                .options
+                .iter()
+                .map(|(_, text)| ListItem::new(text.clone()))
+                .collect();
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(title)
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100)])
+                .split(frame.size());
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
@@ -3,9 +3,11 @@ use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
     backend::Backend, layout::{Constraint, Direction, Layout}, style::{Color, Style}, text::{Line, Span}, widgets::{block::Title, Block, Borders, List, ListItem, ListState}, Terminal
 };
-use sqlx::{MySqlPool};
-
-use crate::{crud::fetch_members_by_project_id, Member};
+use crate::{
+    storage::Storage,
+    undo::{Edit, Operation, UndoStack},
+    Member,
+};
 
 use super::error::{DisplayWindow};
 
@@ -26,8 +28,9 @@ impl ProjectMembersDialog {
     async fn handle_key_event(
         &mut self,
         key_event: KeyEvent,
-        pool: &MySqlPool,
+        store: &impl Storage,
         project_id: i32,
+        undo: &mut UndoStack,
     ) -> std::io::Result<bool> {
         let refresh_needed = match key_event.code {
             KeyCode::Down => {
@@ -51,26 +54,58 @@ impl ProjectMembersDialog {
                 false
             }
             KeyCode::Enter if self.cursor == self.members.len() => {
-                let new_member = self.new_id.clone();
-                let _ = sqlx::query!(
-                    "INSERT INTO ContributesTo (MemberID, ProjectID) VALUES (?, ?)",
-                    new_member,
-                    project_id
-                )
-                .execute(pool)
-                .await;
+                if let Ok(new_member) = self.new_id.parse::<i32>() {
+                    if store.add_project_member(new_member, project_id).await.is_ok() {
+                        undo.push(Edit {
+                            summary: format!("add member #{} to the project", new_member),
+                            undo: vec![Operation::RemoveProjectMember {
+                                member_id: new_member,
+                                project_id,
+                            }],
+                            redo: vec![Operation::AddProjectMember {
+                                member_id: new_member,
+                                project_id,
+                            }],
+                        });
+                    }
+                }
                 self.new_id.clear();
                 true // Indicates the need for refresh
             }
             KeyCode::Char('d') if self.cursor < self.members.len() => {
-                let member_id = self.members[self.cursor].member_id;
-                let _ = sqlx::query!(
-                    "DELETE FROM ContributesTo WHERE MemberID = ? AND ProjectID = ?",
-                    member_id,
-                    project_id
-                )
-                .execute(pool)
-                .await;
+                let member = &self.members[self.cursor];
+                let member_id = member.member_id;
+                // Removing a member unassigns them from the project's tasks, so undoing it assigns them again.
+                let reassign: Vec<Operation> = store
+                    .fetch_projects()
+                    .await?
+                    .iter()
+                    .filter(|p| p.proj_id == project_id)
+                    .flat_map(|p| p.sprints.iter().flat_map(|s| s.tasks.iter()))
+                    .filter(|t| t.assignees.iter().any(|a| a.member_id == member_id))
+                    .map(|t| Operation::SetTaskAssignees {
+                        task_id: t.task_id,
+                        member_ids: t.assignees.iter().map(|a| a.member_id).collect(),
+                    })
+                    .collect();
+                if store.remove_project_member(member_id, project_id).await.is_ok() {
+                    undo.push(Edit {
+                        summary: format!(
+                            "remove {} {} from the project",
+                            member.first_name, member.last_name
+                        ),
+                        undo: std::iter::once(Operation::AddProjectMember {
+                            member_id,
+                            project_id,
+                        })
+                        .chain(reassign)
+                        .collect(),
+                        redo: vec![Operation::RemoveProjectMember {
+                            member_id,
+                            project_id,
+                        }],
+                    });
+                }
                 true // Indicates the need for refresh
             }
             KeyCode::Esc => return Ok(false),
@@ -78,7 +113,7 @@ impl ProjectMembersDialog {
         };
 
         if refresh_needed {
-            self.members = fetch_members_by_project_id(pool, project_id)
+            self.members = store.fetch_project_members(project_id)
                 .await
                 .expect("Failed to update members after change was applied...");
             self.cursor = 0;
@@ -87,24 +122,28 @@ impl ProjectMembersDialog {
         Ok(true)
     }
 
+    /// Every membership change is pushed onto `undo`.
     pub async fn run(
-        mut terminal: &mut Terminal<impl Backend>,
-        pool: &MySqlPool,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
         project_id: i32,
+        undo: &mut UndoStack,
     ) -> std::io::Result<()> {
         let mut diag = ProjectMembersDialog::new();
-        diag.members = fetch_members_by_project_id(pool, project_id)
+        diag.members = store.fetch_project_members(project_id)
             .await
             .expect("Failed to fetch members!");
 
         loop {
-            diag.draw(&mut terminal)?;
+            diag.draw(terminal)?;
 
             if let Event::Key(key_event) = read()? {
-                if key_event.kind == KeyEventKind::Press {
-                    if !diag.handle_key_event(key_event, pool, project_id).await? {
-                        return Ok(());
-                    }
+                if key_event.kind == KeyEventKind::Press
+                    && !diag
+                        .handle_key_event(key_event, store, project_id, undo)
+                        .await?
+                {
+                    return Ok(());
                 }
             }
         }
@@ -181,14 +220,15 @@ impl CreateProjectDialog {
         }
     }
 
+    /// Returns the ID of the new project, or `None` if none was created.
     pub async fn run(
-        mut terminal: &mut Terminal<impl Backend>,
-        pool: &MySqlPool,
-    ) -> std::io::Result<()> {
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+    ) -> std::io::Result<Option<i32>> {
         let mut diag = CreateProjectDialog::new();
 
         loop {
-            diag.draw(&mut terminal)?;
+            diag.draw(terminal)?;
 
             if let Event::Key(key_event) = read()? {
                 if key_event.kind == KeyEventKind::Press {
@@ -198,29 +238,19 @@ impl CreateProjectDialog {
                             1 => diag.desc.push(c),
                             _ => {}
                         },
-                        KeyCode::Enter => {
-                            if diag.cursor == 2 {
-                                //Actually create the project and return.
-                                let query =
-                                    "INSERT INTO Project (Title, Description) VALUES (?, ?)";
-
-                                match sqlx::query(query)
-                                    .bind(&diag.name)
-                                    .bind(&diag.desc)
-                                    .execute(pool)
-                                    .await
-                                {
-                                    Ok(_) => {
-                                        return Ok(());
-                                    }
-                                    Err(e) => {
-                                        DisplayWindow::run(
-                                            terminal,
-                                            format!("Failed to create project: {}", e),
-                                        )
-                                        .await?;
-                                        return Ok(());
-                                    }
+                        KeyCode::Enter if diag.cursor == 2 => {
+                            //Actually create the project and return.
+                            match store.create_project(&diag.name, &diag.desc).await {
+                                Ok(project_id) => {
+                                    return Ok(Some(project_id));
+                                }
+                                Err(e) => {
+                                    DisplayWindow::run(
+                                        terminal,
+                                        format!("Failed to create project: {}", e),
+                                    )
+                                    .await?;
+                                    return Ok(None);
                                 }
                             }
                         }
@@ -239,7 +269,7 @@ impl CreateProjectDialog {
                         KeyCode::Up => {
                             diag.cursor = if diag.cursor > 0 { diag.cursor - 1 } else { 3 };
                         }
-                        KeyCode::Esc => return Ok(()),
+                        KeyCode::Esc => return Ok(None),
                         _ => {}
                     }
                 }
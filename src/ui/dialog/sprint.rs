@@ -5,7 +5,7 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState},
     Terminal,
 };
-use sqlx::MySqlPool;
+use crate::{storage::Storage, Sprint};
 
 use super::error::DisplayWindow;
 
@@ -26,15 +26,16 @@ impl CreateSprintDialog {
         }
     }
 
+    /// Returns the new sprint, or `None` if none was created.
     pub async fn run(
-        mut terminal: &mut Terminal<impl Backend>,
-        pool: &MySqlPool,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
         id: i32,
-    ) -> std::io::Result<()> {
+    ) -> std::io::Result<Option<Sprint>> {
         let mut diag = CreateSprintDialog::new();
 
         loop {
-            diag.draw(&mut terminal)?;
+            diag.draw(terminal)?;
 
             if let Event::Key(key_event) = read()? {
                 if key_event.kind == KeyEventKind::Press {
@@ -45,39 +46,38 @@ impl CreateSprintDialog {
                             2 => diag.end_date.push(c),
                             _ => {}
                         },
-                        KeyCode::Enter => {
-                            if diag.cursor == 3 {
-                                // CREATE SPRINT
-                                // Insert the sprint into the SPRINT table
-                                let sprint_query = "INSERT INTO Sprint (Title, startDate, endDate) VALUES (?, ?, ?)";
-                                let sprint_result = sqlx::query(sprint_query)
-                                    .bind(&diag.title)
-                                    .bind(&diag.start_date)
-                                    .bind(&diag.end_date)
-                                    .execute(pool)
-                                    .await;
+                        KeyCode::Enter if diag.cursor == 3 => {
+                            let start_date = chrono::NaiveDate::parse_from_str(
+                                &diag.start_date,
+                                "%Y-%m-%d",
+                            );
+                            let end_date =
+                                chrono::NaiveDate::parse_from_str(&diag.end_date, "%Y-%m-%d");
 
-                                let last_sprint_id = match sprint_result {
-                                    Ok(result) => result.last_insert_id(),
-                                    Err(e) => {
-                                        DisplayWindow::run(
-                                            terminal,
-                                            format!("Failed to create sprint: {}", e),
-                                        )
-                                        .await?;
-                                        return Ok(()); // Or return an error indicating that the insertion failed
-                                    }
-                                };
+                            let (Ok(start_date), Ok(end_date)) = (start_date, end_date) else {
+                                DisplayWindow::run(
+                                    terminal,
+                                    "Invalid date format. Dates must be YYYY-MM-DD.".into(),
+                                )
+                                .await?;
+                                continue;
+                            };
 
-                                let project_sprint_query =
-                                    "INSERT INTO ProjectSprint (ProjectID, SprintID) VALUES (?, ?)";
-                                sqlx::query(project_sprint_query)
-                                        .bind(id)
-                                        .bind(last_sprint_id)
-                                        .execute(pool)
-                                        .await.expect("Failed to associated sprint with project! Sprint is now orphaned...");
-
-                                return Ok(());
+                            // CREATE SPRINT
+                            // Insert the sprint and link it to the project.
+                            match store
+                                .create_sprint(id, &diag.title, start_date, end_date)
+                                .await
+                            {
+                                Ok(sprint) => return Ok(Some(sprint)),
+                                Err(e) => {
+                                    DisplayWindow::run(
+                                        terminal,
+                                        format!("Failed to create sprint: {}", e),
+                                    )
+                                    .await?;
+                                    return Ok(None);
+                                }
                             }
                         }
                         KeyCode::Backspace => match diag.cursor {
@@ -98,7 +98,7 @@ impl CreateSprintDialog {
                         KeyCode::Up => {
                             diag.cursor = if diag.cursor > 0 { diag.cursor - 1 } else { 3 };
                         }
-                        KeyCode::Esc => return Ok(()),
+                        KeyCode::Esc => return Ok(None),
                         _ => {}
                     }
                 }
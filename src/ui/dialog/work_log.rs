@@ -0,0 +1,217 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+use crate::{storage::Storage, Member, Task, WorkLogEntry};
+
+use super::{
+    confirm_delete::ConfirmDelete,
+    create::{CreateRecordDialog, CreateResults},
+    error::DisplayWindow,
+    picker::PickerDialog,
+};
+
+/// Lists the work logged on a task, and lets members log more work or remove entries.
+pub struct WorkLogDialog {
+    cursor: usize,
+    entries: Vec<WorkLogEntry>,
+}
+
+impl WorkLogDialog {
+    /// `members` are the members of the project owning `task`, the only ones who can log work on it.
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        task: &Task,
+        members: &[Member],
+    ) -> std::io::Result<()> {
+        let mut diag = WorkLogDialog {
+            cursor: 0,
+            entries: store.fetch_work_log(task.task_id).await?,
+        };
+
+        loop {
+            diag.draw(terminal, task, members)?;
+
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down => {
+                            diag.cursor = (diag.cursor + 1) % (diag.entries.len() + 1);
+                        }
+                        KeyCode::Up => {
+                            diag.cursor = if diag.cursor > 0 {
+                                diag.cursor - 1
+                            } else {
+                                diag.entries.len()
+                            };
+                        }
+                        KeyCode::Char('c') => diag.log_work(terminal, store, task, members).await?,
+                        KeyCode::Enter if diag.cursor == diag.entries.len() => {
+                            diag.log_work(terminal, store, task, members).await?
+                        }
+                        KeyCode::Char('d') if diag.cursor < diag.entries.len() => {
+                            diag.remove_entry(terminal, store, task).await?
+                        }
+                        KeyCode::Esc => return Ok(()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    async fn log_work(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        task: &Task,
+        members: &[Member],
+    ) -> std::io::Result<()> {
+        if members.is_empty() {
+            return DisplayWindow::run(
+                terminal,
+                "Only members of the project can log work. Add some with <M> first.".into(),
+            )
+            .await;
+        }
+
+        let options = members
+            .iter()
+            .map(|m| (m.member_id, format!("{} {}", m.first_name, m.last_name)))
+            .collect();
+        let Some(member_id) = PickerDialog::run(terminal, "Who did the work?", options).await?
+        else {
+            return Ok(());
+        };
+
+        let today = chrono::Local::now().date_naive().to_string();
+        let CreateResults::Create(data) = CreateRecordDialog::new_edit(
+            vec!["Date (YYYY-MM-DD)".into(), "Hours".into(), "Note".into()],
+            vec![today, String::new(), String::new()],
+            |_d: &CreateRecordDialog| true,
+        )
+        .run(terminal)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let Ok(work_date) = chrono::NaiveDate::parse_from_str(&data[0], "%Y-%m-%d") else {
+            return DisplayWindow::run(
+                terminal,
+                "Invalid date format. Dates must be YYYY-MM-DD.".into(),
+            )
+            .await;
+        };
+        let Ok(hours) = data[1].parse::<i32>() else {
+            return DisplayWindow::run(terminal, "Hours must be a whole number.".into()).await;
+        };
+
+        if let Err(e) = store
+            .add_work_log_entry(task.task_id, member_id, work_date, hours, &data[2])
+            .await
+        {
+            DisplayWindow::run(terminal, format!("Failed to log work: {}", e)).await?;
+        }
+
+        self.entries = store.fetch_work_log(task.task_id).await?;
+        Ok(())
+    }
+
+    async fn remove_entry(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        task: &Task,
+    ) -> std::io::Result<()> {
+        if !ConfirmDelete::run(terminal).await {
+            return Ok(());
+        }
+
+        if let Err(e) = store
+            .remove_work_log_entry(self.entries[self.cursor].entry_id)
+            .await
+        {
+            DisplayWindow::run(terminal, format!("Failed to remove entry: {}", e)).await?;
+        }
+
+        self.entries = store.fetch_work_log(task.task_id).await?;
+        self.cursor = self.cursor.min(self.entries.len());
+        Ok(())
+    }
+
+    fn draw(
+        &self,
+        terminal: &mut Terminal<impl Backend>,
+        task: &Task,
+        members: &[Member],
+    ) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Log Work "),
+                Span::styled("<C> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Remove Entry "),
+                Span::styled("<D> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Return "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = self
+                .entries
+                .iter()
+                .map(|entry| {
+                    // Members who left the project keep their entries, but are no longer in `members`.
+                    let member = members
+                        .iter()
+                        .find(|m| m.member_id == entry.member_id)
+                        .map(|m| format!("{} {}", m.first_name, m.last_name))
+                        .unwrap_or_else(|| format!("Member #{}", entry.member_id));
+                    ListItem::new(format!(
+                        "{} | {} | {}h | {}",
+                        entry.work_date, member, entry.hours, entry.note
+                    ))
+                })
+                .chain(std::iter::once(
+                    ListItem::new("Log Work +").style(Style::default().fg(Color::Green)),
+                ))
+                .collect();
+
+            let total: i32 = self.entries.iter().map(|entry| entry.hours).sum();
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(format!(
+                            "Work Log for '{}' ({}h of {}h estimated)",
+                            task.title, total, task.estimated_hours
+                        ))
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100)])
+                .split(frame.size());
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+use crate::{storage::Storage, Project, StatusDefinition, Workflow};
+
+use super::{
+    create::{CreateRecordDialog, CreateResults},
+    error::DisplayWindow,
+    picker::MultiPickerDialog,
+};
+
+/// Edits the statuses of a project, their order and the transitions allowed between them.
+/// Changes are only written when the user saves.
+pub struct WorkflowDialog {
+    cursor: usize,
+    workflow: Workflow,
+}
+
+impl WorkflowDialog {
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+        project: &Project,
+    ) -> std::io::Result<()> {
+        let mut diag = WorkflowDialog {
+            cursor: 0,
+            workflow: project.workflow.clone(),
+        };
+
+        loop {
+            diag.draw(terminal, project)?;
+
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    let len = diag.workflow.statuses.len();
+                    match key_event.code {
+                        KeyCode::Down => {
+                            diag.cursor = (diag.cursor + 1) % (len + 1);
+                        }
+                        KeyCode::Up => {
+                            diag.cursor = if diag.cursor > 0 { diag.cursor - 1 } else { len };
+                        }
+                        KeyCode::Char('c') => diag.add_status(terminal).await?,
+                        KeyCode::Enter if diag.cursor == len => diag.add_status(terminal).await?,
+                        KeyCode::Char('d') if diag.cursor < len => diag.remove_status(),
+                        KeyCode::Char('t') if diag.cursor < len => {
+                            diag.edit_transitions(terminal).await?
+                        }
+                        KeyCode::Char('k') if diag.cursor > 0 && diag.cursor < len => {
+                            diag.workflow.statuses.swap(diag.cursor, diag.cursor - 1);
+                            diag.cursor -= 1;
+                        }
+                        KeyCode::Char('j') if diag.cursor + 1 < len => {
+                            diag.workflow.statuses.swap(diag.cursor, diag.cursor + 1);
+                            diag.cursor += 1;
+                        }
+                        KeyCode::Char('s') => {
+                            match store.update_workflow(project.proj_id, &diag.workflow).await {
+                                Ok(()) => return Ok(()),
+                                Err(e) => {
+                                    DisplayWindow::run(
+                                        terminal,
+                                        format!("Failed to save workflow: {}", e),
+                                    )
+                                    .await?
+                                }
+                            }
+                        }
+                        KeyCode::Esc => return Ok(()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    async fn add_status(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        let CreateResults::Create(data) = CreateRecordDialog::new(
+            vec!["Name".into(), "Emoji".into()],
+            |d: &CreateRecordDialog| !d.entries()[0].trim().is_empty(),
+        )
+        .run(terminal)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let name = data[0].trim().to_string();
+        if self.workflow.statuses.iter().any(|s| s.name == name) {
+            return DisplayWindow::run(terminal, format!("Status '{}' already exists.", name))
+                .await;
+        }
+
+        self.workflow.statuses.push(StatusDefinition {
+            name,
+            emoji: data[1].trim().to_string(),
+        });
+        self.cursor = self.workflow.statuses.len() - 1;
+        Ok(())
+    }
+
+    /// Removes the selected status along with every transition from or to it.
+    fn remove_status(&mut self) {
+        let removed = self.workflow.statuses.remove(self.cursor);
+        self.workflow
+            .transitions
+            .retain(|(from, to)| *from != removed.name && *to != removed.name);
+        self.cursor = self.cursor.min(self.workflow.statuses.len());
+    }
+
+    /// Lets the user pick the statuses tasks may move to from the selected one.
+    async fn edit_transitions(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+    ) -> std::io::Result<()> {
+        let from = self.workflow.statuses[self.cursor].name.clone();
+
+        // Statuses are identified by their position in the workflow.
+        let options = self
+            .workflow
+            .statuses
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.name != from)
+            .map(|(idx, s)| (idx as i32, format!("{} {}", s.name, s.emoji)))
+            .collect();
+        let picked = self
+            .workflow
+            .statuses
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.name != from && self.workflow.can_transition(&from, &s.name))
+            .map(|(idx, _)| idx as i32)
+            .collect();
+
+        let title = format!("Tasks in {} may move to", from);
+        let Some(picked) = MultiPickerDialog::run(terminal, &title, options, picked).await? else {
+            return Ok(());
+        };
+
+        self.workflow.transitions.retain(|(f, _)| *f != from);
+        for idx in picked {
+            let to = self.workflow.statuses[idx as usize].name.clone();
+            self.workflow.transitions.push((from.clone(), to));
+        }
+        Ok(())
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>, project: &Project) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Add Status "),
+                Span::styled("<C> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Remove "),
+                Span::styled("<D> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Transitions "),
+                Span::styled("<T> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Move Up/Down "),
+                Span::styled("<K>/<J> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Save "),
+                Span::styled("<S> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Discard "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = self
+                .workflow
+                .statuses
+                .iter()
+                .map(|status| {
+                    let targets: Vec<&str> = self
+                        .workflow
+                        .statuses
+                        .iter()
+                        .filter(|to| {
+                            to.name != status.name
+                                && self.workflow.can_transition(&status.name, &to.name)
+                        })
+                        .map(|to| to.name.as_str())
+                        .collect();
+                    let targets = if targets.is_empty() {
+                        "(final)".to_string()
+                    } else {
+                        targets.join(", ")
+                    };
+                    ListItem::new(format!("{} {} → {}", status.name, status.emoji, targets))
+                })
+                .chain(std::iter::once(
+                    ListItem::new("Add Status +").style(Style::default().fg(Color::Green)),
+                ))
+                .collect();
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title(format!("Workflow of '{}'", project.title))
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(100)])
+                .split(frame.size());
+
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.cursor));
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
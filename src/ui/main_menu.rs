@@ -1,15 +1,19 @@
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::prelude::*;
 use ratatui::widgets::{Paragraph, Widget, Wrap};
-use sqlx::MySqlPool;
-
-use crate::ui::{members, projects};
+use crate::audit;
+use crate::storage::Storage;
+use crate::ui::dialog::prelude::PickerDialog;
+use crate::ui::{members, projects, trash};
+use crate::undo::UndoStack;
 
 /// An enum describing the possible cursor positions in the main menu.
 #[derive(Clone, PartialEq, Eq)]
 enum MainMenuCursor {
     ManageProjects,
     ManageMembers,
+    Trash,
+    ActingAs,
     Exit,
 }
 
@@ -18,7 +22,9 @@ impl MainMenuCursor {
     fn next(&mut self) {
         *self = match *self {
             MainMenuCursor::ManageProjects => MainMenuCursor::ManageMembers,
-            MainMenuCursor::ManageMembers => MainMenuCursor::Exit,
+            MainMenuCursor::ManageMembers => MainMenuCursor::Trash,
+            MainMenuCursor::Trash => MainMenuCursor::ActingAs,
+            MainMenuCursor::ActingAs => MainMenuCursor::Exit,
             MainMenuCursor::Exit => MainMenuCursor::ManageProjects,
         }
     }
@@ -28,7 +34,9 @@ impl MainMenuCursor {
         *self = match *self {
             MainMenuCursor::ManageProjects => MainMenuCursor::Exit,
             MainMenuCursor::ManageMembers => MainMenuCursor::ManageProjects,
-            MainMenuCursor::Exit => MainMenuCursor::ManageMembers,
+            MainMenuCursor::Trash => MainMenuCursor::ManageMembers,
+            MainMenuCursor::ActingAs => MainMenuCursor::Trash,
+            MainMenuCursor::Exit => MainMenuCursor::ActingAs,
         }
     }
 }
@@ -38,6 +46,10 @@ impl MainMenuCursor {
 /// The main app only has the cursor for its state.
 pub struct App {
     cursor: MainMenuCursor,
+    /// The name of the member changes are recorded against, if any.
+    acting_as: Option<String>,
+    /// The edits made in the project manager, kept for the whole session.
+    undo: UndoStack,
 }
 
 impl App {
@@ -45,19 +57,23 @@ impl App {
     pub fn new() -> Self {
         App {
             cursor: MainMenuCursor::ManageProjects,
+            acting_as: None,
+            undo: UndoStack::default(),
         }
     }
 
     /// Returns a rendering of all of the lines for the main menu, with the selected one highlighted.
-    fn get_main_menu_lines(&self) -> Vec<Span> {
+    fn get_main_menu_lines(&self) -> Vec<Span<'_>> {
         let highlight_style = Style::default()
             .fg(Color::Black)
             .bg(Color::White)
             .add_modifier(Modifier::BOLD);
 
-        let menu_items = vec![
+        let menu_items = [
             MainMenuCursor::ManageProjects,
             MainMenuCursor::ManageMembers,
+            MainMenuCursor::Trash,
+            MainMenuCursor::ActingAs,
             MainMenuCursor::Exit,
         ];
 
@@ -65,9 +81,14 @@ impl App {
             .iter()
             .map(|item| {
                 let name = match item {
-                    MainMenuCursor::ManageProjects => "Manage Projects",
-                    MainMenuCursor::ManageMembers => "Manage Members",
-                    MainMenuCursor::Exit => "Exit",
+                    MainMenuCursor::ManageProjects => "Manage Projects".to_string(),
+                    MainMenuCursor::ManageMembers => "Manage Members".to_string(),
+                    MainMenuCursor::Trash => "Trash".to_string(),
+                    MainMenuCursor::ActingAs => format!(
+                        "Acting as: {}",
+                        self.acting_as.as_deref().unwrap_or("Nobody")
+                    ),
+                    MainMenuCursor::Exit => "Exit".to_string(),
                 };
 
                 let is_selected = self.cursor == *item;
@@ -84,7 +105,7 @@ impl App {
     pub async fn run(
         &mut self,
         mut terminal: Terminal<impl Backend>,
-        pool: &MySqlPool,
+        store: &impl Storage,
     ) -> std::io::Result<()> {
         loop {
             self.draw(&mut terminal)?;
@@ -99,14 +120,24 @@ impl App {
                         Down => self.cursor.next(),
                         Up => self.cursor.prev(),
                         Enter => {
-                            let _ = match cur {
+                            match cur {
                                 MainMenuCursor::ManageProjects => {
-                                    projects::ProjectManager::run(&mut terminal, pool.clone())
-                                        .await?
+                                    projects::ProjectManager::run(
+                                        &mut terminal,
+                                        store.clone(),
+                                        &mut self.undo,
+                                    )
+                                    .await?
                                 }
                                 MainMenuCursor::ManageMembers => {
-                                    let mut mgr = members::MemberManager::new(&pool.clone()).await;
-                                    mgr.run(&mut terminal, &pool.clone()).await?;
+                                    let mut mgr = members::MemberManager::new(store).await;
+                                    mgr.run(&mut terminal, store).await?;
+                                }
+                                MainMenuCursor::Trash => {
+                                    trash::TrashManager::run(&mut terminal, store).await?
+                                }
+                                MainMenuCursor::ActingAs => {
+                                    self.pick_acting_member(&mut terminal, store).await?
                                 }
                                 MainMenuCursor::Exit => {
                                     return Ok(());
@@ -120,6 +151,32 @@ impl App {
         }
     }
 
+    /// Picks the member every following change is recorded against in the audit log.
+    async fn pick_acting_member(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+    ) -> std::io::Result<()> {
+        let members = store.fetch_members().await?;
+        // Member IDs start at 1, so 0 is free to stand for nobody.
+        let options = std::iter::once((0, "Nobody".to_string()))
+            .chain(
+                members
+                    .iter()
+                    .map(|m| (m.member_id, format!("{} {}", m.first_name, m.last_name))),
+            )
+            .collect();
+        let Some(member_id) = PickerDialog::run(terminal, "Who is making changes?", options).await?
+        else {
+            return Ok(());
+        };
+
+        let acting = members.into_iter().find(|m| m.member_id == member_id);
+        audit::set_acting_member(acting.as_ref().map(|m| m.member_id));
+        self.acting_as = acting.map(|m| format!("{} {}", m.first_name, m.last_name));
+        Ok(())
+    }
+
     /// Draws this menu to the terminal.
     fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
         terminal.draw(|f| f.render_widget(self, f.size()))?;
@@ -131,7 +188,7 @@ impl App {
         let text_lines: Vec<Line> = self
             .get_main_menu_lines()
             .into_iter()
-            .map(|span| Line::from(span))
+            .map(Line::from)
             .collect();
 
         let paragraph = Paragraph::new(text_lines)
@@ -152,9 +209,7 @@ fn render_title(area: Rect, buf: &mut Buffer) {
 
 /// Renders the footer of the application to the main menu.
 fn render_footer(area: Rect, buf: &mut Buffer) {
-    Paragraph::new(format!(
-        "TATs 2024© All Rights Reserved. Developed exclusively by Drew Ridley."
-    ))
+    Paragraph::new("TATs 2024© All Rights Reserved. Developed exclusively by Drew Ridley.")
     .bold()
     .centered()
     .render(area, buf);
@@ -6,11 +6,12 @@ use ratatui::{
     widgets::{block::Title, Block, Borders, List},
     Terminal,
 };
-use sqlx::MySqlPool;
-
-use crate::Member;
+use crate::{
+    storage::{Storage, StorageError},
+    Member, RecordKind,
+};
 
-use super::dialog::prelude::{ConfirmDelete, CreateRecordDialog, CreateResults};
+use super::dialog::prelude::{ConfirmDelete, ConflictDialog, CreateRecordDialog, CreateResults};
 
 pub struct MemberManager {
     members: Vec<Member>,
@@ -18,35 +19,31 @@ pub struct MemberManager {
 }
 
 impl MemberManager {
-    pub async fn new(pool: &MySqlPool) -> Self {
+    pub async fn new(store: &impl Storage) -> Self {
         let mut manager = MemberManager {
             members: vec![],
             cursor: 0,
         };
 
-        manager.fetch_members(pool).await;
+        manager.fetch_members(store).await;
         manager
     }
 
-    pub async fn fetch_members(&mut self, pool: &MySqlPool) {
-        let fetch_query = "SELECT MemberID, firstName, lastName, email, phone FROM Member";
-        let members = sqlx::query_as::<_, Member>(fetch_query)
-            .fetch_all(pool)
-            .await
-            .expect("Failed to fetch members");
+    pub async fn fetch_members(&mut self, store: &impl Storage) {
+        let members = store.fetch_members().await.expect("Failed to fetch members");
         self.members = members;
     }
 
     pub async fn run<B: Backend>(
         &mut self,
         terminal: &mut Terminal<B>,
-        pool: &MySqlPool,
+        store: &impl Storage,
     ) -> std::io::Result<CreateResults> {
         loop {
             self.draw(terminal)?;
 
-            if self.members.len() == 0 {
-                self.fetch_members(pool).await;
+            if self.members.is_empty() {
+                self.fetch_members(store).await;
             }
 
             if let Event::Key(key_event) = read()? {
@@ -67,19 +64,19 @@ impl MemberManager {
                             .await?
                             {
                                 CreateResults::Create(data) => {
-                                    let insert_query = "INSERT INTO Member (firstName, lastName, email, phone) VALUES (?, ?, ?, ?)";
-                                    let result = sqlx::query(insert_query)
-                                        .bind(&data[0]) // First Name
-                                        .bind(&data[1]) // Last Name
-                                        .bind(&data[2]) // Email
-                                        .bind(&data[3]) // Phone
-                                        .execute(pool)
+                                    let result = store
+                                        .create_member(
+                                            &data[0], // First Name
+                                            &data[1], // Last Name
+                                            &data[2], // Email
+                                            &data[3], // Phone
+                                        )
                                         .await;
 
                                     match result {
                                         Ok(_) => {
                                             // Fetch members again to update the list with new member
-                                            self.fetch_members(pool).await;
+                                            self.fetch_members(store).await;
                                         }
                                         Err(e) => {
                                             eprintln!("Failed to create member: {}", e);
@@ -115,25 +112,54 @@ impl MemberManager {
                             .run(terminal)
                             .await?
                             {
-                                CreateResults::Create(data) => {
-                                    let update_query = "UPDATE Member SET firstName = ?, lastName = ?, email = ?, phone = ? WHERE MemberID = ?";
-                                    let result = sqlx::query(update_query)
-                                        .bind(&data[0]) // First Name
-                                        .bind(&data[1]) // Last Name
-                                        .bind(&data[2]) // Email
-                                        .bind(&data[3]) // Phone
-                                        .bind(current_member.member_id) // MemberID
-                                        .execute(pool)
-                                        .await;
+                                CreateResults::Create(mut data) => {
+                                    let member_id = current_member.member_id;
+                                    let mut version = current_member.version;
+                                    loop {
+                                        let result = store
+                                            .update_member(
+                                                member_id, // MemberID
+                                                version,   // Version the edit is based on
+                                                &data[0],  // First Name
+                                                &data[1],  // Last Name
+                                                &data[2],  // Email
+                                                &data[3],  // Phone
+                                            )
+                                            .await;
 
-                                    match result {
-                                        Ok(_) => {
-                                            // Fetch members again to update the list with edited member
-                                            self.fetch_members(pool).await;
-                                        }
-                                        Err(e) => {
-                                            eprintln!("Failed to update member: {}", e);
+                                        match result {
+                                            Ok(_) => {}
+                                            Err(StorageError::Conflict) => {
+                                                // Someone else edited the member meanwhile, let the user sort it out.
+                                                let theirs = store
+                                                    .fetch_members()
+                                                    .await?
+                                                    .into_iter()
+                                                    .find(|m| m.member_id == member_id);
+                                                if let Some(theirs) = theirs {
+                                                    let resolved = ConflictDialog::run(
+                                                        terminal,
+                                                        &format!("Member '{} {}'", theirs.first_name, theirs.last_name),
+                                                        &["First Name", "Last Name", "Email", "Phone"],
+                                                        &data,
+                                                        &[theirs.first_name, theirs.last_name, theirs.email, theirs.phone],
+                                                    )
+                                                    .await?;
+                                                    if let Some(resolved) = resolved {
+                                                        data = resolved;
+                                                        version = theirs.version;
+                                                        continue;
+                                                    }
+                                                }
+                                            }
+                                            Err(e) => {
+                                                eprintln!("Failed to update member: {}", e);
+                                            }
                                         }
+
+                                        // Fetch members again to update the list with edited member
+                                        self.fetch_members(store).await;
+                                        break;
                                     }
                                 }
                                 CreateResults::Quit => {
@@ -141,30 +167,19 @@ impl MemberManager {
                                 }
                             };
                         }
-                        KeyCode::Char('d') => {
-                            if ConfirmDelete::run(terminal).await {
-                                let current_member = &self.members[self.cursor];
-
-                                let delete_contributions_query =
-                                    "DELETE FROM ContributesTo WHERE MemberID = ?";
-                                let _ = sqlx::query(delete_contributions_query)
-                                    .bind(current_member.member_id)
-                                    .execute(pool)
-                                    .await;
+                        KeyCode::Char('d') if ConfirmDelete::run(terminal).await => {
+                            let current_member = &self.members[self.cursor];
 
-                                let delete_query = "DELETE FROM Member WHERE MemberID = ?";
-                                let result = sqlx::query(delete_query)
-                                    .bind(current_member.member_id)
-                                    .execute(pool)
-                                    .await;
+                            let result = store
+                                .move_to_trash(RecordKind::Member, current_member.member_id)
+                                .await;
 
-                                match result {
-                                    Ok(_) => {
-                                        self.fetch_members(pool).await;
-                                    }
-                                    Err(_) => {
-                                        panic!("Failed to delete member!");
-                                    }
+                            match result {
+                                Ok(_) => {
+                                    self.fetch_members(store).await;
+                                }
+                                Err(_) => {
+                                    panic!("Failed to delete member!");
                                 }
                             }
                         }
@@ -230,7 +245,7 @@ impl MemberManager {
                             style,
                         ),
                         Span::styled(format!("{} ", member.email), style),
-                        Span::styled(format!("{}", member.phone), style),
+                        Span::styled(member.phone.to_string(), style),
                     ]);
 
                     line
@@ -3,6 +3,7 @@ mod main_menu;
 
 mod members;
 mod projects;
+mod trash;
 
 pub mod prelude {
     pub use super::main_menu::App;
@@ -1,4 +1,7 @@
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use std::{collections::HashMap, time::Duration};
+
+use chrono::NaiveDate;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     backend::Backend,
     style::{Color, Modifier, Style},
@@ -6,13 +9,19 @@ use ratatui::{
     widgets::{block::Title, Block, Borders, List, Widget},
     Terminal,
 };
-use sqlx::MySqlPool;
 
 use crate::{
-    crud::{delete_project_by_id, delete_sprint_by_id, delete_task_by_id, fetch_projects},
-    Project,
+    storage::{Storage, StorageError},
+    undo::{Edit, Operation, UndoStack},
+    ChecklistItem, Label, Member, Project, RecordKind, Sprint, Task, Workflow,
 };
 
+/// How long to wait for a key press before looking for changes made by other clients.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Label of the priority field in the task forms.
+const PRIORITY_LABEL: &str = "Priority (P0-P3)";
+
 // Import all dialogs.
 use super::dialog::prelude::*;
 
@@ -21,6 +30,8 @@ enum ProjectCursorDepth {
     Project,
     Sprint,
     Task,
+    /// The checklist items of the selected task.
+    Item,
 }
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
@@ -29,9 +40,24 @@ struct ProjectCursor {
     project: Option<u8>,
     sprint: Option<u8>,
     task: Option<u8>,
+    item: Option<u8>,
 }
 
 impl ProjectCursor {
+    /// The selected task and, at item depth, the selected item of its checklist, if they exist.
+    fn checklist_item<'p>(
+        &self,
+        projects: &'p [Project],
+    ) -> Option<(&'p Task, Option<&'p ChecklistItem>)> {
+        let task = projects
+            .get(self.project? as usize)?
+            .sprints
+            .get(self.sprint? as usize)?
+            .tasks
+            .get(self.task? as usize)?;
+        Some((task, self.item.and_then(|i| task.checklist.get(i as usize))))
+    }
+
     fn next(&mut self) {
         match self.depth {
             ProjectCursorDepth::Project => {
@@ -50,6 +76,11 @@ impl ProjectCursor {
                     self.task = self.task.map_or(Some(0), |t| Some(t + 1));
                 }
             }
+            ProjectCursorDepth::Item => {
+                if self.project.is_some() && self.sprint.is_some() && self.task.is_some() {
+                    self.item = self.item.map_or(Some(0), |i| Some(i + 1));
+                }
+            }
         }
     }
 
@@ -83,6 +114,15 @@ impl ProjectCursor {
                     }
                 }
             }
+            ProjectCursorDepth::Item => {
+                if self.project.is_some() && self.sprint.is_some() && self.task.is_some() {
+                    if let Some(i) = self.item {
+                        if i > 0 {
+                            self.item = Some(i - 1);
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -97,7 +137,11 @@ impl ProjectCursor {
                 self.task = Some(0);
                 self.depth = ProjectCursorDepth::Task;
             }
-            ProjectCursorDepth::Task => {}
+            ProjectCursorDepth::Task => {
+                self.item = Some(0);
+                self.depth = ProjectCursorDepth::Item;
+            }
+            ProjectCursorDepth::Item => {}
         }
     }
 
@@ -113,6 +157,10 @@ impl ProjectCursor {
                 self.task = None;
                 self.depth = ProjectCursorDepth::Sprint;
             }
+            ProjectCursorDepth::Item => {
+                self.item = None;
+                self.depth = ProjectCursorDepth::Task;
+            }
         }
     }
 }
@@ -124,14 +172,27 @@ impl Default for ProjectCursor {
             project: Some(0),
             sprint: None,
             task: None,
+            item: None,
         }
     }
 }
 
-pub struct ProjectManager {
+pub struct ProjectManager<'a, S: Storage> {
     cursor: ProjectCursor,
     projects: Vec<Project>,
-    pool: MySqlPool,
+    store: S,
+    /// The edits of this session, shared with the main menu so they outlive this manager.
+    undo: &'a mut UndoStack,
+    /// The latest audit log entry the projects were loaded after.
+    last_change: i32,
+    /// The records other clients changed, shown until the next key press.
+    changed_elsewhere: Vec<String>,
+    /// Names of the labels a task has to carry, all of them, to be shown. Empty shows every task.
+    label_filter: Vec<String>,
+    /// The IDs of the unfinished tasks each blocked task waits for, including tasks hidden by the filter.
+    blockers: HashMap<i32, Vec<i32>>,
+    /// The number of tasks of each sprint, by sprint ID, including tasks hidden by the filter.
+    task_counts: HashMap<i32, usize>,
 }
 
 #[derive(Default)]
@@ -141,32 +202,197 @@ enum RunResult {
     Return,
 }
 
-impl ProjectManager {
-    pub fn new(pool: MySqlPool) -> Self {
+impl<'a, S: Storage> ProjectManager<'a, S> {
+    pub fn new(store: S, undo: &'a mut UndoStack) -> Self {
         Self {
             cursor: Default::default(),
             projects: vec![],
-            pool,
+            store,
+            undo,
+            last_change: 0,
+            changed_elsewhere: vec![],
+            label_filter: vec![],
+            blockers: HashMap::new(),
+            task_counts: HashMap::new(),
         }
     }
 
     async fn fetch_projects(&mut self) {
-        self.projects = fetch_projects(&self.pool).await.unwrap();
+        // Taken first, so whatever happens while the projects load is picked up by the next refresh.
+        self.last_change = self.store.fetch_latest_change().await.unwrap();
+        let projects = self.store.fetch_projects().await.unwrap();
+        self.show(projects);
+    }
+
+    /// Shows freshly loaded projects, leaving out the tasks that do not match the label filter
+    /// and sorting the rest by priority. Blockers and task counts take every task into account.
+    fn show(&mut self, mut projects: Vec<Project>) {
+        // Blockers are looked up before filtering, so tasks waiting for hidden ones stay marked.
+        self.blockers = projects
+            .iter()
+            .flat_map(|project| {
+                project.sprints.iter().flat_map(|s| &s.tasks).map(move |task| {
+                    let unfinished = project
+                        .blockers(task)
+                        .iter()
+                        .filter(|b| !project.workflow.is_done(&b.status))
+                        .map(|b| b.task_id)
+                        .collect();
+                    (task.task_id, unfinished)
+                })
+            })
+            .collect();
+        self.task_counts = projects
+            .iter()
+            .flat_map(|p| &p.sprints)
+            .map(|s| (s.sprint_id, s.tasks.len()))
+            .collect();
+
+        // The most urgent tasks of each sprint come first.
+        for sprint in projects.iter_mut().flat_map(|p| &mut p.sprints) {
+            sprint.tasks.retain(|task| task.has_labels(&self.label_filter));
+            sprint.tasks.sort_by_key(|task| (task.priority, task.task_id));
+        }
+        self.projects = projects;
+    }
+
+    /// Reloads the projects if another client changed anything since they were last loaded, or
+    /// regardless with `force`. The cursor stays on the same project, sprint, task
+    /// and checklist item if they are still there, and moves up to the nearest one left otherwise.
+    async fn refresh(&mut self, force: bool) -> std::io::Result<()> {
+        let changes = self.store.fetch_changes_since(self.last_change).await?;
+        if changes.is_empty() && !force {
+            return Ok(());
+        }
+
+        let on_create_row = self.cursor.project == Some(self.projects.len() as u8);
+        let project = self.cursor.project.and_then(|p| self.projects.get(p as usize));
+        let sprint = project
+            .zip(self.cursor.sprint)
+            .and_then(|(p, s)| p.sprints.get(s as usize));
+        let task = sprint
+            .zip(self.cursor.task)
+            .and_then(|(s, t)| s.tasks.get(t as usize));
+        let item = task
+            .zip(self.cursor.item)
+            .and_then(|(t, i)| t.checklist.get(i as usize));
+        let (project, sprint, task, item) = (
+            project.map(|p| p.proj_id),
+            sprint.map(|s| s.sprint_id),
+            task.map(|t| t.task_id),
+            item.map(|i| i.item_id),
+        );
+
+        let projects = self.store.fetch_projects().await?;
+        self.show(projects);
+        if let Some((_, _, entry)) = changes.last() {
+            self.last_change = entry.entry_id;
+        }
+
+        if on_create_row {
+            self.cursor.project = Some(self.projects.len() as u8);
+        } else {
+            // The deepest of the selected records that is still there.
+            let mut found = None;
+            if let Some(p) = project.and_then(|id| self.projects.iter().position(|p| p.proj_id == id)) {
+                self.cursor.project = Some(p as u8);
+                found = Some(ProjectCursorDepth::Project);
+                let sprints = &self.projects[p].sprints;
+                if let Some(s) = sprint.and_then(|id| sprints.iter().position(|s| s.sprint_id == id)) {
+                    self.cursor.sprint = Some(s as u8);
+                    found = Some(ProjectCursorDepth::Sprint);
+                    let tasks = &sprints[s].tasks;
+                    if let Some(t) = task.and_then(|id| tasks.iter().position(|t| t.task_id == id)) {
+                        self.cursor.task = Some(t as u8);
+                        found = Some(ProjectCursorDepth::Task);
+                        let checklist = &tasks[t].checklist;
+                        if let Some(i) = item.and_then(|id| checklist.iter().position(|i| i.item_id == id)) {
+                            self.cursor.item = Some(i as u8);
+                            found = Some(ProjectCursorDepth::Item);
+                        }
+                    }
+                }
+            }
+
+            // A selected record that is gone takes the cursor up to its parent.
+            match found {
+                Some(depth) => {
+                    while self.cursor.depth > depth {
+                        self.cursor.decrease_depth();
+                    }
+                }
+                None => self.cursor = ProjectCursor::default(),
+            }
+        }
+
+        let mut changed = Vec::new();
+        for (kind, id, _) in changes {
+            if !changed.contains(&(kind, id)) {
+                changed.push((kind, id));
+            }
+        }
+        self.changed_elsewhere = changed
+            .into_iter()
+            .map(|(kind, id)| match self.title_of(kind, id) {
+                Some(title) => format!("{} '{}'", kind.label(), title),
+                None => format!("{} #{}", kind.label(), id),
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// The title of a loaded record, or the name of a member. `None` if it is not among the projects.
+    fn title_of(&self, kind: RecordKind, id: i32) -> Option<String> {
+        let sprints = || self.projects.iter().flat_map(|p| &p.sprints);
+        match kind {
+            RecordKind::Project => self
+                .projects
+                .iter()
+                .find(|p| p.proj_id == id)
+                .map(|p| p.title.clone()),
+            RecordKind::Sprint => sprints().find(|s| s.sprint_id == id).map(|s| s.title.clone()),
+            RecordKind::Task => sprints()
+                .flat_map(|s| &s.tasks)
+                .find(|t| t.task_id == id)
+                .map(|t| t.title.clone()),
+            RecordKind::Member => self
+                .projects
+                .iter()
+                .flat_map(|p| &p.members)
+                .find(|m| m.member_id == id)
+                .map(|m| format!("{} {}", m.first_name, m.last_name)),
+            RecordKind::ChecklistItem => sprints()
+                .flat_map(|s| &s.tasks)
+                .flat_map(|t| &t.checklist)
+                .find(|i| i.item_id == id)
+                .map(|i| i.title.clone()),
+            // Comments are not loaded along with the projects.
+            RecordKind::Comment => None,
+        }
     }
 
     pub async fn run(
-        mut terminal: &mut Terminal<impl Backend>,
-        pool: MySqlPool,
+        terminal: &mut Terminal<impl Backend>,
+        store: S,
+        undo: &'a mut UndoStack,
     ) -> std::io::Result<()> {
-        let mut mgr = Self::new(pool);
+        let mut mgr = Self::new(store, undo);
         mgr.fetch_projects().await;
 
         loop {
-            mgr.draw(&mut terminal)?;
+            mgr.draw(terminal)?;
+
+            if !event::poll(REFRESH_INTERVAL)? {
+                // A failed look is simply retried after the next interval.
+                let _ = mgr.refresh(false).await;
+                continue;
+            }
 
             match event::read()? {
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
-                    match mgr.handle_key_press(terminal, key.code).await {
+                    mgr.changed_elsewhere.clear();
+                    match mgr.handle_key_press(terminal, key).await {
                         Ok(result) => match result {
                             RunResult::Continue => {}
                             RunResult::Return => {
@@ -188,7 +414,7 @@ impl ProjectManager {
         }
     }
 
-    async fn edit_project(&self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+    async fn edit_project(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
         let current_proj = &self.projects[self.cursor.project.unwrap() as usize];
         let current_data = vec![current_proj.title.clone(), current_proj.desc.clone()];
 
@@ -200,33 +426,80 @@ impl ProjectManager {
         .run(terminal)
         .await?
         {
-            CreateResults::Create(data) => {
-                // Extract updated data
-                let new_title = &data[0];
-                let new_description = &data[1];
-
-                // SQL statement to update the project
-                let update_query =
-                    "UPDATE Project SET Title = ?, Description = ? WHERE ProjectID = ?";
-                let result = sqlx::query(update_query)
-                    .bind(new_title)
-                    .bind(new_description)
-                    .bind(current_proj.proj_id)
-                    .execute(&self.pool)
-                    .await;
-
-                match result {
-                    Ok(_) => Ok(()),
-                    Err(e) => {
-                        DisplayWindow::run(terminal, format!("Failed to update project: {}", e))
-                            .await
-                            .expect("Failed to show error screen.");
-                        Ok(())
+            CreateResults::Create(mut data) => {
+                // What the update is based on, moved along to someone else's changes on a conflict.
+                let mut version = current_proj.version;
+                let mut before = vec![current_proj.title.clone(), current_proj.desc.clone()];
+
+                loop {
+                    // Extract updated data
+                    let new_title = &data[0];
+                    let new_description = &data[1];
+
+                    let result = self
+                        .store
+                        .update_project(current_proj.proj_id, version, new_title, new_description)
+                        .await;
+
+                    match result {
+                        Ok(_) => {
+                            self.undo.push(Edit {
+                                summary: format!("edit project '{}'", current_proj.title),
+                                undo: vec![Operation::UpdateProject {
+                                    project_id: current_proj.proj_id,
+                                    version: version + 1,
+                                    title: before[0].clone(),
+                                    description: before[1].clone(),
+                                }],
+                                redo: vec![Operation::UpdateProject {
+                                    project_id: current_proj.proj_id,
+                                    version,
+                                    title: new_title.clone(),
+                                    description: new_description.clone(),
+                                }],
+                            });
+                            return Ok(());
+                        }
+                        Err(StorageError::Conflict) => {
+                            let Some(theirs) = self
+                                .store
+                                .fetch_projects()
+                                .await?
+                                .into_iter()
+                                .find(|p| p.proj_id == current_proj.proj_id)
+                            else {
+                                return DisplayWindow::run(
+                                    terminal,
+                                    "Someone else deleted the project in the meantime.".into(),
+                                )
+                                .await;
+                            };
+                            version = theirs.version;
+                            before = vec![theirs.title, theirs.desc];
+                            match ConflictDialog::run(
+                                terminal,
+                                &format!("Project '{}'", current_proj.title),
+                                &["Title", "Description"],
+                                &data,
+                                &before,
+                            )
+                            .await?
+                            {
+                                Some(resolved) => data = resolved,
+                                None => return Ok(()),
+                            }
+                        }
+                        Err(e) => {
+                            DisplayWindow::run(terminal, format!("Failed to update project: {}", e))
+                                .await
+                                .expect("Failed to show error screen.");
+                            return Ok(());
+                        }
                     }
                 }
             }
             CreateResults::Quit => {
-                DisplayWindow::run(terminal, format!("Quit editor. No Changes Made..."))
+                DisplayWindow::run(terminal, "Quit editor. No Changes Made...".to_string())
                     .await
                     .expect("Failed to show error screen.");
                 Ok(())
@@ -234,9 +507,16 @@ impl ProjectManager {
         }
     }
 
-    async fn edit_sprint(&self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+    async fn edit_sprint(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
         let current_proj = &self.projects[self.cursor.project.unwrap() as usize];
         let current_sprint = &current_proj.sprints[self.cursor.sprint.unwrap() as usize];
+        if current_sprint.backlog {
+            return DisplayWindow::run(
+                terminal,
+                format!("The backlog of '{}' has no title or dates to edit.", current_proj.title),
+            )
+            .await;
+        }
         let current_data = vec![
             current_sprint.title.clone(),
             current_sprint.start_date.to_string(),
@@ -251,38 +531,106 @@ impl ProjectManager {
         .run(terminal)
         .await?
         {
-            CreateResults::Create(data) => {
-                let new_title = &data[0];
-                let new_start_date = chrono::NaiveDate::parse_from_str(&data[1], "%Y-%m-%d").ok();
-                let new_end_date = chrono::NaiveDate::parse_from_str(&data[2], "%Y-%m-%d").ok();
-
-                if new_start_date.is_none() || new_end_date.is_none() {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        "Invalid date format",
-                    ));
-                }
+            CreateResults::Create(mut data) => {
+                // What the update is based on, moved along to someone else's changes on a conflict.
+                let mut version = current_sprint.version;
+                let mut before = (
+                    current_sprint.title.clone(),
+                    current_sprint.start_date,
+                    current_sprint.end_date,
+                );
+
+                loop {
+                    let new_title = &data[0];
+                    let new_start_date = chrono::NaiveDate::parse_from_str(&data[1], "%Y-%m-%d").ok();
+                    let new_end_date = chrono::NaiveDate::parse_from_str(&data[2], "%Y-%m-%d").ok();
+
+                    if new_start_date.is_none() || new_end_date.is_none() {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            "Invalid date format",
+                        ));
+                    }
 
-                let update_query =
-                    "UPDATE Sprint SET Title = ?, startDate = ?, endDate = ? WHERE SprintID = ?";
-                sqlx::query(update_query)
-                    .bind(new_title)
-                    .bind(new_start_date.unwrap())
-                    .bind(new_end_date.unwrap())
-                    .bind(current_sprint.sprint_id)
-                    .execute(&self.pool)
-                    .await
-                    .map_err(|_e| {
-                        std::io::Error::new(std::io::ErrorKind::Other, "Failed to update sprint")
-                    })?;
+                    match self
+                        .store
+                        .update_sprint(
+                            current_sprint.sprint_id,
+                            version,
+                            new_title,
+                            new_start_date.unwrap(),
+                            new_end_date.unwrap(),
+                        )
+                        .await
+                    {
+                        Ok(()) => {}
+                        Err(StorageError::Conflict) => {
+                            let Some(theirs) = self
+                                .store
+                                .fetch_projects()
+                                .await?
+                                .into_iter()
+                                .flat_map(|p| p.sprints)
+                                .find(|s| s.sprint_id == current_sprint.sprint_id)
+                            else {
+                                return DisplayWindow::run(
+                                    terminal,
+                                    "Someone else deleted the sprint in the meantime.".into(),
+                                )
+                                .await;
+                            };
+                            version = theirs.version;
+                            before = (theirs.title, theirs.start_date, theirs.end_date);
+                            match ConflictDialog::run(
+                                terminal,
+                                &format!("Sprint '{}'", current_sprint.title),
+                                &["Title", "Start Date", "End Date"],
+                                &data,
+                                &[before.0.clone(), before.1.to_string(), before.2.to_string()],
+                            )
+                            .await?
+                            {
+                                Some(resolved) => data = resolved,
+                                None => return Ok(()),
+                            }
+                            continue;
+                        }
+                        Err(StorageError::Invalid(reason)) => {
+                            return DisplayWindow::run(
+                                terminal,
+                                format!("Failed to update sprint: {}", reason),
+                            )
+                            .await;
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
 
-                Ok(())
+                    self.undo.push(Edit {
+                        summary: format!("edit sprint '{}'", current_sprint.title),
+                        undo: vec![Operation::UpdateSprint {
+                            sprint_id: current_sprint.sprint_id,
+                            version: version + 1,
+                            title: before.0,
+                            start_date: before.1,
+                            end_date: before.2,
+                        }],
+                        redo: vec![Operation::UpdateSprint {
+                            sprint_id: current_sprint.sprint_id,
+                            version,
+                            title: new_title.clone(),
+                            start_date: new_start_date.unwrap(),
+                            end_date: new_end_date.unwrap(),
+                        }],
+                    });
+
+                    return Ok(());
+                }
             }
             CreateResults::Quit => Ok(()),
         }
     }
 
-    async fn edit_task(&self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+    async fn edit_task(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
         // Access the current project and sprint using cursor indexes
         let current_proj = &self.projects[self.cursor.project.unwrap() as usize];
         let current_sprint = &current_proj.sprints[self.cursor.sprint.unwrap() as usize];
@@ -294,60 +642,493 @@ impl ProjectManager {
             current_task.status.clone(),
             current_task.description.clone(),
             current_task.estimated_hours.to_string(),
+            Task::priority_label(current_task.priority),
+            current_task
+                .due_date
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
         ];
 
+        let workflow = current_proj.workflow.clone();
+        let due_dates = Self::due_dates(current_sprint);
+
         // Create and run the dialog for editing task information
         match CreateRecordDialog::new_edit(
             vec![
                 "Title".into(),
-                "Status".into(),
+                Self::status_label(&workflow),
                 "Description".into(),
                 "Estimated Hours".into(),
+                PRIORITY_LABEL.into(),
+                Self::due_date_label(current_sprint),
             ],
             current_data,
-            |_d: &CreateRecordDialog| true,
+            move |d: &CreateRecordDialog| {
+                workflow.parse(&d.entries()[1]).is_some()
+                    && Task::parse_priority(&d.entries()[4]).is_some()
+                    && Self::is_valid_due_date(&d.entries()[5], due_dates)
+            },
         )
         .run(terminal)
         .await?
         {
-            CreateResults::Create(data) => {
-                // Extract updated data from dialog
-                let new_title = &data[0];
-                let new_status = &data[1];
-                let new_description = &data[2];
-                let new_estimated_hours = data[3]
-                    .parse::<i32>()
-                    .unwrap_or(current_task.estimated_hours); // Use existing value as fallback
-
-                // SQL statement to update the task
-                let update_query = "UPDATE Task SET Title = ?, Status = ?, Description = ?, estimatedHours = ? WHERE TaskID = ?";
-                let result = sqlx::query(update_query)
-                    .bind(new_title)
-                    .bind(new_status)
-                    .bind(new_description)
-                    .bind(new_estimated_hours)
-                    .bind(current_task.task_id)
-                    .execute(&self.pool)
+            CreateResults::Create(mut data) => {
+                let workflow = &current_proj.workflow;
+                // Validated by the dialog.
+                let new_status = workflow.parse(&data[1]).unwrap();
+                if !workflow.can_transition(&current_task.status, new_status) {
+                    return DisplayWindow::run(
+                        terminal,
+                        format!(
+                            "Tasks cannot move from {} to {} in this project.",
+                            current_task.status, new_status
+                        ),
+                    )
                     .await;
+                }
+                data[1] = new_status.to_string();
+                data[3] = data[3]
+                    .parse::<i32>()
+                    .unwrap_or(current_task.estimated_hours) // Use existing value as fallback
+                    .to_string();
+                // Validated by the dialog.
+                data[4] = Task::priority_label(Task::parse_priority(&data[4]).unwrap());
+                data[5] = data[5].trim().to_string();
+
+                let assignees =
+                    Self::pick_assignees(terminal, current_proj, &current_task.assignees).await?;
+                let labels = Self::pick_labels(terminal, current_proj, &current_task.labels).await?;
+
+                // What the update is based on, moved along to someone else's changes on a conflict.
+                let mut before = current_task.clone();
+                // The assignees and labels go in with the update, so either all of them are saved or none.
+                let redo = loop {
+                    // Extract updated data from dialog
+                    let mut redo = vec![Operation::UpdateTask {
+                        task_id: current_task.task_id,
+                        version: before.version,
+                        title: data[0].clone(),
+                        status: data[1].clone(),
+                        description: data[2].clone(),
+                        estimated_hours: data[3].parse::<i32>().unwrap_or(before.estimated_hours),
+                        priority: Task::parse_priority(&data[4]).unwrap_or(before.priority),
+                        due_date: Task::parse_due_date(&data[5]).unwrap_or(before.due_date),
+                    }];
+                    if let Some(assignees) = &assignees {
+                        redo.push(Operation::SetTaskAssignees {
+                            task_id: current_task.task_id,
+                            member_ids: assignees.clone(),
+                        });
+                    }
+                    if let Some(labels) = &labels {
+                        redo.push(Operation::SetTaskLabels {
+                            task_id: current_task.task_id,
+                            label_ids: labels.clone(),
+                        });
+                    }
+
+                    // Handle the result of the update operation
+                    match self.store.apply(&redo).await {
+                        Ok(()) => break redo,
+                        Err(StorageError::Conflict) => {
+                            let Some(theirs) = self
+                                .store
+                                .fetch_projects()
+                                .await?
+                                .into_iter()
+                                .flat_map(|p| p.sprints)
+                                .flat_map(|s| s.tasks)
+                                .find(|t| t.task_id == current_task.task_id)
+                            else {
+                                return DisplayWindow::run(
+                                    terminal,
+                                    "Someone else deleted the task in the meantime.".into(),
+                                )
+                                .await;
+                            };
+                            before = theirs;
+                            match ConflictDialog::run(
+                                terminal,
+                                &format!("Task '{}'", current_task.title),
+                                &[
+                                    "Title",
+                                    "Status",
+                                    "Description",
+                                    "Estimated Hours",
+                                    "Priority",
+                                    "Due Date",
+                                ],
+                                &data,
+                                &[
+                                    before.title.clone(),
+                                    before.status.clone(),
+                                    before.description.clone(),
+                                    before.estimated_hours.to_string(),
+                                    Task::priority_label(before.priority),
+                                    before.due_date.map(|d| d.to_string()).unwrap_or_default(),
+                                ],
+                            )
+                            .await?
+                            {
+                                Some(resolved) => data = resolved,
+                                None => return Ok(()),
+                            }
+                        }
+                        Err(StorageError::Invalid(reason)) => {
+                            return DisplayWindow::run(
+                                terminal,
+                                format!("Failed to update task: {}", reason),
+                            )
+                            .await;
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                };
+
+                let mut undo = vec![Operation::UpdateTask {
+                    task_id: current_task.task_id,
+                    version: before.version + 1,
+                    title: before.title,
+                    status: before.status,
+                    description: before.description,
+                    estimated_hours: before.estimated_hours,
+                    priority: before.priority,
+                    due_date: before.due_date,
+                }];
+                if assignees.is_some() {
+                    undo.push(Operation::SetTaskAssignees {
+                        task_id: current_task.task_id,
+                        member_ids: current_task.assignees.iter().map(|m| m.member_id).collect(),
+                    });
+                }
+                if labels.is_some() {
+                    undo.push(Operation::SetTaskLabels {
+                        task_id: current_task.task_id,
+                        label_ids: current_task.labels.iter().map(|l| l.label_id).collect(),
+                    });
+                }
+
+                self.undo.push(Edit {
+                    summary: format!("edit task '{}'", current_task.title),
+                    undo,
+                    redo,
+                });
+                Ok(())
+            }
+            CreateResults::Quit => Ok(()),
+        }
+    }
+
+    /// Label of the status field in the task forms, listing the statuses of the project.
+    fn status_label(workflow: &Workflow) -> String {
+        format!("Status ({})", workflow.status_names())
+    }
+
+    /// The first and last day tasks of a sprint can be due on. Tasks in the backlog cannot be due.
+    fn due_dates(sprint: &Sprint) -> Option<(NaiveDate, NaiveDate)> {
+        (!sprint.backlog).then_some((sprint.start_date, sprint.end_date))
+    }
+
+    /// Label of the due date field in the task forms, naming the days of the sprint.
+    fn due_date_label(sprint: &Sprint) -> String {
+        match Self::due_dates(sprint) {
+            Some((start_date, end_date)) => format!(
+                "Due Date (YYYY-MM-DD from {} to {}, optional)",
+                start_date, end_date
+            ),
+            None => "Due Date (leave empty, the backlog is not scheduled)".into(),
+        }
+    }
+
+    /// The due date a task keeps when it moves to `sprint`: the same day if it falls within
+    /// the sprint, none otherwise.
+    fn due_date_in(sprint: &Sprint, due_date: Option<NaiveDate>) -> Option<NaiveDate> {
+        let (start_date, end_date) = Self::due_dates(sprint)?;
+        due_date.filter(|due| (start_date..=end_date).contains(due))
+    }
+
+    /// How a sprint is headed in the project tree and when picking where tasks go.
+    fn sprint_heading(sprint: &Sprint) -> String {
+        if sprint.backlog {
+            format!("{} (not scheduled yet)", sprint.title)
+        } else {
+            format!(
+                "Sprint #{}: {} ({} to {})",
+                sprint.sprint_id, sprint.title, sprint.start_date, sprint.end_date
+            )
+        }
+    }
+
+    /// A sprint as offered when picking where tasks go.
+    fn sprint_option(sprint: &Sprint) -> (i32, String) {
+        (sprint.sprint_id, Self::sprint_heading(sprint))
+    }
+
+    /// Whether a due date typed into a task form is left empty or falls within `due_dates`,
+    /// the days tasks of the sprint can be due on.
+    fn is_valid_due_date(input: &str, due_dates: Option<(NaiveDate, NaiveDate)>) -> bool {
+        Task::parse_due_date(input).is_some_and(|due| {
+            due.is_none_or(|due| {
+                due_dates.is_some_and(|(start_date, end_date)| (start_date..=end_date).contains(&due))
+            })
+        })
+    }
+
+    /// Moves the selected task on to the next status its project's workflow allows.
+    async fn cycle_status(&mut self) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Task {
+            return Ok(());
+        }
+
+        if let (Some(project_idx), Some(sprint_idx), Some(task_idx)) =
+            (self.cursor.project, self.cursor.sprint, self.cursor.task)
+        {
+            let project = &self.projects[project_idx as usize];
+            let task = &project.sprints[sprint_idx as usize].tasks[task_idx as usize];
+            if let Some(next) = project.workflow.next(&task.status) {
+                self.store
+                    .update_task_status(task.task_id, task.version, next)
+                    .await?;
+                let with_status = |version: i32, status: &str| Operation::UpdateTask {
+                    task_id: task.task_id,
+                    version,
+                    title: task.title.clone(),
+                    status: status.into(),
+                    description: task.description.clone(),
+                    estimated_hours: task.estimated_hours,
+                    priority: task.priority,
+                    due_date: task.due_date,
+                };
+                self.undo.push(Edit {
+                    summary: format!("move task '{}' to {}", task.title, next),
+                    undo: vec![with_status(task.version + 1, &task.status)],
+                    redo: vec![with_status(task.version, next)],
+                });
+                self.fetch_projects().await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds an item to the checklist of the selected task.
+    async fn create_checklist_item(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+    ) -> std::io::Result<()> {
+        let Some((task, _)) = self.cursor.checklist_item(&self.projects) else {
+            return Ok(());
+        };
 
-                // Handle the result of the update operation
-                match result {
-                    Ok(_) => Ok(()),
-                    Err(_) => Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        "Failed to update task:",
+        match CreateRecordDialog::new(vec!["Title".into()], |d: &CreateRecordDialog| {
+            !d.entries()[0].trim().is_empty()
+        })
+        .run(terminal)
+        .await?
+        {
+            CreateResults::Create(data) => {
+                match self.store.create_checklist_item(task.task_id, &data[0]).await {
+                    Ok(item) => self.undo.push(Edit::created(
+                        format!("add checklist item '{}'", item.title),
+                        RecordKind::ChecklistItem,
+                        item.item_id,
                     )),
+                    Err(e) => {
+                        DisplayWindow::run(terminal, format!("Failed to add checklist item: {}", e))
+                            .await?
+                    }
+                }
+                self.fetch_projects().await;
+            }
+            CreateResults::Quit => {}
+        }
+
+        Ok(())
+    }
+
+    async fn edit_checklist_item(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+    ) -> std::io::Result<()> {
+        let Some((_, Some(item))) = self.cursor.checklist_item(&self.projects) else {
+            return Ok(());
+        };
+
+        match CreateRecordDialog::new_edit(
+            vec!["Title".into()],
+            vec![item.title.clone()],
+            |d: &CreateRecordDialog| !d.entries()[0].trim().is_empty(),
+        )
+        .run(terminal)
+        .await?
+        {
+            CreateResults::Create(mut data) => {
+                // What the update is based on, moved along to someone else's changes on a conflict.
+                let mut version = item.version;
+                let mut before = (item.title.clone(), item.done);
+
+                loop {
+                    match self
+                        .store
+                        .update_checklist_item(item.item_id, version, &data[0], before.1)
+                        .await
+                    {
+                        Ok(()) => {
+                            self.undo.push(Edit {
+                                summary: format!("edit checklist item '{}'", item.title),
+                                undo: vec![Operation::UpdateChecklistItem {
+                                    item_id: item.item_id,
+                                    version: version + 1,
+                                    title: before.0,
+                                    done: before.1,
+                                }],
+                                redo: vec![Operation::UpdateChecklistItem {
+                                    item_id: item.item_id,
+                                    version,
+                                    title: data[0].clone(),
+                                    done: before.1,
+                                }],
+                            });
+                            return Ok(());
+                        }
+                        Err(StorageError::Conflict) => {
+                            let Some(theirs) = self
+                                .store
+                                .fetch_projects()
+                                .await?
+                                .into_iter()
+                                .flat_map(|p| p.sprints)
+                                .flat_map(|s| s.tasks)
+                                .flat_map(|t| t.checklist)
+                                .find(|i| i.item_id == item.item_id)
+                            else {
+                                return DisplayWindow::run(
+                                    terminal,
+                                    "Someone else deleted the checklist item in the meantime.".into(),
+                                )
+                                .await;
+                            };
+                            version = theirs.version;
+                            before = (theirs.title, theirs.done);
+                            match ConflictDialog::run(
+                                terminal,
+                                &format!("Checklist item '{}'", item.title),
+                                &["Title"],
+                                &data,
+                                &[before.0.clone()],
+                            )
+                            .await?
+                            {
+                                Some(resolved) => data = resolved,
+                                None => return Ok(()),
+                            }
+                        }
+                        Err(e) => {
+                            return DisplayWindow::run(
+                                terminal,
+                                format!("Failed to update checklist item: {}", e),
+                            )
+                            .await;
+                        }
+                    }
                 }
             }
             CreateResults::Quit => Ok(()),
         }
     }
 
+    /// Ticks the selected checklist item off, or back on if it was done.
+    async fn toggle_checklist_item(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+    ) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Item {
+            return Ok(());
+        }
+        let Some((_, Some(item))) = self.cursor.checklist_item(&self.projects) else {
+            return Ok(());
+        };
+
+        match self
+            .store
+            .update_checklist_item(item.item_id, item.version, &item.title, !item.done)
+            .await
+        {
+            Ok(()) => {
+                let with_done = |version: i32, done: bool| Operation::UpdateChecklistItem {
+                    item_id: item.item_id,
+                    version,
+                    title: item.title.clone(),
+                    done,
+                };
+                self.undo.push(Edit {
+                    summary: format!(
+                        "mark checklist item '{}' as {}",
+                        item.title,
+                        if item.done { "not done" } else { "done" }
+                    ),
+                    undo: vec![with_done(item.version + 1, item.done)],
+                    redo: vec![with_done(item.version, !item.done)],
+                });
+            }
+            Err(e) => {
+                DisplayWindow::run(terminal, format!("Failed to update checklist item: {}", e))
+                    .await?
+            }
+        }
+        self.fetch_projects().await;
+
+        Ok(())
+    }
+
+    /// Lets the user pick the assignees of a task out of the members of `project`.
+    /// Returns `None` if the user backed out, in which case the assignees should be left alone.
+    async fn pick_assignees(
+        terminal: &mut Terminal<impl Backend>,
+        project: &Project,
+        current: &[Member],
+    ) -> std::io::Result<Option<Vec<i32>>> {
+        if project.members.is_empty() {
+            return Ok(None);
+        }
+
+        let options = project
+            .members
+            .iter()
+            .map(|m| (m.member_id, format!("{} {} - {}", m.first_name, m.last_name, m.email)))
+            .collect();
+        let picked = current.iter().map(|m| m.member_id).collect();
+
+        MultiPickerDialog::run(terminal, "Assign Members", options, picked).await
+    }
+
+    /// Lets the user pick the labels of a task out of those of `project`.
+    /// Returns `None` if the user backed out, in which case the labels should be left alone.
+    async fn pick_labels(
+        terminal: &mut Terminal<impl Backend>,
+        project: &Project,
+        current: &[Label],
+    ) -> std::io::Result<Option<Vec<i32>>> {
+        if project.labels.is_empty() {
+            return Ok(None);
+        }
+
+        let options = project
+            .labels
+            .iter()
+            .map(|l| (l.label_id, l.name.clone()))
+            .collect();
+        let picked = current.iter().map(|l| l.label_id).collect();
+
+        MultiPickerDialog::run(terminal, "Labels", options, picked).await
+    }
+
     async fn edit_entry(&mut self, term: &mut Terminal<impl Backend>) -> std::io::Result<()> {
         match self.cursor.depth {
             ProjectCursorDepth::Project => self.edit_project(term).await?,
             ProjectCursorDepth::Sprint => self.edit_sprint(term).await?,
             ProjectCursorDepth::Task => self.edit_task(term).await?,
+            ProjectCursorDepth::Item => self.edit_checklist_item(term).await?,
         }
 
         //Refresh after any possible updates.
@@ -358,19 +1139,35 @@ impl ProjectManager {
     async fn handle_key_press(
         &mut self,
         terminal: &mut Terminal<impl Backend>,
-        code: KeyCode,
+        key: KeyEvent,
     ) -> std::io::Result<RunResult> {
-        match code {
+        match key.code {
             KeyCode::Right => self.cursor.increase_depth(),
             KeyCode::Left => self.cursor.decrease_depth(),
             KeyCode::Down => self.cursor.next(),
             KeyCode::Up => self.cursor.prev(),
             KeyCode::Enter => self.create_project_or_sprint(terminal).await?,
-            KeyCode::Char('r') => return Ok(RunResult::Continue),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.redo(terminal).await?
+            }
+            KeyCode::Char('r') => self.refresh(true).await?,
+            KeyCode::Char('u') => self.undo(terminal).await?,
             KeyCode::Char('e') => self.edit_entry(terminal).await?,
             KeyCode::Char('c') => self.create_sprint_or_task(terminal).await?,
             KeyCode::Char('d') => self.delete_item(terminal).await,
             KeyCode::Char('m') => self.manage_members(terminal).await,
+            KeyCode::Char('w') => self.work_log(terminal).await?,
+            KeyCode::Char('n') => self.comments(terminal).await?,
+            KeyCode::Char('b') => self.edit_dependencies(terminal).await?,
+            KeyCode::Char('s') => self.cycle_status().await?,
+            KeyCode::Char('x') => self.toggle_checklist_item(terminal).await?,
+            KeyCode::Char('f') => self.edit_workflow(terminal).await?,
+            KeyCode::Char('l') => self.edit_labels(terminal).await?,
+            KeyCode::Char('o') => self.show_overdue(terminal).await?,
+            KeyCode::Char('v') => self.move_task(terminal).await?,
+            KeyCode::Char('k') => self.close_sprint(terminal).await?,
+            KeyCode::Char('/') => self.filter_by_labels(terminal).await?,
+            KeyCode::Char('h') => self.show_history(terminal).await?,
             KeyCode::Char('q') => return Ok(RunResult::Return),
             KeyCode::Esc => return Ok(RunResult::Return),
             _ => {}
@@ -382,13 +1179,22 @@ impl ProjectManager {
         &mut self,
         terminal: &mut Terminal<impl Backend>,
     ) -> std::io::Result<()> {
-        if self.cursor.depth == ProjectCursorDepth::Project {
-            if self.cursor.project == Some(self.projects.len() as u8) {
-                CreateProjectDialog::run(terminal, &self.pool).await?;
-                self.fetch_projects().await;
-
-                return Ok(());
+        if self.cursor.depth == ProjectCursorDepth::Project
+            && self.cursor.project == Some(self.projects.len() as u8)
+        {
+            let created = CreateProjectDialog::run(terminal, &self.store).await?;
+            self.fetch_projects().await;
+            if let Some(project) = created
+                .and_then(|project_id| self.projects.iter().find(|p| p.proj_id == project_id))
+            {
+                self.undo.push(Edit::created(
+                    format!("create project '{}'", project.title),
+                    RecordKind::Project,
+                    project.proj_id,
+                ));
             }
+
+            return Ok(());
         }
 
         Ok(())
@@ -401,9 +1207,17 @@ impl ProjectManager {
         match self.cursor.depth {
             ProjectCursorDepth::Project => {
                 if let Some(project_idx) = self.cursor.project {
-                    let proj_id = self.projects[project_idx as usize].proj_id;
                     if project_idx < self.projects.len() as u8 {
-                        CreateSprintDialog::run(terminal, &self.pool, proj_id).await?;
+                        let proj_id = self.projects[project_idx as usize].proj_id;
+                        if let Some(sprint) =
+                            CreateSprintDialog::run(terminal, &self.store, proj_id).await?
+                        {
+                            self.undo.push(Edit::created(
+                                format!("create sprint '{}'", sprint.title),
+                                RecordKind::Sprint,
+                                sprint.sprint_id,
+                            ));
+                        }
                         self.fetch_projects().await;
 
                         return Ok(());
@@ -416,15 +1230,36 @@ impl ProjectManager {
                         let sprint_id = self.projects[project_idx as usize].sprints
                             [sprint_idx as usize]
                             .sprint_id;
+                        let workflow = self.projects[project_idx as usize].workflow.clone();
+                        let sprint =
+                            &self.projects[project_idx as usize].sprints[sprint_idx as usize];
+                        let due_dates = Self::due_dates(sprint);
 
-                        match CreateRecordDialog::new(
+                        match CreateRecordDialog::new_edit(
                             vec![
                                 String::from("Title"),
-                                String::from("Status"),
+                                Self::status_label(&workflow),
                                 String::from("Description"),
                                 String::from("estimatedHours"),
+                                String::from(PRIORITY_LABEL),
+                                Self::due_date_label(sprint),
+                            ],
+                            vec![
+                                String::new(),
+                                workflow.initial().unwrap_or_default().to_string(),
+                                String::new(),
+                                String::new(),
+                                Task::priority_label(Task::DEFAULT_PRIORITY),
+                                String::new(),
                             ],
-                            |_diag: &CreateRecordDialog| true,
+                            {
+                                let workflow = workflow.clone();
+                                move |diag: &CreateRecordDialog| {
+                                    workflow.parse(&diag.entries()[1]).is_some()
+                                        && Task::parse_priority(&diag.entries()[4]).is_some()
+                                        && Self::is_valid_due_date(&diag.entries()[5], due_dates)
+                                }
+                            },
                         )
                         .run(terminal)
                         .await?
@@ -432,32 +1267,72 @@ impl ProjectManager {
                             CreateResults::Create(data) => {
                                 // Extract fields from the data vector
                                 let title = &data[0];
-                                let status = &data[1];
+                                let status = workflow.parse(&data[1]).unwrap(); // Validated by the dialog
                                 let description = &data[2];
                                 let estimated_hours = data[3].parse::<i32>().unwrap_or(0); // Default to 0 if parsing fails
+                                let priority = Task::parse_priority(&data[4]).unwrap(); // Validated by the dialog
+                                let due_date = Task::parse_due_date(&data[5]).unwrap(); // Validated by the dialog
 
-                                // SQL query to insert the new task
-                                let insert_query = "INSERT INTO Task (Title, Status, Description, commitedHours, estimatedHours) VALUES (?, ?, ?, ?, ?)";
-                                let task_row = sqlx::query(insert_query)
-                                    .bind(title)
-                                    .bind(status)
-                                    .bind(description)
-                                    .bind(0) // Setting commitedHours to 0 initially
-                                    .bind(estimated_hours)
-                                    .execute(&self.pool)
+                                let assignees = Self::pick_assignees(
+                                    terminal,
+                                    &self.projects[project_idx as usize],
+                                    &[],
+                                )
+                                .await?;
+                                let labels = Self::pick_labels(
+                                    terminal,
+                                    &self.projects[project_idx as usize],
+                                    &[],
+                                )
+                                .await?;
+
+                                let task_row = self
+                                    .store
+                                    .create_task(
+                                        sprint_id,
+                                        title,
+                                        status,
+                                        description,
+                                        estimated_hours,
+                                        priority,
+                                        due_date,
+                                    )
                                     .await;
                                 match task_row {
-                                    Ok(result) => {
-                                        let last_insert_id = result.last_insert_id();
-                                        let part_of_insert =
-                                            "INSERT INTO PartOf (TaskID, SprintID) VALUES (?, ?)";
-                                        sqlx::query(part_of_insert)
-                                            .bind(last_insert_id)
-                                            .bind(sprint_id)
-                                            .execute(&self.pool)
-                                            .await
-                                            .expect("Failed to link task with sprint in the PartOf table!");
-
+                                    Ok(task) => {
+                                        // The assignees and labels go on in one batch. If they cannot,
+                                        // the task is deleted again rather than left half created.
+                                        let mut operations = Vec::new();
+                                        if let Some(member_ids) = assignees {
+                                            operations.push(Operation::SetTaskAssignees {
+                                                task_id: task.task_id,
+                                                member_ids,
+                                            });
+                                        }
+                                        if let Some(label_ids) = labels {
+                                            operations.push(Operation::SetTaskLabels {
+                                                task_id: task.task_id,
+                                                label_ids,
+                                            });
+                                        }
+                                        match self.store.apply(&operations).await {
+                                            Ok(()) => self.undo.push(Edit::created(
+                                                format!("create task '{}'", task.title),
+                                                RecordKind::Task,
+                                                task.task_id,
+                                            )),
+                                            Err(e) => {
+                                                self.store.delete_task(task.task_id).await?;
+                                                DisplayWindow::run(
+                                                    terminal,
+                                                    format!(
+                                                        "An error occurred. Changes were not saved: {}",
+                                                        e
+                                                    ),
+                                                )
+                                                .await?;
+                                            }
+                                        }
                                         self.fetch_projects().await;
                                     }
                                     Err(e) => {
@@ -469,53 +1344,93 @@ impl ProjectManager {
                                                 e
                                             ),
                                         )
-                                        .await
-                                        .expect("Failed to show error screen.");
+                                        .await?;
 
                                         return Ok(());
                                     }
                                 }
                             }
-                            CreateResults::Quit => todo!(),
+                            CreateResults::Quit => {}
                         }
 
                         return Ok(());
                     }
                 }
             }
-            //You can't hit C on a task (theres nothing to create).
-            ProjectCursorDepth::Task => {}
+            // Tasks hold checklist items, created from the task or from one of its items alike.
+            ProjectCursorDepth::Task | ProjectCursorDepth::Item => {
+                return self.create_checklist_item(terminal).await;
+            }
         }
 
-        return Ok(());
+        Ok(())
     }
 
     async fn delete_item(&mut self, terminal: &mut Terminal<impl Backend>) {
         match self.cursor.depth {
             ProjectCursorDepth::Project => {
-                if let Some(project_idx) = self.cursor.project {
-                    if ConfirmDelete::run(terminal).await {
-                        delete_project_by_id(
-                            &self.pool,
-                            self.projects[project_idx as usize].proj_id,
-                        )
-                        .await
-                        .expect("Failed to delete project");
+                // Nothing to delete on the row creating a new project.
+                if let Some(project) = self
+                    .cursor
+                    .project
+                    .and_then(|project_idx| self.projects.get(project_idx as usize))
+                {
+                    let sprint_ids: Vec<i32> =
+                        project.sprints.iter().map(|s| s.sprint_id).collect();
+                    let task_count = self.count_tasks(&sprint_ids);
+                    let report = format!(
+                        "'{}' moves to the trash along with {} sprint(s) and {} task(s).",
+                        project.title,
+                        project.sprints.iter().filter(|s| !s.backlog).count(),
+                        task_count
+                    );
+
+                    if ConfirmDelete::run_with_report(terminal, Some(&report)).await {
+                        self.store
+                            .move_to_trash(RecordKind::Project, project.proj_id)
+                            .await
+                            .expect("Failed to delete project");
+                        self.undo.push(Edit::trashed(
+                            format!("delete project '{}'", project.title),
+                            RecordKind::Project,
+                            project.proj_id,
+                        ));
                         self.fetch_projects().await;
                     }
                 }
             }
             ProjectCursorDepth::Sprint => {
                 if let Some(project_idx) = self.cursor.project {
-                    if ConfirmDelete::run(terminal).await {
-                        delete_sprint_by_id(
-                            &self.pool,
-                            self.projects[project_idx as usize].sprints
-                                [self.cursor.sprint.unwrap() as usize]
-                                .sprint_id,
+                    let project = &self.projects[project_idx as usize];
+                    let sprint = &project.sprints[self.cursor.sprint.unwrap() as usize];
+                    if sprint.backlog {
+                        DisplayWindow::run(
+                            terminal,
+                            format!(
+                                "The backlog of '{}' cannot be deleted. Delete its tasks instead.",
+                                project.title
+                            ),
                         )
                         .await
-                        .expect("Failed to delete sprint!");
+                        .expect("Failed to show error screen.");
+                        return;
+                    }
+                    let task_count = self.count_tasks(&[sprint.sprint_id]);
+                    let report = format!(
+                        "'{}' moves to the trash along with {} task(s).",
+                        sprint.title, task_count
+                    );
+
+                    if ConfirmDelete::run_with_report(terminal, Some(&report)).await {
+                        self.store
+                            .move_to_trash(RecordKind::Sprint, sprint.sprint_id)
+                            .await
+                            .expect("Failed to delete sprint!");
+                        self.undo.push(Edit::trashed(
+                            format!("delete sprint '{}'", sprint.title),
+                            RecordKind::Sprint,
+                            sprint.sprint_id,
+                        ));
                         self.fetch_projects().await;
                     }
                 }
@@ -523,15 +1438,34 @@ impl ProjectManager {
             ProjectCursorDepth::Task => {
                 if let Some(project_idx) = self.cursor.project {
                     if ConfirmDelete::run(terminal).await {
-                        delete_task_by_id(
-                            &self.pool,
-                            self.projects[project_idx as usize].sprints
-                                [self.cursor.sprint.unwrap() as usize]
-                                .tasks[self.cursor.task.unwrap() as usize]
-                                .task_id,
-                        )
-                        .await
-                        .expect("Failed to delete sprint!");
+                        let task = &self.projects[project_idx as usize].sprints
+                            [self.cursor.sprint.unwrap() as usize]
+                            .tasks[self.cursor.task.unwrap() as usize];
+                        self.store
+                            .move_to_trash(RecordKind::Task, task.task_id)
+                            .await
+                            .expect("Failed to delete task!");
+                        self.undo.push(Edit::trashed(
+                            format!("delete task '{}'", task.title),
+                            RecordKind::Task,
+                            task.task_id,
+                        ));
+                        self.fetch_projects().await;
+                    }
+                }
+            }
+            ProjectCursorDepth::Item => {
+                if let Some((_, Some(item))) = self.cursor.checklist_item(&self.projects) {
+                    if ConfirmDelete::run(terminal).await {
+                        self.store
+                            .move_to_trash(RecordKind::ChecklistItem, item.item_id)
+                            .await
+                            .expect("Failed to delete checklist item!");
+                        self.undo.push(Edit::trashed(
+                            format!("delete checklist item '{}'", item.title),
+                            RecordKind::ChecklistItem,
+                            item.item_id,
+                        ));
                         self.fetch_projects().await;
                     }
                 }
@@ -539,100 +1473,677 @@ impl ProjectManager {
         }
     }
 
+    /// Counts the tasks of the given sprints, including those hidden by the label filter.
+    fn count_tasks(&self, sprint_ids: &[i32]) -> usize {
+        sprint_ids
+            .iter()
+            .filter_map(|sprint_id| self.task_counts.get(sprint_id))
+            .sum()
+    }
+
     async fn manage_members(&mut self, terminal: &mut Terminal<impl Backend>) {
         if let Some(project_idx) = self.cursor.project {
             ProjectMembersDialog::run(
                 terminal,
-                &self.pool,
+                &self.store,
                 self.projects[project_idx as usize].proj_id,
+                self.undo,
             )
             .await
             .expect("Error while managing project. Changes have not been saved.");
+            self.fetch_projects().await;
         }
     }
 
-    fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+    async fn work_log(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Task {
+            return Ok(());
+        }
+
+        if let (Some(project_idx), Some(sprint_idx), Some(task_idx)) =
+            (self.cursor.project, self.cursor.sprint, self.cursor.task)
+        {
+            let project = &self.projects[project_idx as usize];
+            let task = &project.sprints[sprint_idx as usize].tasks[task_idx as usize];
+            WorkLogDialog::run(terminal, &self.store, task, &project.members).await?;
+            self.fetch_projects().await;
+        }
+
+        Ok(())
+    }
+
+    async fn comments(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Task {
+            return Ok(());
+        }
+
+        if let (Some(project_idx), Some(sprint_idx), Some(task_idx)) =
+            (self.cursor.project, self.cursor.sprint, self.cursor.task)
+        {
+            let task = &self.projects[project_idx as usize].sprints[sprint_idx as usize].tasks
+                [task_idx as usize];
+            CommentDialog::run(terminal, &self.store, task).await?;
+            self.fetch_projects().await;
+        }
+
+        Ok(())
+    }
+
+    async fn edit_dependencies(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+    ) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Task {
+            return Ok(());
+        }
+
+        if let (Some(project_idx), Some(sprint_idx), Some(task_idx)) =
+            (self.cursor.project, self.cursor.sprint, self.cursor.task)
+        {
+            let project = &self.projects[project_idx as usize];
+            let task = &project.sprints[sprint_idx as usize].tasks[task_idx as usize];
+            DependencyDialog::run(
+                terminal,
+                &self.store,
+                project.proj_id,
+                task.task_id,
+                self.undo,
+            )
+            .await?;
+            self.fetch_projects().await;
+        }
+
+        Ok(())
+    }
+
+    async fn edit_workflow(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Project {
+            return Ok(());
+        }
+
+        if let Some(project) = self
+            .cursor
+            .project
+            .and_then(|project_idx| self.projects.get(project_idx as usize))
+        {
+            WorkflowDialog::run(terminal, &self.store, project).await?;
+            self.fetch_projects().await;
+        }
+
+        Ok(())
+    }
+
+    async fn edit_labels(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Project {
+            return Ok(());
+        }
+
+        if let Some(project) = self
+            .cursor
+            .project
+            .and_then(|project_idx| self.projects.get(project_idx as usize))
+        {
+            LabelDialog::run(terminal, &self.store, project).await?;
+            self.fetch_projects().await;
+        }
+
+        Ok(())
+    }
+
+    /// Lists the overdue tasks of the selected project, and moves the cursor to the one the user picks.
+    async fn show_overdue(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Project {
+            return Ok(());
+        }
+
+        let Some(project) = self
+            .cursor
+            .project
+            .and_then(|project_idx| self.projects.get(project_idx as usize))
+        else {
+            return Ok(());
+        };
+
+        let today = chrono::Local::now().date_naive();
+        let options: Vec<(i32, String)> = project
+            .overdue_tasks(today)
+            .into_iter()
+            .map(|(sprint, task)| {
+                let due_date = task.due_date.unwrap_or(today);
+                (
+                    task.task_id,
+                    format!(
+                        "Task #{} ({}): {} - {} {} | due {}, {} day(s) late | {}",
+                        task.task_id,
+                        Task::priority_label(task.priority),
+                        task.title,
+                        task.status,
+                        project.workflow.emoji(&task.status),
+                        due_date,
+                        (today - due_date).num_days(),
+                        sprint.title
+                    ),
+                )
+            })
+            .collect();
+        if options.is_empty() {
+            return DisplayWindow::run(
+                terminal,
+                format!("No task of '{}' is overdue.", project.title),
+            )
+            .await;
+        }
+
+        let title = format!("Overdue Tasks of '{}'", project.title);
+        let Some(task_id) = PickerDialog::run(terminal, &title, options).await? else {
+            return Ok(());
+        };
+
+        self.select_task(task_id);
+        Ok(())
+    }
+
+    /// Moves the cursor to the task with the provided ID, if the selected project shows it.
+    fn select_task(&mut self, task_id: i32) {
+        let Some(project) = self
+            .cursor
+            .project
+            .and_then(|project_idx| self.projects.get(project_idx as usize))
+        else {
+            return;
+        };
+
+        if let Some((sprint_idx, task_idx)) =
+            project.sprints.iter().enumerate().find_map(|(s, sprint)| {
+                sprint
+                    .tasks
+                    .iter()
+                    .position(|t| t.task_id == task_id)
+                    .map(|t| (s, t))
+            })
+        {
+            self.cursor.depth = ProjectCursorDepth::Task;
+            self.cursor.sprint = Some(sprint_idx as u8);
+            self.cursor.task = Some(task_idx as u8);
+            self.cursor.item = None;
+        }
+    }
+
+    /// Lets the user pick another sprint of the project, or its backlog, and moves the selected task there.
+    /// The task keeps its due date only if the day falls within the new sprint.
+    async fn move_task(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Task {
+            return Ok(());
+        }
+
+        let (Some(project_idx), Some(sprint_idx), Some(task_idx)) =
+            (self.cursor.project, self.cursor.sprint, self.cursor.task)
+        else {
+            return Ok(());
+        };
+        let project = &self.projects[project_idx as usize];
+        let sprint = &project.sprints[sprint_idx as usize];
+        let task = &sprint.tasks[task_idx as usize];
+
+        let options: Vec<(i32, String)> = project
+            .sprints
+            .iter()
+            .filter(|s| s.sprint_id != sprint.sprint_id)
+            .map(Self::sprint_option)
+            .collect();
+        if options.is_empty() {
+            return DisplayWindow::run(
+                terminal,
+                format!("'{}' has no other sprint to move '{}' to.", project.title, task.title),
+            )
+            .await;
+        }
+
+        let title = format!("Move '{}' to", task.title);
+        let Some(target) = PickerDialog::run(terminal, &title, options)
+            .await?
+            .and_then(|sprint_id| project.sprints.iter().find(|s| s.sprint_id == sprint_id))
+        else {
+            return Ok(());
+        };
+
+        let due_date = Self::due_date_in(target, task.due_date);
+        let mut message = None;
+        match self
+            .store
+            .move_task(task.task_id, task.version, target.sprint_id, due_date)
+            .await
+        {
+            Ok(()) => {
+                let moved = |version: i32, sprint_id: i32, due_date: Option<NaiveDate>| {
+                    Operation::MoveTask {
+                        task_id: task.task_id,
+                        version,
+                        sprint_id,
+                        due_date,
+                    }
+                };
+                self.undo.push(Edit {
+                    summary: format!("move task '{}' to '{}'", task.title, target.title),
+                    undo: vec![moved(task.version + 1, sprint.sprint_id, task.due_date)],
+                    redo: vec![moved(task.version, target.sprint_id, due_date)],
+                });
+                if let (Some(old), None) = (task.due_date, due_date) {
+                    message = Some(if target.backlog {
+                        format!(
+                            "'{}' is no longer due on {}, as the backlog is not scheduled.",
+                            task.title, old
+                        )
+                    } else {
+                        format!(
+                            "'{}' is no longer due on {}, which lies outside '{}'.",
+                            task.title, old, target.title
+                        )
+                    });
+                }
+            }
+            Err(e) => message = Some(format!("Failed to move task: {}", e)),
+        }
+
+        let task_id = task.task_id;
+        self.fetch_projects().await;
+        self.select_task(task_id);
+        if let Some(message) = message {
+            DisplayWindow::run(terminal, message).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Closes the selected sprint by offering to carry its unfinished tasks over to a later sprint
+    /// of the project, the next one first. Either every task moves or none does.
+    async fn close_sprint(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if self.cursor.depth != ProjectCursorDepth::Sprint {
+            return Ok(());
+        }
+
+        let (Some(project_idx), Some(sprint_idx)) = (self.cursor.project, self.cursor.sprint) else {
+            return Ok(());
+        };
+        let project = &self.projects[project_idx as usize];
+        let Some(sprint) = project.sprints.get(sprint_idx as usize) else {
+            return Ok(());
+        };
+        if sprint.backlog {
+            return DisplayWindow::run(
+                terminal,
+                format!(
+                    "The backlog of '{}' stays open. Move its tasks into sprints with <V> instead.",
+                    project.title
+                ),
+            )
+            .await;
+        }
+
+        // The label filter may hide some of the sprint's tasks, so they are looked up again.
+        let unfinished: Vec<Task> = self
+            .store
+            .fetch_projects()
+            .await?
+            .into_iter()
+            .flat_map(|p| p.sprints)
+            .find(|s| s.sprint_id == sprint.sprint_id)
+            .map(|s| s.tasks)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|t| !project.workflow.is_done(&t.status))
+            .collect();
+        if unfinished.is_empty() {
+            return DisplayWindow::run(
+                terminal,
+                format!("Every task of '{}' is done, there is nothing to carry over.", sprint.title),
+            )
+            .await;
+        }
+
+        let mut later: Vec<&Sprint> = project
+            .sprints
+            .iter()
+            .filter(|s| {
+                !s.backlog && (s.start_date, s.sprint_id) > (sprint.start_date, sprint.sprint_id)
+            })
+            .collect();
+        later.sort_by_key(|s| (s.start_date, s.sprint_id));
+        if later.is_empty() {
+            return DisplayWindow::run(
+                terminal,
+                format!(
+                    "'{}' is the last sprint of '{}'. Create the next sprint to carry its unfinished tasks over to.",
+                    sprint.title, project.title
+                ),
+            )
+            .await;
+        }
+
+        let title = format!(
+            "Close '{}': Carry {} Unfinished Task(s) Over To",
+            sprint.title,
+            unfinished.len()
+        );
+        let options = later.iter().map(|s| Self::sprint_option(s)).collect();
+        let Some(target) = PickerDialog::run(terminal, &title, options)
+            .await?
+            .and_then(|sprint_id| later.iter().find(|s| s.sprint_id == sprint_id))
+        else {
+            return Ok(());
+        };
+
+        let mut redo = Vec::new();
+        let mut undo = Vec::new();
+        for task in &unfinished {
+            redo.push(Operation::MoveTask {
+                task_id: task.task_id,
+                version: task.version,
+                sprint_id: target.sprint_id,
+                due_date: Self::due_date_in(target, task.due_date),
+            });
+            undo.push(Operation::MoveTask {
+                task_id: task.task_id,
+                version: task.version + 1,
+                sprint_id: sprint.sprint_id,
+                due_date: task.due_date,
+            });
+        }
+
+        let message = match self.store.apply(&redo).await {
+            Ok(()) => {
+                self.undo.push(Edit {
+                    summary: format!(
+                        "carry {} task(s) of '{}' over to '{}'",
+                        unfinished.len(),
+                        sprint.title,
+                        target.title
+                    ),
+                    undo,
+                    redo,
+                });
+                let cleared = unfinished
+                    .iter()
+                    .filter(|t| {
+                        t.due_date.is_some() && Self::due_date_in(target, t.due_date).is_none()
+                    })
+                    .count();
+                (cleared > 0).then(|| {
+                    format!(
+                        "{} carried task(s) are no longer due, as their due dates lie outside '{}'.",
+                        cleared, target.title
+                    )
+                })
+            }
+            Err(e) => Some(format!("Failed to carry tasks over: {}", e)),
+        };
+
+        self.fetch_projects().await;
+        if let Some(message) = message {
+            DisplayWindow::run(terminal, message).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Lets the user pick the labels tasks have to carry to be shown, out of the label names of every project.
+    async fn filter_by_labels(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        let mut names: Vec<String> = self
+            .projects
+            .iter()
+            .flat_map(|p| &p.labels)
+            .map(|l| l.name.clone())
+            .collect();
+        names.sort();
+        names.dedup();
+        if names.is_empty() {
+            return DisplayWindow::run(terminal, "No project defines any labels yet.".into()).await;
+        }
+
+        // Names are identified by their position in the sorted list.
+        let options = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (idx as i32, name.clone()))
+            .collect();
+        let picked = names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| self.label_filter.contains(name))
+            .map(|(idx, _)| idx as i32)
+            .collect();
+
+        if let Some(picked) =
+            MultiPickerDialog::run(terminal, "Show Tasks Labeled", options, picked).await?
+        {
+            self.label_filter = picked.into_iter().map(|idx| names[idx as usize].clone()).collect();
+            self.refresh(true).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Takes back the latest edit of this session.
+    async fn undo(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if let Some((summary, Err(e))) = self.undo.undo(&self.store).await {
+            DisplayWindow::run(terminal, format!("Could not undo '{}': {}", summary, e)).await?;
+        }
+        self.fetch_projects().await;
+        Ok(())
+    }
+
+    /// Makes the latest undone edit of this session again.
+    async fn redo(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        if let Some((summary, Err(e))) = self.undo.redo(&self.store).await {
+            DisplayWindow::run(terminal, format!("Could not redo '{}': {}", summary, e)).await?;
+        }
+        self.fetch_projects().await;
+        Ok(())
+    }
+
+    /// Shows the recorded changes to the selected project, sprint, task or checklist item.
+    async fn show_history(&self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        let Some(project) = self
+            .cursor
+            .project
+            .and_then(|project_idx| self.projects.get(project_idx as usize))
+        else {
+            return Ok(());
+        };
+        let sprint = self
+            .cursor
+            .sprint
+            .and_then(|sprint_idx| project.sprints.get(sprint_idx as usize));
+        let task = self
+            .cursor
+            .task
+            .and_then(|task_idx| sprint?.tasks.get(task_idx as usize));
+
+        let (kind, id, title) = match (self.cursor.depth, sprint, task) {
+            (ProjectCursorDepth::Project, _, _) => {
+                (RecordKind::Project, project.proj_id, &project.title)
+            }
+            (ProjectCursorDepth::Sprint, Some(sprint), _) => {
+                (RecordKind::Sprint, sprint.sprint_id, &sprint.title)
+            }
+            (ProjectCursorDepth::Task, _, Some(task)) => (RecordKind::Task, task.task_id, &task.title),
+            (ProjectCursorDepth::Item, _, Some(task)) => {
+                match self.cursor.item.and_then(|item_idx| task.checklist.get(item_idx as usize)) {
+                    Some(item) => (RecordKind::ChecklistItem, item.item_id, &item.title),
+                    None => return Ok(()),
+                }
+            }
+            _ => return Ok(()),
+        };
+        HistoryDialog::run(terminal, &self.store, kind, id, title).await
+    }
+
+    fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
         terminal.draw(|f| f.render_widget(self, f.size()))?;
         Ok(())
     }
 
-    fn project_lines(&self) -> Vec<Span> {
+    fn project_lines(&self) -> Vec<Line<'_>> {
         let mut lines = Vec::new();
         let selected_style = Style::default()
             .fg(Color::White)
             .add_modifier(Modifier::BOLD);
 
         let pc = &self.cursor;
+        let today = chrono::Local::now().date_naive();
 
         for (project_index, project) in self.projects.iter().enumerate() {
             let project_is_selected = pc.project == Some(project_index as u8);
 
             let project_span = if project_is_selected {
                 Span::styled(
-                    format!("◆ Project #{}: {}", project.proj_id, project.title),
+                    format!(
+                        "◆ Project #{}: {} ({} members)",
+                        project.proj_id,
+                        project.title,
+                        project.members.len()
+                    ),
                     selected_style,
                 )
             } else {
-                Span::raw(format!("  Project #{}: {}", project.proj_id, project.title))
+                Span::raw(format!(
+                    "  Project #{}: {} ({} members)",
+                    project.proj_id,
+                    project.title,
+                    project.members.len()
+                ))
             };
-            lines.push(project_span);
+            lines.push(Line::from(project_span));
 
-            if project_is_selected
-                && (pc.depth == ProjectCursorDepth::Sprint || pc.depth == ProjectCursorDepth::Task)
-            {
+            if project_is_selected && pc.depth >= ProjectCursorDepth::Sprint {
                 for (sprint_index, sprint) in project.sprints.iter().enumerate() {
                     let sprint_is_selected = pc.sprint == Some(sprint_index as u8);
 
                     let sprint_span = if sprint_is_selected {
                         Span::styled(
-                            format!(
-                                "  ◆ Sprint #{}: {} ({} to {})",
-                                sprint.sprint_id, sprint.title, sprint.start_date, sprint.end_date
-                            ),
+                            format!("  ◆ {}", Self::sprint_heading(sprint)),
                             selected_style,
                         )
                     } else {
-                        Span::raw(format!(
-                            "    Sprint #{}: {} ({} to {})",
-                            sprint.sprint_id, sprint.title, sprint.start_date, sprint.end_date
-                        ))
+                        Span::raw(format!("    {}", Self::sprint_heading(sprint)))
                     };
-                    lines.push(sprint_span);
+                    lines.push(Line::from(sprint_span));
 
-                    if pc.depth == ProjectCursorDepth::Task && sprint_is_selected {
+                    if pc.depth >= ProjectCursorDepth::Task && sprint_is_selected {
                         for (task_index, task) in sprint.tasks.iter().enumerate() {
                             let task_is_selected = pc.task == Some(task_index as u8);
-                            let emoji = match task.status.as_str() {
-                                "NotStarted" => "⏳",
-                                "InProgress" => "🚧",
-                                "Completed" => "✅",
-                                _ => "❓",
+                            let emoji = project.workflow.emoji(&task.status);
+                            let checklist = if task.checklist.is_empty() {
+                                String::new()
+                            } else {
+                                format!(
+                                    " | ☑ {}/{} done",
+                                    task.checklist_done(),
+                                    task.checklist.len()
+                                )
+                            };
+                            let assignees = if task.assignees.is_empty() {
+                                String::new()
+                            } else {
+                                let names: Vec<String> = task
+                                    .assignees
+                                    .iter()
+                                    .map(|m| format!("{} {}", m.first_name, m.last_name))
+                                    .collect();
+                                format!(" | 👤 {}", names.join(", "))
+                            };
+                            let overdue = project.is_overdue(task, today);
+                            let due_soon = project.is_due_soon(task, today);
+                            let due = match task.due_date {
+                                Some(due_date) if overdue => format!(" | due {} (overdue)", due_date),
+                                Some(due_date) if due_soon => format!(" | due {} (soon)", due_date),
+                                Some(due_date) => format!(" | due {}", due_date),
+                                None => String::new(),
+                            };
+                            let blockers = match self.blockers.get(&task.task_id) {
+                                Some(ids) if !ids.is_empty() => {
+                                    let ids: Vec<String> =
+                                        ids.iter().map(|id| format!("#{}", id)).collect();
+                                    format!(" | ⛔ blocked by {}", ids.join(", "))
+                                }
+                                _ => String::new(),
                             };
                             let task_span = if task_is_selected {
                                 Span::styled(
                                     format!(
-                                        "    ◆ Task #{}: {} - {} {} | {}h estimated, {}h completed",
+                                        "    ◆ Task #{} ({}): {} - {} {} | {}h estimated, {}h completed{}{}{}{}",
                                         task.task_id,
+                                        Task::priority_label(task.priority),
                                         task.title,
                                         task.status,
                                         emoji,
                                         task.estimated_hours,
-                                        task.commited_hours
+                                        task.commited_hours,
+                                        due,
+                                        checklist,
+                                        assignees,
+                                        blockers
                                     ),
                                     selected_style,
                                 )
                             } else {
-                                Span::raw(format!(
-                                    "      Task #{}: {} - {} {} | {}h estimated, {}h completed",
+                                let text = format!(
+                                    "      Task #{} ({}): {} - {} {} | {}h estimated, {}h completed{}{}{}{}",
                                     task.task_id,
+                                    Task::priority_label(task.priority),
                                     task.title,
                                     task.status,
                                     emoji,
                                     task.estimated_hours,
-                                    task.commited_hours
-                                ))
+                                    task.commited_hours,
+                                    due,
+                                    checklist,
+                                    assignees,
+                                    blockers
+                                );
+                                if !blockers.is_empty() {
+                                    Span::styled(text, Style::default().fg(Color::Red))
+                                } else if overdue {
+                                    Span::styled(
+                                        text,
+                                        Style::default()
+                                            .fg(Color::LightRed)
+                                            .add_modifier(Modifier::BOLD),
+                                    )
+                                } else if due_soon {
+                                    Span::styled(text, Style::default().fg(Color::Yellow))
+                                } else if task.is_high_priority() {
+                                    let style = Style::default().fg(Color::LightMagenta);
+                                    if task.priority == 0 {
+                                        Span::styled(text, style.add_modifier(Modifier::BOLD))
+                                    } else {
+                                        Span::styled(text, style)
+                                    }
+                                } else {
+                                    Span::raw(text)
+                                }
                             };
-                            lines.push(task_span);
+                            let mut task_line = vec![task_span];
+                            for label in &task.labels {
+                                task_line.push(Span::raw(" "));
+                                task_line.push(label_span(label));
+                            }
+                            lines.push(Line::from(task_line));
+
+                            if pc.depth == ProjectCursorDepth::Item && task_is_selected {
+                                for (item_index, item) in task.checklist.iter().enumerate() {
+                                    let check = if item.done { "[x]" } else { "[ ]" };
+                                    let item_span = if pc.item == Some(item_index as u8) {
+                                        Span::styled(
+                                            format!("      ◆ {} {}", check, item.title),
+                                            selected_style,
+                                        )
+                                    } else {
+                                        Span::raw(format!("        {} {}", check, item.title))
+                                    };
+                                    lines.push(Line::from(item_span));
+                                }
+                            }
                         }
                     }
                 }
@@ -641,7 +2152,7 @@ impl ProjectManager {
 
         let at_project_level = matches!(self.cursor.depth, ProjectCursorDepth::Project);
         if at_project_level {
-            lines.push(Span::styled(
+            lines.push(Line::styled(
                 " Create New Project +",
                 Style::default().fg(Color::Green).add_modifier(
                     if pc.project == Some(lines.len() as u8) {
@@ -657,11 +2168,13 @@ impl ProjectManager {
     }
 }
 
-impl Widget for &mut ProjectManager {
+impl<S: Storage> Widget for &mut ProjectManager<'_, S> {
     fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
         let mut instruction_spans = Vec::new();
         let mut create_text = None;
         let mut show_instructions = true;
+        // The backlog cannot be edited, deleted or closed.
+        let mut on_backlog = false;
 
         match self.cursor.depth {
             ProjectCursorDepth::Project => {
@@ -689,6 +2202,7 @@ impl Widget for &mut ProjectManager {
                             let sprint =
                                 &self.projects[project_idx as usize].sprints[sprint_idx as usize];
                             create_text = Some(format!(" Create Task for '{}' ", sprint.title));
+                            on_backlog = sprint.backlog;
                         } else {
                             show_instructions = false;
                         }
@@ -699,8 +2213,10 @@ impl Widget for &mut ProjectManager {
                     show_instructions = false;
                 }
             }
-            ProjectCursorDepth::Task => {
-                show_instructions = true;
+            ProjectCursorDepth::Task | ProjectCursorDepth::Item => {
+                if let Some((task, _)) = self.cursor.checklist_item(&self.projects) {
+                    create_text = Some(format!(" Add Checklist Item to '{}' ", task.title));
+                }
             }
         }
 
@@ -713,14 +2229,16 @@ impl Widget for &mut ProjectManager {
                 ));
             }
 
-            instruction_spans.extend(vec![
-                Span::raw("Edit "),
-                Span::styled("<E> ", Style::default().fg(Color::Rgb(255, 165, 0))),
-                Span::raw("Delete "),
-                Span::styled("<D> ", Style::default().fg(Color::Rgb(255, 165, 0))),
-            ]);
+            if !on_backlog {
+                instruction_spans.extend(vec![
+                    Span::raw("Edit "),
+                    Span::styled("<E> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                    Span::raw("Delete "),
+                    Span::styled("<D> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                ]);
+            }
 
-            if self.cursor.depth != ProjectCursorDepth::Sprint {
+            if matches!(self.cursor.depth, ProjectCursorDepth::Project | ProjectCursorDepth::Task) {
                 instruction_spans.push(Span::raw("Manage Members "));
                 instruction_spans.push(Span::styled(
                     "<M> ",
@@ -728,16 +2246,114 @@ impl Widget for &mut ProjectManager {
                 ));
             }
 
+            if self.cursor.depth == ProjectCursorDepth::Project {
+                instruction_spans.push(Span::raw("Workflow "));
+                instruction_spans.push(Span::styled(
+                    "<F> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+                instruction_spans.push(Span::raw("Labels "));
+                instruction_spans.push(Span::styled(
+                    "<L> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+                instruction_spans.push(Span::raw("Overdue "));
+                instruction_spans.push(Span::styled(
+                    "<O> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+            }
+
+            if self.cursor.depth == ProjectCursorDepth::Sprint && !on_backlog {
+                instruction_spans.push(Span::raw("Close Sprint "));
+                instruction_spans.push(Span::styled(
+                    "<K> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+            }
+
+            if self.cursor.depth == ProjectCursorDepth::Task {
+                instruction_spans.push(Span::raw("Cycle Status "));
+                instruction_spans.push(Span::styled(
+                    "<S> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+                instruction_spans.push(Span::raw("Work Log "));
+                instruction_spans.push(Span::styled(
+                    "<W> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+                instruction_spans.push(Span::raw("Comments "));
+                instruction_spans.push(Span::styled(
+                    "<N> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+                instruction_spans.push(Span::raw("Dependencies "));
+                instruction_spans.push(Span::styled(
+                    "<B> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+                instruction_spans.push(Span::raw("Move "));
+                instruction_spans.push(Span::styled(
+                    "<V> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+            }
+
+            if self.cursor.depth == ProjectCursorDepth::Item {
+                instruction_spans.push(Span::raw("Toggle Done "));
+                instruction_spans.push(Span::styled(
+                    "<X> ",
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                ));
+            }
+
+            instruction_spans.push(Span::raw("Filter "));
+            instruction_spans.push(Span::styled(
+                "</> ",
+                Style::default().fg(Color::Rgb(255, 165, 0)),
+            ));
+            instruction_spans.push(Span::raw("History "));
+            instruction_spans.push(Span::styled(
+                "<H> ",
+                Style::default().fg(Color::Rgb(255, 165, 0)),
+            ));
+            instruction_spans.push(Span::raw("Undo "));
+            instruction_spans.push(Span::styled(
+                "<U> ",
+                Style::default().fg(Color::Rgb(255, 165, 0)),
+            ));
+            instruction_spans.push(Span::raw("Redo "));
+            instruction_spans.push(Span::styled(
+                "<Ctrl-R> ",
+                Style::default().fg(Color::Rgb(255, 165, 0)),
+            ));
+
             instruction_spans.extend(vec![
-                Span::raw("Return "),
+                Span::raw("Refresh "),
                 Span::styled("<R> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Return "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
             ]);
         }
 
         let instructions = Title::from(Line::from(instruction_spans));
 
+        let mut notes = Vec::new();
+        if !self.label_filter.is_empty() {
+            notes.push(format!("labeled {}", self.label_filter.join(" + ")));
+        }
+        if !self.changed_elsewhere.is_empty() {
+            notes.push(format!("changed elsewhere: {}", self.changed_elsewhere.join(", ")));
+        }
+        let title = if notes.is_empty() {
+            "Projects".to_string()
+        } else {
+            format!("Projects ({})", notes.join("; "))
+        };
+
         let proj_block = Block::default()
-            .title("Projects")
+            .title(title)
             .title(
                 instructions
                     .alignment(ratatui::layout::Alignment::Center)
@@ -746,12 +2362,7 @@ impl Widget for &mut ProjectManager {
             .borders(Borders::ALL)
             .title_alignment(ratatui::layout::Alignment::Center);
 
-        let proj_lines: Vec<Line> = self
-            .project_lines()
-            .into_iter()
-            .map(|span| Line::from(span))
-            .collect();
-        let proj_list = List::new(proj_lines).block(proj_block).highlight_style(
+        let proj_list = List::new(self.project_lines()).block(proj_block).highlight_style(
             Style::default()
                 .fg(Color::Black)
                 .bg(Color::White)
@@ -761,3 +2372,267 @@ impl Widget for &mut ProjectManager {
         proj_list.render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+    use crate::memory::MemoryStore;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    /// A store with one project, whose sprint holds a task with two checklist items.
+    /// The backlog of the project comes before the sprint.
+    async fn sample_store() -> MemoryStore {
+        let store = MemoryStore::default();
+        let project_id = store.create_project("Demo", "").await.unwrap();
+        let sprint = store
+            .create_sprint(project_id, "S1", date(1), date(14))
+            .await
+            .unwrap();
+        let task = store
+            .create_task(sprint.sprint_id, "T1", "NotStarted", "", 3, 2, None)
+            .await
+            .unwrap();
+        store
+            .create_checklist_item(task.task_id, "First")
+            .await
+            .unwrap();
+        store
+            .create_checklist_item(task.task_id, "Second")
+            .await
+            .unwrap();
+        store
+    }
+
+    async fn press<S: Storage>(
+        mgr: &mut ProjectManager<'_, S>,
+        terminal: &mut Terminal<TestBackend>,
+        code: KeyCode,
+    ) {
+        mgr.handle_key_press(terminal, KeyEvent::from(code))
+            .await
+            .unwrap();
+    }
+
+    fn selected_item<S: Storage>(mgr: &ProjectManager<'_, S>) -> Option<String> {
+        match mgr.cursor.checklist_item(&mgr.projects) {
+            Some((_, Some(item))) => Some(item.title.clone()),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn cursor_moves_through_every_level() {
+        let mut undo = UndoStack::default();
+        let mut mgr = ProjectManager::new(sample_store().await, &mut undo);
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        mgr.fetch_projects().await;
+
+        press(&mut mgr, &mut terminal, KeyCode::Up).await;
+        assert_eq!(mgr.cursor, ProjectCursor::default());
+
+        press(&mut mgr, &mut terminal, KeyCode::Right).await;
+        assert_eq!(mgr.cursor.depth, ProjectCursorDepth::Sprint);
+        assert!(mgr.projects[0].sprints[0].backlog);
+        press(&mut mgr, &mut terminal, KeyCode::Down).await;
+        assert_eq!(mgr.cursor.sprint, Some(1));
+
+        press(&mut mgr, &mut terminal, KeyCode::Right).await;
+        assert_eq!(mgr.cursor.depth, ProjectCursorDepth::Task);
+        assert_eq!(mgr.cursor.task, Some(0));
+
+        press(&mut mgr, &mut terminal, KeyCode::Right).await;
+        assert_eq!(mgr.cursor.depth, ProjectCursorDepth::Item);
+        assert_eq!(selected_item(&mgr).as_deref(), Some("First"));
+        press(&mut mgr, &mut terminal, KeyCode::Down).await;
+        assert_eq!(selected_item(&mgr).as_deref(), Some("Second"));
+        press(&mut mgr, &mut terminal, KeyCode::Right).await;
+        assert_eq!(mgr.cursor.depth, ProjectCursorDepth::Item);
+        press(&mut mgr, &mut terminal, KeyCode::Up).await;
+        press(&mut mgr, &mut terminal, KeyCode::Up).await;
+        assert_eq!(selected_item(&mgr).as_deref(), Some("First"));
+
+        press(&mut mgr, &mut terminal, KeyCode::Left).await;
+        assert_eq!(mgr.cursor.depth, ProjectCursorDepth::Task);
+        assert_eq!(mgr.cursor.item, None);
+        press(&mut mgr, &mut terminal, KeyCode::Left).await;
+        assert_eq!(mgr.cursor.depth, ProjectCursorDepth::Sprint);
+        assert_eq!(mgr.cursor.task, None);
+        press(&mut mgr, &mut terminal, KeyCode::Left).await;
+        press(&mut mgr, &mut terminal, KeyCode::Left).await;
+        assert_eq!(mgr.cursor, ProjectCursor::default());
+
+        // Below the last project is the row creating a new one.
+        press(&mut mgr, &mut terminal, KeyCode::Down).await;
+        assert_eq!(mgr.cursor.project, Some(mgr.projects.len() as u8));
+    }
+
+    #[tokio::test]
+    async fn edits_through_the_store_show_up_in_the_tree() {
+        let store = MemoryStore::default();
+        let mut undo = UndoStack::default();
+        let mut mgr = ProjectManager::new(store.clone(), &mut undo);
+
+        let project_id = mgr.store.create_project("Demo", "").await.unwrap();
+        let sprint = mgr
+            .store
+            .create_sprint(project_id, "S1", date(1), date(14))
+            .await
+            .unwrap();
+        let task = mgr
+            .store
+            .create_task(
+                sprint.sprint_id,
+                "T1",
+                "NotStarted",
+                "",
+                3,
+                2,
+                Some(date(7)),
+            )
+            .await
+            .unwrap();
+        mgr.fetch_projects().await;
+        let tasks = &mgr.projects[0].sprints[1].tasks;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].due_date, Some(date(7)));
+
+        mgr.store
+            .update_task(
+                task.task_id,
+                task.version,
+                "Renamed",
+                "InProgress",
+                "",
+                5,
+                0,
+                None,
+            )
+            .await
+            .unwrap();
+        mgr.fetch_projects().await;
+        let updated = &mgr.projects[0].sprints[1].tasks[0];
+        assert_eq!(
+            (updated.title.as_str(), updated.status.as_str()),
+            ("Renamed", "InProgress")
+        );
+        assert_eq!(updated.version, task.version + 1);
+
+        // The edit was based on the version before the update, so it is refused.
+        let stale = mgr
+            .store
+            .update_task(
+                task.task_id,
+                task.version,
+                "Stale",
+                "InProgress",
+                "",
+                5,
+                0,
+                None,
+            )
+            .await;
+        assert!(matches!(stale, Err(StorageError::Conflict)));
+
+        let item = mgr
+            .store
+            .create_checklist_item(task.task_id, "Item")
+            .await
+            .unwrap();
+        mgr.store
+            .move_to_trash(RecordKind::ChecklistItem, item.item_id)
+            .await
+            .unwrap();
+        mgr.store
+            .move_to_trash(RecordKind::Task, task.task_id)
+            .await
+            .unwrap();
+        mgr.fetch_projects().await;
+        assert!(mgr.projects[0].sprints[1].tasks.is_empty());
+
+        mgr.store.delete_sprint(sprint.sprint_id).await.unwrap();
+        mgr.fetch_projects().await;
+        assert_eq!(mgr.projects[0].sprints.len(), 1);
+        assert!(matches!(
+            mgr.store
+                .delete_sprint(mgr.projects[0].sprints[0].sprint_id)
+                .await,
+            Err(StorageError::Invalid(_))
+        ));
+
+        mgr.store.delete_project(project_id).await.unwrap();
+        mgr.fetch_projects().await;
+        assert!(mgr.projects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tasks_hidden_by_the_label_filter_still_count() {
+        let mut undo = UndoStack::default();
+        let mut mgr = ProjectManager::new(sample_store().await, &mut undo);
+        mgr.label_filter = vec!["Bug".into()];
+        mgr.fetch_projects().await;
+
+        let sprint = &mgr.projects[0].sprints[1];
+        assert!(sprint.tasks.is_empty());
+        assert_eq!(mgr.count_tasks(&[sprint.sprint_id]), 1);
+    }
+
+    #[tokio::test]
+    async fn refresh_keeps_the_cursor_on_the_same_task() {
+        let store = sample_store().await;
+        let mut undo = UndoStack::default();
+        let mut mgr = ProjectManager::new(store.clone(), &mut undo);
+        mgr.fetch_projects().await;
+        mgr.cursor = ProjectCursor {
+            depth: ProjectCursorDepth::Task,
+            project: Some(0),
+            sprint: Some(1),
+            task: Some(0),
+            item: None,
+        };
+        let task_id = mgr.projects[0].sprints[1].tasks[0].task_id;
+
+        // Another client adds a more urgent task, which sorts before the selected one.
+        let sprint_id = mgr.projects[0].sprints[1].sprint_id;
+        store
+            .create_task(sprint_id, "Urgent", "NotStarted", "", 1, 0, None)
+            .await
+            .unwrap();
+        mgr.refresh(false).await.unwrap();
+
+        assert_eq!(mgr.projects[0].sprints[1].tasks.len(), 2);
+        assert_eq!(mgr.cursor.task, Some(1));
+        assert_eq!(mgr.projects[0].sprints[1].tasks[1].task_id, task_id);
+        assert_eq!(mgr.changed_elsewhere, vec!["Task 'Urgent'".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn refresh_moves_the_cursor_up_when_the_task_is_gone() {
+        let store = sample_store().await;
+        let mut undo = UndoStack::default();
+        let mut mgr = ProjectManager::new(store.clone(), &mut undo);
+        mgr.fetch_projects().await;
+        mgr.cursor = ProjectCursor {
+            depth: ProjectCursorDepth::Task,
+            project: Some(0),
+            sprint: Some(1),
+            task: Some(0),
+            item: None,
+        };
+
+        // Another client deletes the selected task, the only one of its sprint.
+        store
+            .delete_task(mgr.projects[0].sprints[1].tasks[0].task_id)
+            .await
+            .unwrap();
+        mgr.refresh(false).await.unwrap();
+
+        assert!(mgr.projects[0].sprints[1].tasks.is_empty());
+        assert_eq!(mgr.cursor.depth, ProjectCursorDepth::Sprint);
+        assert_eq!((mgr.cursor.sprint, mgr.cursor.task), (Some(1), None));
+    }
+}
@@ -0,0 +1,158 @@
+use crossterm::event::{read, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+
+use crate::{
+    storage::{Storage, StorageError, StorageResult},
+    RecordKind, TrashedRecord,
+};
+
+use super::dialog::prelude::{ConfirmDelete, DisplayWindow};
+
+/// Lists the deleted projects, sprints, tasks and members, and restores or purges them.
+pub struct TrashManager {
+    cursor: usize,
+    trash: Vec<TrashedRecord>,
+}
+
+impl TrashManager {
+    pub async fn run(
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+    ) -> std::io::Result<()> {
+        let mut mgr = TrashManager {
+            cursor: 0,
+            trash: store.fetch_trash().await?,
+        };
+
+        loop {
+            mgr.draw(terminal)?;
+
+            if let Event::Key(key_event) = read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Down if !mgr.trash.is_empty() => {
+                            mgr.cursor = (mgr.cursor + 1) % mgr.trash.len();
+                        }
+                        KeyCode::Up if !mgr.trash.is_empty() => {
+                            mgr.cursor = if mgr.cursor > 0 {
+                                mgr.cursor - 1
+                            } else {
+                                mgr.trash.len() - 1
+                            };
+                        }
+                        KeyCode::Char('r') if !mgr.trash.is_empty() => {
+                            let record = &mgr.trash[mgr.cursor];
+                            if let Err(e) = store.restore_from_trash(record.kind, record.id).await {
+                                DisplayWindow::run(terminal, format!("Failed to restore: {}", e))
+                                    .await?;
+                            }
+                            mgr.refresh(store).await?;
+                        }
+                        KeyCode::Char('p') if !mgr.trash.is_empty() => {
+                            mgr.purge(terminal, store).await?
+                        }
+                        KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    async fn refresh(&mut self, store: &impl Storage) -> std::io::Result<()> {
+        self.trash = store.fetch_trash().await?;
+        self.cursor = self.cursor.min(self.trash.len().saturating_sub(1));
+        Ok(())
+    }
+
+    /// Deletes the selected record for good, along with everything under it.
+    async fn purge(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        store: &impl Storage,
+    ) -> std::io::Result<()> {
+        let record = &self.trash[self.cursor];
+        let report = format!(
+            "{} '{}' and everything under it will be deleted for good. This cannot be undone.",
+            record.kind.label(),
+            record.title
+        );
+        if !ConfirmDelete::run_with_report(terminal, Some(&report)).await {
+            return Ok(());
+        }
+
+        let result: StorageResult<()> = match record.kind {
+            RecordKind::Project => store.delete_project(record.id).await,
+            RecordKind::Sprint => store.delete_sprint(record.id).await,
+            RecordKind::Task => store.delete_task(record.id).await,
+            RecordKind::Member => store.delete_member(record.id).await,
+            RecordKind::ChecklistItem => store.delete_checklist_item(record.id).await,
+            // Comments are deleted right away and never end up in the trash.
+            RecordKind::Comment => Err(StorageError::NotFound),
+        };
+        if let Err(e) = result {
+            DisplayWindow::run(terminal, format!("Failed to purge: {}", e)).await?;
+        }
+
+        self.refresh(store).await
+    }
+
+    fn draw(&self, terminal: &mut Terminal<impl Backend>) -> std::io::Result<()> {
+        terminal.draw(|frame| {
+            let instructions_span = vec![
+                Span::raw("Restore "),
+                Span::styled("<R> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Purge "),
+                Span::styled("<P> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+                Span::raw("Return "),
+                Span::styled("<Esc> ", Style::default().fg(Color::Rgb(255, 165, 0))),
+            ];
+
+            let items: Vec<ListItem> = if self.trash.is_empty() {
+                vec![ListItem::new("The trash is empty.")]
+            } else {
+                self.trash
+                    .iter()
+                    .map(|record| {
+                        ListItem::new(format!(
+                            "{} #{}: {} (deleted {})",
+                            record.kind.label(),
+                            record.id,
+                            record.title,
+                            record.deleted_at.format("%Y-%m-%d %H:%M")
+                        ))
+                    })
+                    .collect()
+            };
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(
+                            Title::from(Line::from(instructions_span))
+                                .alignment(ratatui::layout::Alignment::Center)
+                                .position(ratatui::widgets::block::Position::Bottom),
+                        )
+                        .title("Trash")
+                        .title_alignment(ratatui::layout::Alignment::Center),
+                )
+                .highlight_symbol(">")
+                .highlight_style(Style::default().fg(Color::Yellow));
+
+            let mut list_state = ListState::default();
+            if !self.trash.is_empty() {
+                list_state.select(Some(self.cursor));
+            }
+            frame.render_stateful_widget(list, frame.size(), &mut list_state);
+        })?;
+
+        Ok(())
+    }
+}
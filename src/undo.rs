@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::{
+    models::RecordKind,
+    storage::{Storage, StorageResult},
+};
+
+/// A single change to the stored records, kept so it can be applied again later.
+/// Updates carry the version the record has to be at, as any other update does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    UpdateProject {
+        project_id: i32,
+        version: i32,
+        title: String,
+        description: String,
+    },
+    UpdateSprint {
+        sprint_id: i32,
+        version: i32,
+        title: String,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    },
+    UpdateTask {
+        task_id: i32,
+        version: i32,
+        title: String,
+        status: String,
+        description: String,
+        estimated_hours: i32,
+        priority: i32,
+        due_date: Option<NaiveDate>,
+    },
+    /// Moves a task to another sprint of its project, along with the due date it has there.
+    MoveTask {
+        task_id: i32,
+        version: i32,
+        sprint_id: i32,
+        due_date: Option<NaiveDate>,
+    },
+    SetTaskAssignees {
+        task_id: i32,
+        member_ids: Vec<i32>,
+    },
+    SetTaskLabels {
+        task_id: i32,
+        label_ids: Vec<i32>,
+    },
+    UpdateChecklistItem {
+        item_id: i32,
+        version: i32,
+        title: String,
+        done: bool,
+    },
+    AddTaskDependency {
+        blocker_id: i32,
+        blocked_id: i32,
+    },
+    RemoveTaskDependency {
+        blocker_id: i32,
+        blocked_id: i32,
+    },
+    AddProjectMember {
+        member_id: i32,
+        project_id: i32,
+    },
+    RemoveProjectMember {
+        member_id: i32,
+        project_id: i32,
+    },
+    Trash {
+        kind: RecordKind,
+        id: i32,
+    },
+    Restore {
+        kind: RecordKind,
+        id: i32,
+    },
+}
+
+impl Operation {
+    /// The record an update is about and the version it expects that record to be at.
+    fn version_mut(&mut self) -> Option<((RecordKind, i32), &mut i32)> {
+        match self {
+            Operation::UpdateProject {
+                project_id,
+                version,
+                ..
+            } => Some(((RecordKind::Project, *project_id), version)),
+            Operation::UpdateSprint {
+                sprint_id, version, ..
+            } => Some(((RecordKind::Sprint, *sprint_id), version)),
+            Operation::UpdateTask {
+                task_id, version, ..
+            }
+            | Operation::MoveTask {
+                task_id, version, ..
+            } => Some(((RecordKind::Task, *task_id), version)),
+            Operation::UpdateChecklistItem {
+                item_id, version, ..
+            } => Some(((RecordKind::ChecklistItem, *item_id), version)),
+            _ => None,
+        }
+    }
+}
+
+/// Something the user did, with the operations that take it back and the ones that do it again.
+#[derive(Debug, Clone)]
+pub struct Edit {
+    /// What was done, such as "edit task 'T1'", for messages about undoing and redoing it.
+    pub summary: String,
+    pub undo: Vec<Operation>,
+    pub redo: Vec<Operation>,
+}
+
+impl Edit {
+    /// Records are never created again on redo, as that would give them new IDs: undoing a creation
+    /// moves the record to the trash, and redoing it takes the record back out.
+    pub fn created(summary: String, kind: RecordKind, id: i32) -> Edit {
+        Edit {
+            summary,
+            undo: vec![Operation::Trash { kind, id }],
+            redo: vec![Operation::Restore { kind, id }],
+        }
+    }
+
+    pub fn trashed(summary: String, kind: RecordKind, id: i32) -> Edit {
+        Edit {
+            summary,
+            undo: vec![Operation::Restore { kind, id }],
+            redo: vec![Operation::Trash { kind, id }],
+        }
+    }
+}
+
+/// The edits made during this session, which can be undone and redone in order.
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    done: Vec<Edit>,
+    undone: Vec<Edit>,
+    /// The version each updated record was left at by the latest edit, undo or redo touching it.
+    /// Undoing and redoing expects records to still be there, so only changes made elsewhere conflict.
+    versions: HashMap<(RecordKind, i32), i32>,
+}
+
+impl UndoStack {
+    /// Records an edit that was just made. Anything undone before can no longer be redone.
+    pub fn push(&mut self, mut edit: Edit) {
+        // The operations undoing the edit expect the versions it left its records at.
+        let mut versions = HashMap::new();
+        for (record, version) in edit.undo.iter_mut().filter_map(Operation::version_mut) {
+            versions.entry(record).or_insert(*version);
+        }
+        self.versions.extend(versions);
+        self.done.push(edit);
+        self.undone.clear();
+    }
+
+    /// Points the updates in `operations` at the versions their records were left at, returning the
+    /// versions they leave them at, as every update bumps the version of its record.
+    fn follow_versions(&self, operations: &mut [Operation]) -> HashMap<(RecordKind, i32), i32> {
+        let mut next_versions = HashMap::new();
+        for (record, version) in operations.iter_mut().filter_map(Operation::version_mut) {
+            let next_version = next_versions
+                .entry(record)
+                .or_insert_with(|| self.versions.get(&record).copied().unwrap_or(*version));
+            *version = *next_version;
+            *next_version += 1;
+        }
+        next_versions
+    }
+
+    /// Takes back the latest edit. Returns its summary, or `None` if there is nothing to undo.
+    /// If the store refuses, because the record was purged since for example, the edit is dropped
+    /// along with the error so the edits before it can still be undone.
+    pub async fn undo(&mut self, store: &impl Storage) -> Option<(String, StorageResult<()>)> {
+        let mut edit = self.done.pop()?;
+        let next_versions = self.follow_versions(&mut edit.undo);
+        let result = store.apply(&edit.undo).await;
+        let summary = edit.summary.clone();
+        if result.is_ok() {
+            self.versions.extend(next_versions);
+            self.undone.push(edit);
+        }
+        Some((summary, result))
+    }
+
+    /// Makes the latest undone edit again. Returns its summary, or `None` if there is nothing to redo.
+    /// Like [`UndoStack::undo`], drops the edit if the store refuses.
+    pub async fn redo(&mut self, store: &impl Storage) -> Option<(String, StorageResult<()>)> {
+        let mut edit = self.undone.pop()?;
+        let next_versions = self.follow_versions(&mut edit.redo);
+        let result = store.apply(&edit.redo).await;
+        let summary = edit.summary.clone();
+        if result.is_ok() {
+            self.versions.extend(next_versions);
+            self.done.push(edit);
+        }
+        Some((summary, result))
+    }
+}